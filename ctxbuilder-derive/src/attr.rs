@@ -0,0 +1,148 @@
+use syn::{spanned::Spanned, Attribute, Error, Expr, ExprPath, Field, LitStr, Result};
+
+/// Attributes on the container
+#[derive(Default)]
+pub(crate) struct ContainerAttrs {
+    /// Use field names as entry names
+    pub(crate) named: bool,
+    /// Ignore fields that are not marked otherwise
+    pub(crate) skip: bool,
+}
+
+impl ContainerAttrs {
+    pub(crate) fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut out = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("ctx")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("named") {
+                    out.named = true;
+                    Ok(())
+                } else if meta.path.is_ident("skip") {
+                    out.skip = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown `ctx` attribute"))
+                }
+            })?;
+        }
+        Ok(out)
+    }
+}
+
+/// Attributes on an enum variant
+#[derive(Default)]
+pub(crate) struct VariantAttrs {
+    /// Build this variant when the context doesn't select one
+    pub(crate) default_variant: bool,
+}
+
+impl VariantAttrs {
+    pub(crate) fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut out = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("ctx")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("default_variant") {
+                    out.default_variant = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown `ctx` attribute"))
+                }
+            })?;
+        }
+        Ok(out)
+    }
+}
+
+/// Attributes on a field
+#[derive(Default)]
+pub(crate) struct FieldAttrs {
+    /// Explicit entry name
+    pub(crate) name: Option<LitStr>,
+    /// Use the field name as the entry name
+    pub(crate) named: bool,
+    /// Ignore this field
+    pub(crate) skip: bool,
+    /// Build this field with the context, even if the container skips fields
+    pub(crate) builder: bool,
+    /// Expression to build this field with, bypassing the context
+    pub(crate) default: Option<Expr>,
+    /// Function to build this field with, called with the context
+    pub(crate) with: Option<ExprPath>,
+}
+
+impl FieldAttrs {
+    pub(crate) fn parse(field: &Field) -> Result<Self> {
+        let mut out = Self::default();
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("ctx"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    out.name = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("named") {
+                    out.named = true;
+                    Ok(())
+                } else if meta.path.is_ident("skip") {
+                    out.skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("builder") {
+                    out.builder = true;
+                    Ok(())
+                } else if meta.path.is_ident("default") {
+                    out.default = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("with") {
+                    out.with = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown `ctx` attribute"))
+                }
+            })?;
+        }
+        Ok(out)
+    }
+
+    /// Errors for the attributes of this field that are only supported by `Builder`
+    pub(crate) fn builder_only(&self, field: &Field) -> impl Iterator<Item = Error> + '_ {
+        let span = field.span();
+        [
+            (self.default.is_some(), "default"),
+            (self.with.is_some(), "with"),
+            (self.builder, "builder"),
+        ]
+        .into_iter()
+        .filter(|(used, _)| *used)
+        .map(move |(_, attr)| {
+            Error::new(
+                span,
+                format!("`{attr}` on a field is only supported by `Builder`"),
+            )
+        })
+    }
+
+    /// Whether this field is ignored, given the container attributes
+    pub(crate) fn is_skipped(&self, container: &ContainerAttrs) -> bool {
+        let marked = self.builder
+            || self.named
+            || self.name.is_some()
+            || self.default.is_some()
+            || self.with.is_some();
+        self.skip || (container.skip && !marked)
+    }
+
+    /// Entry name for this field, if any
+    pub(crate) fn entry_name(&self, field: &Field, container: &ContainerAttrs) -> Option<LitStr> {
+        if let Some(name) = &self.name {
+            return Some(name.clone());
+        }
+        match &field.ident {
+            Some(ident) if self.named || container.named => {
+                Some(LitStr::new(&ident.to_string(), ident.span()))
+            }
+            _ => None,
+        }
+    }
+}
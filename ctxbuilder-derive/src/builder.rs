@@ -0,0 +1,176 @@
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{quote, ToTokens};
+use syn::{
+    parse_quote, spanned::Spanned, Data, DeriveInput, Error, Fields, Generics, Ident, LitStr,
+    Result, Type, WherePredicate,
+};
+
+use crate::attr::{ContainerAttrs, FieldAttrs, VariantAttrs};
+
+pub(crate) fn expand(input: DeriveInput) -> Result<TokenStream> {
+    let container = ContainerAttrs::parse(&input.attrs)?;
+    let mut bounds = Bounds::new(&input.generics);
+    let body = match &input.data {
+        Data::Struct(data) => construct(quote! { Self }, &data.fields, &container, &mut bounds)?,
+        Data::Enum(data) => {
+            let mut default = None;
+            let mut arms = Vec::new();
+            for variant in &data.variants {
+                let attrs = VariantAttrs::parse(&variant.attrs)?;
+                if attrs.default_variant && default.replace(&variant.ident).is_some() {
+                    return Err(Error::new(
+                        variant.span(),
+                        "only one variant can be the `default_variant`",
+                    ));
+                }
+                let ident = &variant.ident;
+                let name = LitStr::new(&ident.to_string(), ident.span());
+                let value = construct(
+                    quote! { Self::#ident },
+                    &variant.fields,
+                    &container,
+                    &mut bounds,
+                )?;
+                arms.push(quote! { #name => #value, });
+            }
+            let Some(default) = default.or_else(|| data.variants.first().map(|v| &v.ident)) else {
+                return Err(Error::new(
+                    input.span(),
+                    "`Builder` can't be derived for enums without variants",
+                ));
+            };
+            let default = LitStr::new(&default.to_string(), default.span());
+            // The selected variant is looked up by `Variant<Self>`, which must be `'static`
+            for param in input.generics.type_params() {
+                let ident = &param.ident;
+                bounds.predicates.push(parse_quote! { #ident: 'static });
+            }
+            quote! {
+                let variant = ::ctxbuilder::ContextRead::get::<::ctxbuilder::Variant<Self>>(ctx)
+                    .map(::ctxbuilder::Variant::name)
+                    .unwrap_or(#default);
+                match variant {
+                    #(#arms)*
+                    other => ::core::panic!(
+                        "unknown variant `{}` for `{}`",
+                        other,
+                        ::core::any::type_name::<Self>(),
+                    ),
+                }
+            }
+        }
+        Data::Union(_) => {
+            return Err(Error::new(
+                input.span(),
+                "`Builder` can only be derived for structs and enums",
+            ))
+        }
+    };
+
+    let ident = &input.ident;
+    let mut generics = input.generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .extend(bounds.predicates);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let ctx_param = crate::context_param();
+    Ok(quote! {
+        impl #impl_generics ::ctxbuilder::Builder for #ident #ty_generics #where_clause {
+            fn build<#ctx_param: ::ctxbuilder::Context>(ctx: &mut #ctx_param) -> Self {
+                #body
+            }
+        }
+    })
+}
+
+/// Build the fields of a struct or enum variant, and construct it
+fn construct(
+    path: TokenStream,
+    fields: &Fields,
+    container: &ContainerAttrs,
+    bounds: &mut Bounds,
+) -> Result<TokenStream> {
+    let mut values = Vec::new();
+    for field in fields.iter() {
+        let attrs = FieldAttrs::parse(field)?;
+        if attrs.default.is_some() && attrs.with.is_some() {
+            return Err(Error::new(
+                field.span(),
+                "`default` and `with` can't be used on the same field",
+            ));
+        }
+        if attrs.skip && attrs.builder {
+            return Err(Error::new(
+                field.span(),
+                "`skip` and `builder` can't be used on the same field",
+            ));
+        }
+
+        let ty = &field.ty;
+        values.push(if let Some(default) = &attrs.default {
+            quote! { #default }
+        } else if let Some(with) = &attrs.with {
+            quote! { #with(ctx) }
+        } else if attrs.is_skipped(container) {
+            bounds.add(ty, quote! { ::core::default::Default });
+            quote! { ::core::default::Default::default() }
+        } else {
+            match attrs.entry_name(field, container) {
+                Some(name) => {
+                    bounds.add(ty, quote! { ::ctxbuilder::NamedBuilder });
+                    quote! { ::ctxbuilder::Context::build_named::<#ty, _>(ctx, #name) }
+                }
+                None => {
+                    bounds.add(ty, quote! { ::ctxbuilder::Builder });
+                    quote! { ::ctxbuilder::Context::build::<#ty>(ctx) }
+                }
+            }
+        });
+    }
+
+    Ok(match fields {
+        Fields::Named(fields) => {
+            let idents = fields.named.iter().map(|field| &field.ident);
+            quote! { #path { #(#idents: #values,)* } }
+        }
+        Fields::Unnamed(_) => quote! { #path(#(#values,)*) },
+        Fields::Unit => path,
+    })
+}
+
+/// Bounds to add to the generated impl for the field types that use type parameters
+struct Bounds {
+    params: Vec<Ident>,
+    predicates: Vec<WherePredicate>,
+}
+
+impl Bounds {
+    fn new(generics: &Generics) -> Self {
+        Self {
+            params: generics
+                .type_params()
+                .map(|param| param.ident.clone())
+                .collect(),
+            predicates: Vec::new(),
+        }
+    }
+
+    /// Require `ty: bound` if `ty` uses a type parameter
+    ///
+    /// Fields with concrete types are left alone, as a bound that doesn't hold would fail to
+    /// compile rather than disable the impl.
+    fn add(&mut self, ty: &Type, bound: TokenStream) {
+        if self.uses_params(ty.to_token_stream()) {
+            self.predicates.push(parse_quote! { #ty: #bound });
+        }
+    }
+
+    fn uses_params(&self, tokens: TokenStream) -> bool {
+        tokens.into_iter().any(|token| match token {
+            TokenTree::Ident(ident) => self.params.contains(&ident),
+            TokenTree::Group(group) => self.uses_params(group.stream()),
+            _ => false,
+        })
+    }
+}
@@ -0,0 +1,76 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{spanned::Spanned, Data, DeriveInput, Error, Fields, Result};
+
+use crate::attr::{ContainerAttrs, FieldAttrs};
+
+pub(crate) fn expand(input: DeriveInput) -> Result<TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(
+            input.span(),
+            "`FromContext` can only be derived for structs",
+        ));
+    };
+    let container = ContainerAttrs::parse(&input.attrs)?;
+    if container.skip {
+        return Err(Error::new(
+            input.span(),
+            "`skip` on the struct is only supported by `Builder`",
+        ));
+    }
+
+    let mut values = Vec::new();
+    let mut errors = Vec::new();
+    for field in data.fields.iter() {
+        let attrs = FieldAttrs::parse(field)?;
+        errors.extend(attrs.builder_only(field));
+        let ty = &field.ty;
+        values.push(if attrs.skip {
+            quote! { ::core::default::Default::default() }
+        } else {
+            match attrs.entry_name(field, &container) {
+                Some(name) => quote! {
+                    ::core::clone::Clone::clone(
+                        ctx.get_named::<#ty>(#name)
+                            .ok_or_else(|| ::ctxbuilder::MissingEntry::new::<#ty>(Some(#name)))?,
+                    )
+                },
+                None => quote! {
+                    ::core::clone::Clone::clone(
+                        ctx.get::<#ty>()
+                            .ok_or_else(|| ::ctxbuilder::MissingEntry::new::<#ty>(None))?,
+                    )
+                },
+            }
+        });
+    }
+
+    if let Some(error) = errors.into_iter().reduce(|mut error, other| {
+        error.combine(other);
+        error
+    }) {
+        return Err(error);
+    }
+
+    let body = match &data.fields {
+        Fields::Named(fields) => {
+            let idents = fields.named.iter().map(|field| &field.ident);
+            quote! { Self { #(#idents: #values,)* } }
+        }
+        Fields::Unnamed(_) => quote! { Self(#(#values,)*) },
+        Fields::Unit => quote! { Self },
+    };
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let ctx_param = crate::context_param();
+    Ok(quote! {
+        impl #impl_generics ::ctxbuilder::FromContext for #ident #ty_generics #where_clause {
+            fn from_context<#ctx_param: ::ctxbuilder::ContextRead>(
+                ctx: &#ctx_param,
+            ) -> ::core::result::Result<Self, ::ctxbuilder::MissingEntry> {
+                ::core::result::Result::Ok(#body)
+            }
+        }
+    })
+}
@@ -0,0 +1,58 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{spanned::Spanned, Data, DeriveInput, Error, Index, Member, Result};
+
+use crate::attr::{ContainerAttrs, FieldAttrs};
+
+pub(crate) fn expand(input: DeriveInput) -> Result<TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(
+            input.span(),
+            "`IntoContext` can only be derived for structs",
+        ));
+    };
+    let container = ContainerAttrs::parse(&input.attrs)?;
+    if container.skip {
+        return Err(Error::new(
+            input.span(),
+            "`skip` on the struct is only supported by `Builder`",
+        ));
+    }
+
+    let mut inserts = Vec::new();
+    let mut errors = Vec::new();
+    for (i, field) in data.fields.iter().enumerate() {
+        let attrs = FieldAttrs::parse(field)?;
+        errors.extend(attrs.builder_only(field));
+        if attrs.skip {
+            continue;
+        }
+
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(i)),
+        };
+        inserts.push(match attrs.entry_name(field, &container) {
+            Some(name) => quote! { ctx.insert_named(#name, self.#member); },
+            None => quote! { ctx.insert(self.#member); },
+        });
+    }
+
+    if let Some(error) = errors.into_iter().reduce(|mut error, other| {
+        error.combine(other);
+        error
+    }) {
+        return Err(error);
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let ctx_param = crate::context_param();
+    Ok(quote! {
+        impl #impl_generics ::ctxbuilder::preset::Preset for #ident #ty_generics #where_clause {
+            fn apply<#ctx_param: ::ctxbuilder::Context>(self, ctx: &mut #ctx_param) {
+                #(#inserts)*
+            }
+        }
+    })
+}
@@ -0,0 +1,95 @@
+//! Derive macros for [`ctxbuilder`](https://docs.rs/ctxbuilder)
+//!
+//! These macros are re-exported by `ctxbuilder` when its `derive` feature is enabled.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use syn::{parse_macro_input, DeriveInput, Ident};
+
+mod attr;
+mod builder;
+mod from_context;
+mod into_context;
+mod newtype;
+
+/// Type parameter for the context in generated methods, that can't collide with the type
+/// parameters of the derived type
+fn context_param() -> Ident {
+    Ident::new("__CtxbuilderCtx", Span::call_site())
+}
+
+/// Derive `IntoContext` to insert every field of a struct into a context
+///
+/// This generates an implementation of
+/// [`Preset`](https://docs.rs/ctxbuilder/latest/ctxbuilder/preset/trait.Preset.html), so the
+/// struct can be applied to a context with `Context::apply`.
+///
+/// Fields are inserted by type. Use `#[ctx(name = "...")]` on a field to insert it by name
+/// instead, `#[ctx(named)]` to use the field name, or `#[ctx(skip)]` to ignore it. Putting
+/// `#[ctx(named)]` on the struct inserts all its fields by their field names. The `default`,
+/// `with` and `builder` attributes of `Builder` can't be used.
+#[proc_macro_derive(IntoContext, attributes(ctx))]
+pub fn derive_into_context(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    into_context::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive `FromContext` to extract every field of a struct from a context
+///
+/// Fields are cloned from the entries with the same type. Use `#[ctx(name = "...")]` on a field
+/// to look it up by name instead, `#[ctx(named)]` to use the field name, or `#[ctx(skip)]` to
+/// use its default value. Putting `#[ctx(named)]` on the struct looks up all its fields by their
+/// field names. The `default`, `with` and `builder` attributes of `Builder` can't be used.
+#[proc_macro_derive(FromContext, attributes(ctx))]
+pub fn derive_from_context(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    from_context::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive `Builder` to build every field of a struct or enum variant with the context
+///
+/// Fields are built with their own `Builder` implementation. Use `#[ctx(name = "...")]` on a
+/// field to build it with its `NamedBuilder` implementation instead, or `#[ctx(named)]` to use
+/// the field name. Putting `#[ctx(named)]` on the struct builds all its fields by their field
+/// names.
+///
+/// Use `#[ctx(default = "expr")]` to set a field to an expression without using the context,
+/// `#[ctx(with = "path::to_fn")]` to build it with a function that receives the context, such as
+/// `fn(&mut C) -> T` with `C: Context`, or `#[ctx(skip)]` to use its default value.
+///
+/// For structs where only a few fields matter to fixtures, put `#[ctx(skip)]` on the struct: fields
+/// then use their default value, unless they are marked with `#[ctx(builder)]` or one of the
+/// attributes above.
+///
+/// For enums, the variant named by the `Variant<Self>` in the context is built, or the variant
+/// marked with `#[ctx(default_variant)]` if there is none, or else the first variant. The fields of
+/// the variant are built like the fields of a struct.
+///
+/// For generic types, the impl requires the types of the fields that use type parameters to
+/// implement `Builder`, `NamedBuilder` or `Default`, depending on how they are built.
+#[proc_macro_derive(Builder, attributes(ctx))]
+pub fn derive_builder(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    builder::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive `Builder` and `NamedBuilder` for a newtype, such as `struct OrderId(Uuid)`
+///
+/// The inner value is built with its `NamedBuilder` implementation, using the path of the newtype
+/// as the entry name, such as `"my_crate::ids::OrderId"`, so that newtypes wrapping the same type
+/// get their own values: `OrderId` and `CustomerId` don't share the `Uuid` they wrap. Named builds
+/// of the newtype are namespaced the same way, with the name appended after a dot, such as
+/// `"my_crate::ids::OrderId.buyer"`.
+#[proc_macro_derive(DefaultBuilder)]
+pub fn derive_default_builder(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    newtype::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
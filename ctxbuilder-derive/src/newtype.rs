@@ -0,0 +1,65 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, spanned::Spanned, Data, DeriveInput, Error, LitStr, Result};
+
+pub(crate) fn expand(input: DeriveInput) -> Result<TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) if data.fields.len() == 1 => &data.fields,
+        _ => {
+            return Err(Error::new(
+                input.span(),
+                "`DefaultBuilder` can only be derived for structs with a single field",
+            ))
+        }
+    };
+    let field = fields.iter().next().expect("single field");
+    let inner = &field.ty;
+    let wrap = |value: TokenStream| match &field.ident {
+        Some(ident) => quote! { Self { #ident: #value } },
+        None => quote! { Self(#value) },
+    };
+
+    let ident = &input.ident;
+    // Entries are named after the path of the newtype, so that newtypes with the same name in
+    // different modules don't share them
+    let path = LitStr::new(&format!("::{ident}"), ident.span());
+    let namespace = quote! { ::core::concat!(::core::module_path!(), #path) };
+    let build = wrap(quote! { ::ctxbuilder::Context::build_named::<#inner, _>(ctx, #namespace) });
+    let build_with_name = wrap(quote! {
+        ::ctxbuilder::Context::build_named::<#inner, _>(
+            ctx,
+            ::ctxbuilder::__namespaced(#namespace, name),
+        )
+    });
+
+    let mut builder_generics = input.generics.clone();
+    builder_generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote! { #inner: ::ctxbuilder::NamedBuilder });
+    let (impl_generics, ty_generics, where_clause) = builder_generics.split_for_impl();
+    let ctx_param = crate::context_param();
+    let builder = quote! {
+        impl #impl_generics ::ctxbuilder::Builder for #ident #ty_generics #where_clause {
+            fn build<#ctx_param: ::ctxbuilder::Context>(ctx: &mut #ctx_param) -> Self {
+                #build
+            }
+        }
+    };
+
+    let named_builder = quote! {
+        impl #impl_generics ::ctxbuilder::NamedBuilder for #ident #ty_generics #where_clause {
+            fn build_with_name<#ctx_param: ::ctxbuilder::Context>(
+                ctx: &mut #ctx_param,
+                name: &'static str,
+            ) -> Self {
+                #build_with_name
+            }
+        }
+    };
+
+    Ok(quote! {
+        #builder
+        #named_builder
+    })
+}
@@ -0,0 +1,201 @@
+use alloc::{sync::Arc, vec::Vec};
+use core::any::TypeId;
+
+use crate::{AnyMap, Key, KeySet};
+
+/// Layers of a context, from the one lookups check first to the one they check last
+///
+/// Returned by [`ContextRead::ancestors`](crate::ContextRead::ancestors). This is useful to find
+/// out which layer a value is resolved from, for example when a sub-context or a fork shadows an
+/// entry unexpectedly:
+///
+/// ```
+/// # use ctxbuilder::{Context, ContextRead, ContextWrite, LayerKind};
+/// let ctx = ctxbuilder::ctx().with(1u32).with(true);
+/// let mut sub = ctx.sub();
+/// sub.insert(2u32);
+///
+/// let (_, layer) = sub.ancestors().resolve::<u32>().unwrap();
+/// assert_eq!(layer.kind(), LayerKind::Local);
+/// let (depth, layer) = sub.ancestors().resolve::<bool>().unwrap();
+/// assert_eq!((depth, layer.kind()), (1, LayerKind::Parent));
+/// ```
+#[derive(Clone)]
+pub struct Ancestors<'c> {
+    layers: alloc::vec::IntoIter<Layer<'c>>,
+}
+
+/// Single layer of entries in a context
+#[derive(Clone, Copy)]
+pub struct Layer<'c> {
+    kind: LayerKind,
+    map: &'c AnyMap,
+}
+
+/// Where the entries of a [`Layer`] come from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LayerKind {
+    /// Entries inserted in the context itself
+    Local,
+    /// Entries inserted in a parent context, such as the one of a
+    /// [`SubContext`](crate::SubContext) or a [`LayeredContext`](crate::LayeredContext)
+    Parent,
+    /// Entries shared with forked contexts, see [`MainContext::fork`](crate::MainContext::fork)
+    Shared,
+}
+
+impl<'c> Ancestors<'c> {
+    pub(crate) fn new(layers: Vec<Layer<'c>>) -> Self {
+        Self {
+            layers: layers.into_iter(),
+        }
+    }
+
+    /// Find the layer that an object is resolved from, by its type
+    ///
+    /// This returns the position of the layer and the layer itself, or `None` if the object is
+    /// missing or was removed from the context.
+    pub fn resolve<T: 'static>(&self) -> Option<(usize, Layer<'c>)> {
+        self.resolve_key(&(TypeId::of::<T>(), None))
+    }
+
+    /// Find the layer that an object is resolved from, by its name and type
+    ///
+    /// See [`resolve`](Self::resolve).
+    pub fn resolve_named<T: 'static>(&self, name: &'static str) -> Option<(usize, Layer<'c>)> {
+        self.resolve_key(&(TypeId::of::<T>(), Some(name)))
+    }
+
+    /// Get the visible named objects of type `T` across all layers, sorted by name
+    pub(crate) fn named<T: 'static>(self) -> Vec<(&'static str, &'c T)> {
+        let mut seen = KeySet::default();
+        let mut values = Vec::new();
+        for layer in self {
+            for (key, val) in layer.map {
+                let Some(name) = key.1.filter(|_| key.0 == TypeId::of::<T>()) else {
+                    continue;
+                };
+                // Only the newest layer of an entry is visible
+                if seen.insert(*key) {
+                    values.extend(val.downcast_ref().map(|val| (name, val)));
+                }
+            }
+        }
+        values.sort_unstable_by_key(|(name, _)| *name);
+        values
+    }
+
+    pub(crate) fn resolve_key(&self, key: &Key) -> Option<(usize, Layer<'c>)> {
+        let (depth, layer, val) = self
+            .layers
+            .as_slice()
+            .iter()
+            .enumerate()
+            .find_map(|(depth, layer)| layer.map.get(key).map(|val| (depth, *layer, val)))?;
+        // A tombstone hides the entries of the older layers
+        (!val.is_removed()).then_some((depth, layer))
+    }
+}
+
+impl<'c> Iterator for Ancestors<'c> {
+    type Item = Layer<'c>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.layers.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.layers.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Ancestors<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.layers.next_back()
+    }
+}
+
+impl ExactSizeIterator for Ancestors<'_> {}
+
+impl<'c> Layer<'c> {
+    pub(crate) fn new(kind: LayerKind, map: &'c AnyMap) -> Self {
+        Self { kind, map }
+    }
+
+    /// Layers of a map and the shared maps below it
+    pub(crate) fn with_shared(
+        kind: LayerKind,
+        map: &'c AnyMap,
+        shared: &'c [Arc<AnyMap>],
+    ) -> impl Iterator<Item = Self> + 'c {
+        core::iter::once(Self::new(kind, map)).chain(
+            shared
+                .iter()
+                .rev()
+                .map(|map| Self::new(LayerKind::Shared, map)),
+        )
+    }
+
+    /// Where the entries of this layer come from
+    pub fn kind(&self) -> LayerKind {
+        self.kind
+    }
+
+    /// Number of entries in this layer, not counting removed ones
+    pub fn len(&self) -> usize {
+        self.map.values().filter(|val| !val.is_removed()).count()
+    }
+
+    /// Whether this layer has no entries
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether this layer holds an object of this type
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.get::<T>().is_some()
+    }
+
+    /// Whether this layer holds an object of this name and type
+    pub fn contains_named<T: 'static>(&self, name: &'static str) -> bool {
+        self.get_named::<T>(name).is_some()
+    }
+
+    /// Get an object of this layer by its type
+    ///
+    /// Unlike [`ContextRead::get`](crate::ContextRead::get), this doesn't mark the entry as read.
+    pub fn get<T: 'static>(&self) -> Option<&'c T> {
+        self.map.get(&(TypeId::of::<T>(), None))?.peek()
+    }
+
+    /// Get an object of this layer by its name and type
+    ///
+    /// Unlike [`ContextRead::get_named`](crate::ContextRead::get_named), this doesn't mark the
+    /// entry as read.
+    pub fn get_named<T: 'static>(&self, name: &'static str) -> Option<&'c T> {
+        self.map.get(&(TypeId::of::<T>(), Some(name)))?.peek()
+    }
+
+    /// Return the type and name of the entries in this layer
+    ///
+    /// Entries are sorted by type name, then by name.
+    pub fn entry_keys(&self) -> Vec<(&'static str, Option<&'static str>)> {
+        let mut keys: Vec<_> = self
+            .map
+            .iter()
+            .filter(|(_, val)| !val.is_removed())
+            .map(|(key, val)| (val.type_name(), key.1))
+            .collect();
+        keys.sort_unstable();
+        keys
+    }
+}
+
+impl core::fmt::Debug for Layer<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Layer")
+            .field("kind", &self.kind)
+            .field("entries", &self.entry_keys())
+            .finish()
+    }
+}
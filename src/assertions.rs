@@ -0,0 +1,109 @@
+//! Assertions on the entries of a context
+//!
+//! These produce better failure messages than `assert_eq!(ctx.get::<T>(), Some(&x))`: when an
+//! entry is missing, the message lists the entries with the same type or the same name.
+//!
+//! ```
+//! use ctxbuilder::{assertions::*, Context};
+//!
+//! let ctx = ctxbuilder::ctx().with(42u32).with_named("owner", "alice");
+//! assert_contains::<u32>(&ctx);
+//! assert_entry_eq(&ctx, "owner", &"alice");
+//! ```
+
+use alloc::{format, string::String, vec::Vec};
+use core::{any::type_name, fmt::Debug};
+
+use crate::ContextRead;
+
+/// Assert that the context contains an entry of type `T`
+#[track_caller]
+pub fn assert_contains<T: Send + Sync + 'static>(ctx: &impl ContextRead) {
+    if ctx.get::<T>().is_none() {
+        panic!("{}", missing::<T>(ctx, None));
+    }
+}
+
+/// Assert that the context contains a named entry of type `T`
+#[track_caller]
+pub fn assert_contains_named<T: Send + Sync + 'static>(ctx: &impl ContextRead, name: &'static str) {
+    if ctx.get_named::<T>(name).is_none() {
+        panic!("{}", missing::<T>(ctx, Some(name)));
+    }
+}
+
+/// Assert that the entry of type `T` is equal to `expected`
+#[track_caller]
+pub fn assert_value_eq<T: PartialEq + Debug + Send + Sync + 'static>(
+    ctx: &impl ContextRead,
+    expected: &T,
+) {
+    match ctx.get::<T>() {
+        Some(val) if val == expected => {}
+        Some(val) => panic!(
+            "context entry of type `{}` differs\n  expected: {expected:?}\n     found: {val:?}",
+            type_name::<T>()
+        ),
+        None => panic!("{}", missing::<T>(ctx, None)),
+    }
+}
+
+/// Assert that the named entry of type `T` is equal to `expected`
+#[track_caller]
+pub fn assert_entry_eq<T: PartialEq + Debug + Send + Sync + 'static>(
+    ctx: &impl ContextRead,
+    name: &'static str,
+    expected: &T,
+) {
+    match ctx.get_named::<T>(name) {
+        Some(val) if val == expected => {}
+        Some(val) => panic!(
+            "context entry `{name}` of type `{}` differs\n  expected: {expected:?}\n     found: {val:?}",
+            type_name::<T>()
+        ),
+        None => panic!("{}", missing::<T>(ctx, Some(name))),
+    }
+}
+
+/// Describe a missing entry, with the entries that have the same type or name
+fn missing<T>(ctx: &impl ContextRead, name: Option<&'static str>) -> String {
+    let type_name = type_name::<T>();
+    let keys = ctx.entry_keys();
+
+    let mut out = match name {
+        Some(name) => format!("missing context entry `{name}` of type `{type_name}`"),
+        None => format!("missing context entry of type `{type_name}`"),
+    };
+
+    let mut same_type: Vec<_> = keys
+        .iter()
+        .filter(|(t, _)| *t == type_name)
+        .map(|(_, n)| match n {
+            Some(n) => format!("`{n}`"),
+            None => String::from("(unnamed)"),
+        })
+        .collect();
+    same_type.sort_unstable();
+    if !same_type.is_empty() {
+        out.push_str(&format!(
+            "\n  entries of type `{type_name}`: {}",
+            same_type.join(", ")
+        ));
+    }
+
+    let mut same_name: Vec<_> = keys
+        .iter()
+        .filter(|(_, n)| *n == name)
+        .map(|(t, _)| format!("`{t}`"))
+        .collect();
+    same_name.sort_unstable();
+    if !same_name.is_empty() {
+        let label = match name {
+            Some(name) => format!("entries named `{name}`"),
+            None => String::from("unnamed entries"),
+        };
+        out.push_str(&format!("\n  {label}: {}", same_name.join(", ")));
+    }
+
+    out
+}
@@ -0,0 +1,35 @@
+use std::sync::Mutex;
+
+use crate::ContextRead;
+
+/// Sources of values that are not derived from the seeded random number generator or the
+/// [`Clock`](crate::Clock) of a context
+///
+/// Stored in contexts created with
+/// [`MainContext::audit_determinism`](crate::MainContext::audit_determinism).
+#[derive(Default)]
+pub(crate) struct Audit {
+    sources: Mutex<Vec<&'static str>>,
+}
+
+impl Audit {
+    pub(crate) fn sources(&self) -> Vec<&'static str> {
+        self.sources
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+}
+
+/// Record that a value was generated from a non-deterministic source, if the context is audited
+pub(crate) fn record<C: ContextRead + ?Sized>(ctx: &C, source: &'static str) {
+    if let Some(audit) = ctx.get::<Audit>() {
+        let mut sources = audit
+            .sources
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if !sources.contains(&source) {
+            sources.push(source);
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//! Builders for [`aws_lambda_events`](::aws_lambda_events) payloads
+//!
+//! All payloads built from the same context share the same [`AccountId`], [`Region`] and resource
+//! names, so ARNs and request IDs line up across events without building them by hand.
+
+use ::aws_lambda_events::{
+    apigw::ApiGatewayProxyRequest,
+    dynamodb::{self, EventRecord, StreamRecord, StreamViewType},
+    http::Method,
+    sqs::{SqsEvent, SqsMessage},
+};
+use uuid::Uuid;
+
+use crate::{Builder, Context};
+
+/// Name of the [`Uuid`] entry used as the API Gateway request ID
+pub const REQUEST_ID: &str = "aws_request_id";
+
+/// AWS account ID shared by all payloads
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccountId(pub String);
+
+impl Builder for AccountId {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        ctx.entry::<Self>()
+            .or_insert_with(|| AccountId("123456789012".to_string()))
+            .clone()
+    }
+}
+
+/// AWS region shared by all payloads
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Region(pub String);
+
+impl Builder for Region {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        ctx.entry::<Self>()
+            .or_insert_with(|| Region("us-east-1".to_string()))
+            .clone()
+    }
+}
+
+/// Name of the SQS queue that [`SqsMessage`]s are received from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueueName(pub String);
+
+impl Builder for QueueName {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        ctx.entry::<Self>()
+            .or_insert_with(|| QueueName("queue".to_string()))
+            .clone()
+    }
+}
+
+/// Name of the DynamoDB table that stream [`EventRecord`]s originate from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableName(pub String);
+
+impl Builder for TableName {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        ctx.entry::<Self>()
+            .or_insert_with(|| TableName("table".to_string()))
+            .clone()
+    }
+}
+
+/// Build the ARN of the SQS queue in the context
+pub fn queue_arn<C: Context>(ctx: &mut C) -> String {
+    let Region(region) = ctx.build();
+    let AccountId(account_id) = ctx.build();
+    let QueueName(queue) = ctx.build();
+    format!("arn:aws:sqs:{region}:{account_id}:{queue}")
+}
+
+/// Build the ARN of the DynamoDB table in the context
+pub fn table_arn<C: Context>(ctx: &mut C) -> String {
+    let Region(region) = ctx.build();
+    let AccountId(account_id) = ctx.build();
+    let TableName(table) = ctx.build();
+    format!("arn:aws:dynamodb:{region}:{account_id}:table/{table}")
+}
+
+impl Builder for ApiGatewayProxyRequest {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        let mut req = ApiGatewayProxyRequest::default();
+        req.http_method = ctx.entry::<Method>().or_insert(Method::GET).clone();
+        req.path = Some(
+            ctx.entry_named::<String>("path")
+                .or_insert_with(|| "/".to_string())
+                .clone(),
+        );
+        req.body = ctx.get_named::<String>("body").cloned();
+        req.request_context.http_method = req.http_method.clone();
+        req.request_context.path = req.path.clone();
+        req.request_context.account_id = Some(AccountId::build(ctx).0);
+        req.request_context.request_id = Some(ctx.build_named::<Uuid, _>(REQUEST_ID).to_string());
+        req
+    }
+}
+
+impl Builder for SqsMessage {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        let mut msg = SqsMessage::default();
+        msg.message_id = Some(ctx.rng().next_uuid().to_string());
+        msg.receipt_handle = Some(ctx.rng().next_uuid().to_string());
+        msg.body = ctx.get_named::<String>("body").cloned();
+        msg.event_source = Some("aws:sqs".to_string());
+        msg.event_source_arn = Some(queue_arn(ctx));
+        msg.aws_region = Some(Region::build(ctx).0);
+        msg
+    }
+}
+
+impl Builder for SqsEvent {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        let mut event = SqsEvent::default();
+        event.records = vec![SqsMessage::build(ctx)];
+        event
+    }
+}
+
+impl Builder for EventRecord {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        let mut change = StreamRecord::default();
+        change.stream_view_type = Some(StreamViewType::NewAndOldImages);
+
+        let mut record = EventRecord::default();
+        record.aws_region = Region::build(ctx).0;
+        record.change = change;
+        record.event_id = ctx.rng().next_uuid().to_string();
+        record.event_name = "INSERT".to_string();
+        record.event_source = Some("aws:dynamodb".to_string());
+        record.event_version = Some("1.1".to_string());
+        record.event_source_arn =
+            Some(format!("{}/stream/1970-01-01T00:00:00.000", table_arn(ctx)));
+        record.table_name = Some(TableName::build(ctx).0);
+        record
+    }
+}
+
+impl Builder for dynamodb::Event {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        let mut event = dynamodb::Event::default();
+        event.records = vec![EventRecord::build(ctx)];
+        event
+    }
+}
@@ -0,0 +1,132 @@
+//! Helpers to build [`axum`](::axum) requests and state from a [`Context`]
+//!
+//! The request parts ([`Method`], [`Uri`], [`HeaderMap`] and the body as [`Bytes`]) are read
+//! from the context when present, so a single fixture context can describe both the request and
+//! the shared [`State`] a handler runs with.
+
+use ::axum::{
+    body::{Body, Bytes},
+    extract::State,
+    http::{HeaderMap, Method, Request, Uri},
+};
+use tower::{Service, ServiceExt};
+
+use crate::{Builder, Context, NamedBuilder};
+
+impl Builder for Method {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        ctx.entry::<Self>().or_insert(Method::GET).clone()
+    }
+}
+
+impl NamedBuilder for Method {
+    fn build_with_name<C: Context>(ctx: &mut C, name: &'static str) -> Self {
+        ctx.entry_named::<Self>(name).or_insert(Method::GET).clone()
+    }
+}
+
+/// Build the URI of requests
+///
+/// This defaults to `/`, or to the URI of the mock server in the context with the `wiremock`
+/// feature.
+impl Builder for Uri {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        if let Some(uri) = ctx.get::<Self>() {
+            return uri.clone();
+        }
+        let uri = default_uri(ctx, None);
+        ctx.entry::<Self>().or_insert(uri).clone()
+    }
+}
+
+impl NamedBuilder for Uri {
+    fn build_with_name<C: Context>(ctx: &mut C, name: &'static str) -> Self {
+        if let Some(uri) = ctx.get_named::<Self>(name) {
+            return uri.clone();
+        }
+        let uri = default_uri(ctx, Some(name));
+        ctx.entry_named::<Self>(name).or_insert(uri).clone()
+    }
+}
+
+#[cfg_attr(not(feature = "wiremock"), allow(unused_variables))]
+fn default_uri<C: Context>(ctx: &C, name: Option<&'static str>) -> Uri {
+    #[cfg(feature = "wiremock")]
+    if let Some(uri) = crate::wiremock::mock_server_uri(ctx, name) {
+        return format!("{uri}/")
+            .parse()
+            .expect("mock server URIs are valid");
+    }
+    Uri::from_static("/")
+}
+
+impl Builder for HeaderMap {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        ctx.entry::<Self>().or_default().clone()
+    }
+}
+
+impl NamedBuilder for HeaderMap {
+    fn build_with_name<C: Context>(ctx: &mut C, name: &'static str) -> Self {
+        ctx.entry_named::<Self>(name).or_default().clone()
+    }
+}
+
+// With the `bytes` feature, bodies can also be generated from a `PayloadSpec`
+#[cfg(not(feature = "bytes"))]
+impl Builder for Bytes {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        ctx.entry::<Self>().or_default().clone()
+    }
+}
+
+#[cfg(not(feature = "bytes"))]
+impl NamedBuilder for Bytes {
+    fn build_with_name<C: Context>(ctx: &mut C, name: &'static str) -> Self {
+        ctx.entry_named::<Self>(name).or_default().clone()
+    }
+}
+
+impl Builder for Request<Body> {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        let mut req = Request::new(Body::from(Bytes::build(ctx)));
+        *req.method_mut() = Method::build(ctx);
+        *req.uri_mut() = Uri::build(ctx);
+        *req.headers_mut() = HeaderMap::build(ctx);
+        req
+    }
+}
+
+impl<S: Builder + Clone + Send + Sync + 'static> Builder for State<S> {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        // The state is stored in the context, so that every `State` built from the same context
+        // shares the same value.
+        if let Some(state) = ctx.get::<S>() {
+            return State(state.clone());
+        }
+
+        let state = S::build(ctx);
+        ctx.insert(state.clone());
+        State(state)
+    }
+}
+
+/// Build a request and the shared [`State`] from the same context
+pub fn request_with_state<S, C>(ctx: &mut C) -> (Request<Body>, State<S>)
+where
+    S: Builder + Clone + Send + Sync + 'static,
+    C: Context,
+{
+    let state = State::build(ctx);
+    (Request::build(ctx), state)
+}
+
+/// Build a request from the context and send it to a [`tower::Service`], such as an
+/// [`axum::Router`](::axum::Router)
+pub async fn call<S, C>(ctx: &mut C, service: S) -> Result<S::Response, S::Error>
+where
+    S: Service<Request<Body>>,
+    C: Context,
+{
+    service.oneshot(Request::build(ctx)).await
+}
@@ -0,0 +1,159 @@
+use alloc::{string::String, vec::Vec};
+use core::{any::type_name, fmt};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{intern::intern, value::Stored, DynContext, HashMap, MainContext};
+
+/// Version of the binary snapshot format, written at the start of each snapshot
+const FORMAT_VERSION: u32 = 1;
+
+/// Entries of a snapshot: version, and the type name, name and encoded value of each entry
+type Snapshot<'a> = (u32, Vec<(&'a str, Option<&'a str>, &'a [u8])>);
+
+/// Encoding of a type registered with [`MainContext::register_binary`]
+#[derive(Clone, Copy)]
+pub(crate) struct Codec {
+    type_name: &'static str,
+    encode: fn(&Stored) -> Option<postcard::Result<Vec<u8>>>,
+    decode: fn(&[u8]) -> postcard::Result<Stored>,
+}
+
+impl Codec {
+    pub(crate) fn of<T: Serialize + DeserializeOwned + Send + Sync + 'static>() -> Self {
+        Self {
+            type_name: type_name::<T>(),
+            encode: |stored| stored.peek::<T>().map(postcard::to_allocvec),
+            decode: |bytes| postcard::from_bytes::<T>(bytes).map(Stored::new),
+        }
+    }
+}
+
+impl MainContext {
+    /// Register `T` as serializable, so that [binary snapshots](Self::to_binary) include its
+    /// values
+    ///
+    /// Types must be registered on both the context that writes a snapshot and the one that
+    /// loads it.
+    pub fn register_binary<T: Serialize + DeserializeOwned + Send + Sync + 'static>(&mut self) {
+        self.registry.register_binary::<T>();
+    }
+
+    /// Write the entries of this context into a compact binary snapshot
+    ///
+    /// This is meant for large generated datasets, such as benchmark inputs, that are too slow to
+    /// generate for every run. Only the entries whose type was registered with
+    /// [`register_binary`](Self::register_binary) are included, sorted by type name and then by
+    /// name. Values are encoded with [`postcard`], and tagged with their type name.
+    ///
+    /// ```
+    /// # use ctxbuilder::{ContextRead, ContextWrite, MainContext};
+    /// let mut ctx = MainContext::new();
+    /// ctx.register_binary::<Vec<u64>>();
+    /// ctx.insert_named("rows", (0..100_000u64).collect::<Vec<_>>());
+    /// let bytes = ctx.to_binary().unwrap();
+    ///
+    /// let mut loaded = MainContext::new();
+    /// loaded.register_binary::<Vec<u64>>();
+    /// assert_eq!(loaded.load_binary(&bytes).unwrap(), 1);
+    /// assert_eq!(loaded.get_named::<Vec<u64>>("rows").unwrap().len(), 100_000);
+    /// ```
+    pub fn to_binary(&self) -> Result<Vec<u8>, BinaryError> {
+        let mut entries = Vec::new();
+        for (key, val) in self.entries() {
+            let Some(codec) = self.registry.get(key.0).binary else {
+                continue;
+            };
+            if let Some(bytes) = (codec.encode)(val) {
+                entries.push((
+                    codec.type_name,
+                    key.1,
+                    bytes.map_err(BinaryError::Encoding)?,
+                ));
+            }
+        }
+        entries.sort_unstable_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+        let snapshot: Snapshot<'_> = (
+            FORMAT_VERSION,
+            entries
+                .iter()
+                .map(|(type_name, name, bytes)| (*type_name, *name, bytes.as_slice()))
+                .collect(),
+        );
+        postcard::to_allocvec(&snapshot).map_err(BinaryError::Encoding)
+    }
+
+    /// Insert the entries of a binary snapshot written by [`to_binary`](Self::to_binary),
+    /// returning the number of entries
+    ///
+    /// Names of the entries are leaked to get `&'static str`s, like when
+    /// [replaying](crate::Replayer::replay) a [`Recipe`](crate::Recipe). Nothing is inserted if
+    /// the snapshot can't be loaded.
+    pub fn load_binary(&mut self, bytes: &[u8]) -> Result<usize, BinaryError> {
+        // Check the version first, as the rest of a snapshot in another format may not decode
+        let (version, rest) =
+            postcard::take_from_bytes::<u32>(bytes).map_err(BinaryError::Encoding)?;
+        if version != FORMAT_VERSION {
+            return Err(BinaryError::UnsupportedVersion(version));
+        }
+        let entries: Vec<(&str, Option<&str>, &[u8])> =
+            postcard::from_bytes(rest).map_err(BinaryError::Encoding)?;
+
+        let codecs: HashMap<&str, _> = self
+            .registry
+            .iter()
+            .filter_map(|(type_id, meta)| Some((meta.binary?.type_name, (*type_id, meta.binary?))))
+            .collect();
+        let mut values = Vec::with_capacity(entries.len());
+        for (type_name, name, bytes) in entries {
+            let (type_id, codec) = codecs
+                .get(type_name)
+                .ok_or_else(|| BinaryError::UnknownType(type_name.into()))?;
+            let val = (codec.decode)(bytes).map_err(BinaryError::Encoding)?;
+            values.push(((*type_id, name.map(intern)), val));
+        }
+
+        let count = values.len();
+        for (key, val) in values {
+            self.insert_stored(key, val);
+        }
+        Ok(count)
+    }
+}
+
+/// Error returned when a binary snapshot can't be written or loaded
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum BinaryError {
+    /// A value or the snapshot itself can't be encoded or decoded
+    Encoding(postcard::Error),
+    /// The snapshot was written with a format that this version of the crate can't read
+    UnsupportedVersion(u32),
+    /// The snapshot holds a type that is not registered with
+    /// [`MainContext::register_binary`]
+    UnknownType(String),
+}
+
+impl fmt::Display for BinaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Encoding(err) => write!(f, "invalid binary snapshot: {err}"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported binary snapshot format version {version}, expected {FORMAT_VERSION}"
+            ),
+            Self::UnknownType(type_name) => {
+                write!(f, "no binary encoding registered for `{type_name}`")
+            }
+        }
+    }
+}
+
+impl core::error::Error for BinaryError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Encoding(err) => Some(err),
+            _ => None,
+        }
+    }
+}
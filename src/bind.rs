@@ -0,0 +1,39 @@
+use alloc::{boxed::Box, sync::Arc};
+use core::any::{type_name, TypeId};
+
+use crate::{
+    dynamic::{DynContext, DynRef},
+    value::Stored,
+    Builder, Context,
+};
+
+type BuildFn<I> = dyn Fn(&mut DynRef<'_>) -> Box<I> + Send + Sync;
+
+/// Implementation bound to the trait object `I`, see [`Context::bind`]
+pub(crate) struct Binding<I: ?Sized>(Arc<BuildFn<I>>);
+
+impl<I: ?Sized + 'static> Binding<I> {
+    pub(crate) fn new<T: Builder + 'static>(coerce: fn(Box<T>) -> Box<I>) -> Self {
+        Self(Arc::new(move |ctx| coerce(Box::new(ctx.build::<T>()))))
+    }
+}
+
+/// Build the implementation bound to `I`
+pub(crate) fn build<I: ?Sized + 'static>(ctx: &mut dyn DynContext) -> Box<I> {
+    let binding = ctx
+        .get_stored(&(TypeId::of::<Binding<I>>(), None))
+        .and_then(Stored::downcast_ref::<Binding<I>>)
+        .map(|binding| binding.0.clone())
+        .unwrap_or_else(|| panic!("no implementation bound for `{}`", type_name::<I>()));
+    binding(&mut DynRef(ctx))
+}
+
+/// Get the type-erased view of a context to build bound implementations
+pub(crate) fn dyn_context<C: Context>(ctx: &mut C) -> &mut dyn DynContext {
+    ctx.as_dyn().unwrap_or_else(|| {
+        panic!(
+            "`{}` does not support building bound implementations",
+            type_name::<C>()
+        )
+    })
+}
@@ -0,0 +1,140 @@
+use core::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use crate::ContextRead;
+#[cfg(feature = "std")]
+use crate::{Builder, Context};
+
+/// Mock clock stored in a context
+///
+/// Builders for time-based values, such as [`SystemTime`](std::time::SystemTime),
+/// [`FrozenInstant`] or the timestamp of a ULID, read the time from the
+/// [`Clock`] in the context if there is one, which makes them deterministic. The clock only moves
+/// when it is [advanced](Self::advance). It uses interior mutability, so that a
+/// [`SubContext`](crate::SubContext) advances the clock it inherits from its parent.
+#[derive(Debug)]
+pub struct Clock {
+    /// Nanoseconds since the UNIX epoch
+    nanos: AtomicU64,
+}
+
+impl Clock {
+    /// Create a new [`Clock`] at a time since the UNIX epoch
+    pub fn from_unix_time(since_epoch: Duration) -> Self {
+        Self {
+            nanos: AtomicU64::new(since_epoch.as_nanos() as u64),
+        }
+    }
+
+    /// Create a new [`Clock`] at a number of milliseconds since the UNIX epoch
+    pub fn from_unix_millis(millis: u64) -> Self {
+        Self::from_unix_time(Duration::from_millis(millis))
+    }
+
+    /// Create a new [`Clock`] at the current system time
+    #[cfg(feature = "std")]
+    pub fn now() -> Self {
+        Self::from_unix_time(system_unix_time())
+    }
+
+    /// Return the time since the UNIX epoch
+    pub fn unix_time(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::Relaxed))
+    }
+
+    /// Return the number of milliseconds since the UNIX epoch
+    pub fn unix_millis(&self) -> u64 {
+        self.unix_time().as_millis() as u64
+    }
+
+    /// Move the clock forward
+    pub fn advance(&self, by: Duration) {
+        self.nanos
+            .fetch_add(by.as_nanos() as u64, Ordering::Relaxed);
+    }
+}
+
+/// Return the time of the [`Clock`] in the context
+///
+/// Without a clock, this falls back to the system time, or the UNIX epoch without the `std`
+/// feature.
+pub(crate) fn unix_time<C: ContextRead>(ctx: &C) -> Duration {
+    if let Some(clock) = ctx.get::<Clock>() {
+        return clock.unix_time();
+    }
+    #[cfg(feature = "std")]
+    {
+        crate::audit::record(ctx, "`SystemTime::now`, without a `Clock` in the context");
+        system_unix_time()
+    }
+    #[cfg(not(feature = "std"))]
+    return Duration::ZERO;
+}
+
+#[cfg(feature = "std")]
+fn system_unix_time() -> Duration {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+/// Builds the current time of the [`Clock`] in the context
+///
+/// The time is not stored in the context, so advancing the clock changes the next built value.
+#[cfg(feature = "std")]
+impl Builder for std::time::SystemTime {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        std::time::UNIX_EPOCH + unix_time(ctx)
+    }
+}
+
+/// [`Instant`](std::time::Instant) that follows the [`Clock`] in the context
+///
+/// An `Instant` can't be created at an arbitrary time. Instead, the first `FrozenInstant` built
+/// with a context captures the current instant, and later ones are offset from it by how much the
+/// clock moved since.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FrozenInstant(pub std::time::Instant);
+
+/// Instant and clock time captured by the first [`FrozenInstant`] of a context
+#[cfg(feature = "std")]
+struct InstantBase {
+    instant: std::time::Instant,
+    unix_time: Duration,
+}
+
+#[cfg(feature = "std")]
+impl FrozenInstant {
+    /// Return the wrapped instant
+    pub fn instant(&self) -> std::time::Instant {
+        self.0
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<FrozenInstant> for std::time::Instant {
+    fn from(instant: FrozenInstant) -> Self {
+        instant.0
+    }
+}
+
+#[cfg(feature = "std")]
+impl Builder for FrozenInstant {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        let now = unix_time(ctx);
+        if ctx.get::<InstantBase>().is_none() {
+            crate::audit::record(ctx, "`Instant::now`, for the first `FrozenInstant`");
+        }
+        let base = ctx.entry::<InstantBase>().or_insert_with(|| InstantBase {
+            instant: std::time::Instant::now(),
+            unix_time: now,
+        });
+        match now.checked_sub(base.unix_time) {
+            Some(elapsed) => Self(base.instant + elapsed),
+            None => Self(base.instant - (base.unix_time - now)),
+        }
+    }
+}
@@ -1,27 +1,237 @@
-use std::any::TypeId;
+use alloc::{boxed::Box, collections::BTreeMap, string::String, sync::Arc, vec::Vec};
+use core::{any::TypeId, ops::Range};
 
-use crate::{AnyMap, Builder, Entry, NamedBuilder};
-
-/// Trait for implementing a shared context to generate objects
-pub trait Context: Sized {
-    /// Get an entry in the context by its type
-    fn entry<T: Send + Sync + 'static>(&mut self) -> Entry<'_, T>;
-
-    /// Get an entry in the context by its name and type
-    fn entry_named<T: Send + Sync + 'static>(&mut self, name: &'static str) -> Entry<'_, T>;
+use crate::{
+    ancestors::{Layer, LayerKind},
+    bind::{self, Binding},
+    dynamic::{DynContext, DynRef, RawEntry},
+    factory::FnBuilder,
+    finalize::{self, Pending},
+    meta::Registry,
+    preset::Preset,
+    provider::Provider,
+    pushed::Pushed,
+    remove_stored,
+    scenario::{Scenario, ScenarioRecord},
+    trace,
+    value::Stored,
+    Ancestors, AnyMap, BoxedValue, BuildPlan, BuildStep, Builder, Dataset, DeferredEntry, Entry,
+    EntryName, Factory, Finalize, FixtureSet, FromContext, IdStrategy, Identified, Key, KeySet,
+    MissingEntries, MissingEntry, Name, NamedBuilder, ParamBuilder, PlanError, Rebuild,
+    Reservation, Rng, SampleUniform, Sequence, Weighted,
+};
+#[cfg(feature = "metrics")]
+use crate::{metrics::Metrics, Stats};
+#[cfg(feature = "std")]
+use crate::{recipe::Recorder, BuildReport, ObjectGraph, Recipe, Shutdown, Teardown};
 
+/// Trait for reading the objects in a context
+///
+/// Helpers that only inspect a context can take `&impl ContextRead`, which is implemented by
+/// every [`Context`].
+pub trait ContextRead {
     /// Get an object by its type
     fn get<T: Send + Sync + 'static>(&self) -> Option<&T>;
 
     /// Get an object by its name and type
     fn get_named<T: Send + Sync + 'static>(&self, name: &'static str) -> Option<&T>;
 
+    /// Get the random number generator of the context
+    fn rng(&self) -> &Rng;
+
+    /// Whether the context holds an object of this type
+    ///
+    /// Unlike checking the result of [`get`](Self::get), this doesn't mark the entry as read, so
+    /// it doesn't consume [one-shot](Context::insert_once) values. The default implementation
+    /// relies on [`ancestors`](Self::ancestors), and falls back to [`get`](Self::get) for contexts
+    /// that don't implement it.
+    fn contains<T: Send + Sync + 'static>(&self) -> bool {
+        let ancestors = self.ancestors();
+        if ancestors.len() == 0 {
+            return self.get::<T>().is_some();
+        }
+        ancestors.resolve::<T>().is_some()
+    }
+
+    /// Whether the context holds an object of this name and type, see [`contains`](Self::contains)
+    fn contains_named<T: Send + Sync + 'static>(&self, name: &'static str) -> bool {
+        let ancestors = self.ancestors();
+        if ancestors.len() == 0 {
+            return self.get_named::<T>(name).is_some();
+        }
+        ancestors.resolve_named::<T>(name).is_some()
+    }
+
+    /// Get an object by its [`EntryName`] and type
+    fn get_const<K: EntryName, T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.get_named(K::NAME)
+    }
+
+    /// Iterate over the named objects of type `T`, sorted by name
+    ///
+    /// This is useful to enumerate the objects registered by fixtures without knowing their
+    /// names in advance. It relies on [`ancestors`](Self::ancestors), so contexts that don't
+    /// implement it return nothing.
+    ///
+    /// ```
+    /// # use ctxbuilder::{Context, ContextRead};
+    /// let ctx = ctxbuilder::ctx()
+    ///     .with_named("bob", 2u32)
+    ///     .with_named("alice", 1u32)
+    ///     .with(3u32);
+    ///
+    /// let values: Vec<_> = ctx.iter_named::<u32>().collect();
+    /// assert_eq!(values, [("alice", &1), ("bob", &2)]);
+    /// ```
+    fn iter_named<T: Send + Sync + 'static>(&self) -> impl Iterator<Item = (&'static str, &T)> {
+        self.ancestors().named().into_iter()
+    }
+
+    /// Get a copy of an object by its type
+    ///
+    /// Unlike [`get`](Self::get), the result doesn't borrow the context, so it can be used across
+    /// later calls that need `&mut self`, such as builds.
+    fn get_copied<T: Copy + Send + Sync + 'static>(&self) -> Option<T> {
+        self.get().copied()
+    }
+
+    /// Get a copy of an object by its name and type, see [`get_copied`](Self::get_copied)
+    fn get_copied_named<T: Copy + Send + Sync + 'static>(&self, name: &'static str) -> Option<T> {
+        self.get_named(name).copied()
+    }
+
+    /// Get a clone of an object by its type, see [`get_copied`](Self::get_copied)
+    fn get_cloned<T: Clone + Send + Sync + 'static>(&self) -> Option<T> {
+        self.get().cloned()
+    }
+
+    /// Get a clone of an object by its name and type, see [`get_copied`](Self::get_copied)
+    fn get_cloned_named<T: Clone + Send + Sync + 'static>(&self, name: &'static str) -> Option<T> {
+        self.get_named(name).cloned()
+    }
+
+    /// Get all the objects of type `T` appended with [`Context::push`], in insertion order
+    fn get_all<T: Send + Sync + 'static>(&self) -> &[Arc<T>] {
+        self.get::<Pushed<T>>().map_or(&[], |list| &list.0)
+    }
+
+    /// Return the type and name of the entries in the context, in no particular order
+    ///
+    /// This is used for diagnostics, such as in the [`assertions`](crate::assertions) module.
+    /// The default implementation returns nothing. With the `ordered` feature, a [`MainContext`]
+    /// returns its entries in insertion order.
+    fn entry_keys(&self) -> Vec<(&'static str, Option<&'static str>)> {
+        Vec::new()
+    }
+
+    /// Iterate over the layers of the context, from the one lookups check first to the last one
+    ///
+    /// See [`Ancestors`]. The default implementation returns no layers.
+    fn ancestors(&self) -> Ancestors<'_> {
+        Ancestors::new(Vec::new())
+    }
+
+    /// Recorder of the builds and inserts, for contexts created with
+    /// [`MainContext::recording`]
+    #[doc(hidden)]
+    #[cfg(feature = "std")]
+    fn recorder(&self) -> Option<&Arc<Recorder>> {
+        None
+    }
+
+    /// Counters of the builds and inserts, for contexts that keep them
+    #[doc(hidden)]
+    #[cfg(feature = "metrics")]
+    fn metrics(&self) -> Option<&Metrics> {
+        None
+    }
+
+    /// Return the build, cache hit and insert counters per type of this context
+    ///
+    /// Sub-contexts report the counters of their parent, which include their own builds and
+    /// inserts. Contexts that don't keep counters, such as [frozen](MainContext::freeze) ones,
+    /// return empty [`Stats`].
+    ///
+    /// ```
+    /// # use ctxbuilder::{Builder, Context, ContextRead};
+    /// struct Config;
+    ///
+    /// impl Builder for Config {
+    ///     fn build<C: Context>(_ctx: &mut C) -> Self {
+    ///         Self
+    ///     }
+    /// }
+    ///
+    /// let mut ctx = ctxbuilder::ctx();
+    /// ctx.get_or_build::<Config>();
+    /// ctx.get_or_build::<Config>();
+    ///
+    /// let stats = ctx.stats().get::<Config>();
+    /// assert_eq!((stats.builds, stats.cache_hits, stats.inserts), (1, 1, 1));
+    /// ```
+    #[cfg(feature = "metrics")]
+    fn stats(&self) -> Stats {
+        self.metrics().map(Metrics::stats).unwrap_or_default()
+    }
+
+    /// Extract a typed snapshot of the entries in this context
+    fn extract<T: FromContext>(&self) -> Result<T, MissingEntry>
+    where
+        Self: Sized,
+    {
+        T::from_context(self)
+    }
+
+    /// Check that this context holds all the entries required by the [`Builder`] of `T`
+    ///
+    /// This reports all the missing entries at once, with their type and name. See
+    /// [`Builder::requires`].
+    fn validate_for<T: Builder>(&self) -> Result<(), MissingEntries>
+    where
+        Self: Sized,
+    {
+        MissingEntries::check(self, T::requires())
+    }
+}
+
+/// Trait for inserting objects in a context
+pub trait ContextWrite: ContextRead {
+    /// Get an entry in the context by its type
+    fn entry<T: Send + Sync + 'static>(&mut self) -> Entry<'_, T>;
+
+    /// Get an entry in the context by its name and type
+    fn entry_named<T: Send + Sync + 'static>(&mut self, name: &'static str) -> Entry<'_, T>;
+
     /// Insert an object by type
     fn insert<T: Send + Sync + 'static>(&mut self, val: T) -> Option<T>;
 
     /// Insert an object by type and name
     fn insert_named<T: Send + Sync + 'static>(&mut self, name: &'static str, val: T) -> Option<T>;
 
+    /// Get an entry in the context by its [`EntryName`] and type
+    fn entry_const<K: EntryName, T: Send + Sync + 'static>(&mut self) -> Entry<'_, T> {
+        self.entry_named(K::NAME)
+    }
+
+    /// Insert an object by [`EntryName`] and type
+    fn insert_const<K: EntryName, T: Send + Sync + 'static>(&mut self, val: T) -> Option<T> {
+        self.insert_named(K::NAME, val)
+    }
+
+    /// Get a type-erased view of this context
+    ///
+    /// This is used to build [bound](Context::bind) implementations, and to pass contexts behind
+    /// trait objects, see [`DynContext`]. Contexts that don't provide one can't build them.
+    fn as_dyn(&mut self) -> Option<&mut dyn DynContext> {
+        None
+    }
+}
+
+/// Trait for implementing a shared context to generate objects
+///
+/// This is implemented for every type that implements both [`ContextRead`] and
+/// [`ContextWrite`].
+pub trait Context: ContextRead + ContextWrite + Sized {
     /// Convenience method to add objects by type while constructing the [`Context`]
     fn with<T: Send + Sync + 'static>(mut self, val: T) -> Self {
         self.insert(val);
@@ -34,21 +244,930 @@ pub trait Context: Sized {
         self
     }
 
+    /// Insert the result of `f` by type, only if the context doesn't hold an object of this type
+    ///
+    /// Unlike [`insert`](ContextWrite::insert), this never replaces an existing object, and `f`
+    /// only runs if needed. This returns whether the object was inserted.
+    ///
+    /// ```
+    /// # use ctxbuilder::{Context, ContextRead};
+    /// let mut ctx = ctxbuilder::ctx().with(1u32);
+    /// assert!(!ctx.insert_with(|| 2u32));
+    /// assert!(ctx.insert_with(|| "expensive".to_string()));
+    /// assert_eq!(ctx.get::<u32>(), Some(&1));
+    /// ```
+    fn insert_with<T: Send + Sync + 'static, F: FnOnce() -> T>(&mut self, f: F) -> bool {
+        self.entry::<T>().or_insert_with_entry(f).1.is_inserted()
+    }
+
+    /// Insert the result of `f` by name and type, only if the context doesn't hold an object of
+    /// this name and type, see [`insert_with`](Self::insert_with)
+    fn insert_named_with<T: Send + Sync + 'static, F: FnOnce() -> T>(
+        &mut self,
+        name: &'static str,
+        f: F,
+    ) -> bool {
+        self.entry_named::<T>(name)
+            .or_insert_with_entry(f)
+            .1
+            .is_inserted()
+    }
+
+    /// Insert an object by type, only if the context doesn't hold an object of this type
+    ///
+    /// This returns whether the object was inserted. Otherwise, the existing object is kept and
+    /// `val` is dropped. See [`insert_with`](Self::insert_with) for values that are expensive to
+    /// create.
+    ///
+    /// ```
+    /// # use ctxbuilder::{Context, ContextRead};
+    /// let mut ctx = ctxbuilder::ctx().with(1u32);
+    /// assert!(!ctx.insert_if_absent(2u32));
+    /// assert_eq!(ctx.get::<u32>(), Some(&1));
+    /// ```
+    fn insert_if_absent<T: Send + Sync + 'static>(&mut self, val: T) -> bool {
+        self.insert_with(|| val)
+    }
+
+    /// Insert an object by name and type, only if the context doesn't hold an object of this name
+    /// and type, see [`insert_if_absent`](Self::insert_if_absent)
+    fn insert_named_if_absent<T: Send + Sync + 'static>(
+        &mut self,
+        name: &'static str,
+        val: T,
+    ) -> bool {
+        self.insert_named_with(name, || val)
+    }
+
+    /// Insert an object by type, panicking if the context already holds an object of this type
+    ///
+    /// This is useful in larger setups, where silently replacing an object that another preset
+    /// or fixture inserted leads to wrong fixtures that are hard to track down. Objects inherited
+    /// from a parent context count as existing objects.
+    ///
+    /// ```should_panic
+    /// # use ctxbuilder::Context;
+    /// let mut ctx = ctxbuilder::ctx().with(1u32);
+    /// ctx.insert_or_panic(2u32);
+    /// ```
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the context already holds an object of this type.
+    #[track_caller]
+    fn insert_or_panic<T: Send + Sync + 'static>(&mut self, val: T) {
+        if self.contains::<T>() {
+            panic!(
+                "context already holds an entry of type `{}`",
+                core::any::type_name::<T>()
+            );
+        }
+        self.insert(val);
+    }
+
+    /// Insert an object by name and type, panicking if the context already holds an object of
+    /// this name and type, see [`insert_or_panic`](Self::insert_or_panic)
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the context already holds an object of this name and type.
+    #[track_caller]
+    fn insert_named_or_panic<T: Send + Sync + 'static>(&mut self, name: &'static str, val: T) {
+        if self.contains_named::<T>(name) {
+            panic!(
+                "context already holds an entry `{name}` of type `{}`",
+                core::any::type_name::<T>()
+            );
+        }
+        self.insert_named(name, val);
+    }
+
+    /// Replace an existing object by type, panicking if the context doesn't hold one
+    ///
+    /// This returns the previous object if it was stored in this context. Objects inherited from
+    /// a parent context or shared with a [fork](MainContext::fork) are shadowed rather than
+    /// moved, so `None` is returned for them.
+    ///
+    /// ```
+    /// # use ctxbuilder::{Context, ContextRead};
+    /// let mut ctx = ctxbuilder::ctx().with(1u32);
+    /// assert_eq!(ctx.replace(2u32), Some(1));
+    /// assert_eq!(ctx.get::<u32>(), Some(&2));
+    /// ```
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the context doesn't hold an object of this type.
+    #[track_caller]
+    fn replace<T: Send + Sync + 'static>(&mut self, val: T) -> Option<T> {
+        if !self.contains::<T>() {
+            panic!(
+                "context has no entry of type `{}` to replace",
+                core::any::type_name::<T>()
+            );
+        }
+        self.insert(val)
+    }
+
+    /// Replace an existing object by name and type, panicking if the context doesn't hold one,
+    /// see [`replace`](Self::replace)
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the context doesn't hold an object of this name and type.
+    #[track_caller]
+    fn replace_named<T: Send + Sync + 'static>(&mut self, name: &'static str, val: T) -> Option<T> {
+        if !self.contains_named::<T>(name) {
+            panic!(
+                "context has no entry `{name}` of type `{}` to replace",
+                core::any::type_name::<T>()
+            );
+        }
+        self.insert_named(name, val)
+    }
+
+    /// Apply a [`Preset`] onto this context
+    fn apply<P: Preset>(&mut self, preset: P) {
+        preset.apply(self);
+    }
+
+    /// Convenience method to apply a [`Preset`] while constructing the [`Context`]
+    fn with_preset<P: Preset>(mut self, preset: P) -> Self {
+        self.apply(preset);
+        self
+    }
+
+    /// Run a [`Scenario`] on this context, returning the entries it created
+    fn run_scenario<S: Scenario>(&mut self, scenario: S) -> ScenarioRecord {
+        crate::scenario::run(self, scenario)
+    }
+
     /// Build a new object with this context
     fn build<T: Builder>(&mut self) -> T {
+        let _span = trace::build::<T>(None);
+        #[cfg(feature = "std")]
+        let _record = self
+            .recorder()
+            .map(|recorder| recorder.enter::<T>(None, true));
+        #[cfg(feature = "std")]
+        let _report = crate::report::enter::<T, _>(self, T::requires());
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = self.metrics() {
+            metrics.build(core::any::type_name::<T>());
+        }
         T::build(self)
     }
 
+    /// Build a new object, collecting the missing entries and panics of all the nested builds
+    ///
+    /// See [`BuildReport`]. This returns an error if any of the builds misses entries that it
+    /// [requires](Builder::requires), even if the object could be built.
+    #[cfg(feature = "std")]
+    fn try_build<T: Builder>(&mut self) -> Result<T, BuildReport> {
+        crate::report::try_build(self)
+    }
+
+    /// Build a new object with a closure, without defining a [`Builder`] for its type
+    ///
+    /// This is useful for one-off fixture shapes. The closure receives this context, and the build
+    /// is traced like [`build`](Self::build). See [`FnBuilder`](crate::factory::FnBuilder) to
+    /// reuse a closure.
+    ///
+    /// ```
+    /// # use ctxbuilder::{Context, ContextRead};
+    /// let mut ctx = ctxbuilder::ctx().with_named("name", "Alice");
+    /// let greeting = ctx.build_fn(|ctx| {
+    ///     let name = ctx.get_named::<&str>("name").unwrap();
+    ///     format!("Hello, {name}!")
+    /// });
+    /// assert_eq!(greeting, "Hello, Alice!");
+    /// ```
+    fn build_fn<T, F: FnOnce(&mut Self) -> T>(&mut self, f: F) -> T {
+        let _span = trace::build::<T>(None);
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = self.metrics() {
+            metrics.build(core::any::type_name::<T>());
+        }
+        f(self)
+    }
+
+    /// Build a new set of related objects with this context, see [`FixtureSet`]
+    fn build_set<T: FixtureSet>(&mut self) -> T::Output {
+        let _span = trace::build::<T>(None);
+        T::build_set(self)
+    }
+
     /// Build a new named object with this context
-    fn build_named<T: NamedBuilder>(&mut self, name: &'static str) -> T {
+    ///
+    /// The name can be any [`Name`], such as a `&'static str` or an enum variant.
+    fn build_named<T: NamedBuilder<N>, N: Name>(&mut self, name: N) -> T {
+        let _span = trace::build::<T>(name.as_str());
+        // Names that aren't strings can't be replayed
+        #[cfg(feature = "std")]
+        let _record = self
+            .recorder()
+            .map(|recorder| recorder.enter::<T>(name.as_str(), name.as_str().is_some()));
+        #[cfg(feature = "std")]
+        let _report = crate::report::enter::<T, _>(self, &[]);
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = self.metrics() {
+            metrics.build(core::any::type_name::<T>());
+        }
         T::build_with_name(self, name)
     }
+
+    /// Bind the implementation `T` to the trait object `I`
+    ///
+    /// [`build_dyn`](Self::build_dyn) and [`build_dyn_arc`](Self::build_dyn_arc) then build a `T`
+    /// and return it as an `I`. Binding another implementation replaces the previous one, so that
+    /// tests can swap implementations without changing the builders that use them.
+    ///
+    /// `coerce` converts the boxed implementation into the trait object, and is usually
+    /// `|val| val`:
+    ///
+    /// ```
+    /// # use ctxbuilder::{Builder, Context};
+    /// trait Repository {
+    ///     fn name(&self) -> &'static str;
+    /// }
+    ///
+    /// struct InMemoryRepo;
+    ///
+    /// impl Repository for InMemoryRepo {
+    ///     fn name(&self) -> &'static str {
+    ///         "in-memory"
+    ///     }
+    /// }
+    ///
+    /// impl Builder for InMemoryRepo {
+    ///     fn build<C: Context>(_ctx: &mut C) -> Self {
+    ///         Self
+    ///     }
+    /// }
+    ///
+    /// let mut ctx = ctxbuilder::ctx();
+    /// ctx.bind::<dyn Repository, InMemoryRepo>(|val| val);
+    /// assert_eq!(ctx.build_dyn::<dyn Repository>().name(), "in-memory");
+    /// ```
+    fn bind<I: ?Sized + 'static, T: Builder + 'static>(&mut self, coerce: fn(Box<T>) -> Box<I>) {
+        self.insert(Binding::new(coerce));
+    }
+
+    /// Build the implementation [bound](Self::bind) to the trait object `I`
+    ///
+    /// ## Panics
+    ///
+    /// This panics if no implementation is bound to `I`.
+    fn build_dyn<I: ?Sized + 'static>(&mut self) -> Box<I> {
+        let _span = trace::build::<Box<I>>(None);
+        bind::build(bind::dyn_context(self))
+    }
+
+    /// Build the implementation [bound](Self::bind) to the trait object `I` into an [`Arc`]
+    ///
+    /// ## Panics
+    ///
+    /// This panics if no implementation is bound to `I`.
+    fn build_dyn_arc<I: ?Sized + 'static>(&mut self) -> Arc<I> {
+        Arc::from(self.build_dyn::<I>())
+    }
+
+    /// Build these types in an order that satisfies their [requirements](Builder::requires), and
+    /// insert them in this context
+    ///
+    /// See [`BuildPlan`] for how the order is computed.
+    ///
+    /// ## Panics
+    ///
+    /// This panics if this context doesn't support type-erased builds, like
+    /// [`build_dyn`](Self::build_dyn).
+    fn build_all(&mut self, steps: &[BuildStep]) -> Result<(), PlanError> {
+        BuildPlan::new(steps)?.run(self)
+    }
+
+    /// Get a shared handle to the mock object of type `M`, creating it with [`Default`] if needed
+    ///
+    /// This is meant for mocks generated by crates such as `mockall`, so that services can pull
+    /// their mocks from the same context as their data. Expectations are set with
+    /// [`configure_mock`](Self::configure_mock) beforehand.
+    ///
+    /// ```
+    /// # use ctxbuilder::Context;
+    /// #[mockall::automock]
+    /// trait Clock {
+    ///     fn now(&self) -> u64;
+    /// }
+    ///
+    /// let mut ctx = ctxbuilder::ctx();
+    /// ctx.configure_mock::<MockClock>(|mock| {
+    ///     mock.expect_now().return_const(42u64);
+    /// });
+    ///
+    /// let clock: std::sync::Arc<dyn Clock + Send + Sync> = ctx.mock::<MockClock>();
+    /// assert_eq!(clock.now(), 42);
+    /// ```
+    fn mock<M: Default + Send + Sync + 'static>(&mut self) -> Arc<M> {
+        self.entry::<Arc<M>>().or_default().clone()
+    }
+
+    /// Configure the mock object of type `M`, creating it with [`Default`] if needed
+    ///
+    /// In a [`SubContext`], this configures a new mock that shadows the one of the parent.
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the mock was already shared with [`mock`](Self::mock), as expectations
+    /// can't be changed while other handles exist.
+    fn configure_mock<M: Default + Send + Sync + 'static>(&mut self, f: impl FnOnce(&mut M)) {
+        let mut f = Some(f);
+        let mut configure = |mock: &mut Arc<M>| {
+            let mock = Arc::get_mut(mock).unwrap_or_else(|| {
+                panic!(
+                    "mock `{}` was already shared, configure it before calling `mock`",
+                    core::any::type_name::<M>()
+                )
+            });
+            if let Some(f) = f.take() {
+                f(mock);
+            }
+        };
+        let entry = self.entry::<Arc<M>>().and_modify(&mut configure);
+        entry.or_insert_with(|| {
+            let mut mock = Arc::default();
+            configure(&mut mock);
+            mock
+        });
+    }
+
+    /// Build a new object with this context and parameters
+    fn build_with<T: ParamBuilder<P>, P>(&mut self, params: P) -> T {
+        let _span = trace::build::<T>(None);
+        T::build_with(self, params)
+    }
+
+    /// Get an entry in the context by its type, with a default value that can depend on the rest
+    /// of the context
+    ///
+    /// ```
+    /// # use ctxbuilder::{Context, ContextRead};
+    /// let mut ctx = ctxbuilder::ctx().with_named("name", "alice".to_string());
+    /// let email = ctx
+    ///     .deferred_entry_named::<String>("email")
+    ///     .or_insert_with_ctx(|ctx| format!("{}@example.com", ctx.get_named::<String>("name").unwrap()));
+    /// assert_eq!(email, "alice@example.com");
+    /// ```
+    fn deferred_entry<T: Send + Sync + 'static>(&mut self) -> DeferredEntry<'_, Self, T> {
+        DeferredEntry::new(self, None)
+    }
+
+    /// Get an entry in the context by its name and type, with a default value that can depend on
+    /// the rest of the context
+    fn deferred_entry_named<T: Send + Sync + 'static>(
+        &mut self,
+        name: &'static str,
+    ) -> DeferredEntry<'_, Self, T> {
+        DeferredEntry::new(self, Some(name))
+    }
+
+    /// Reserve an entry by its type, without borrowing the context
+    ///
+    /// This lets builders build other objects before filling the entry, which an [`Entry`] can't
+    /// do as it borrows the context. See [`Reservation`].
+    fn reserve<T: Send + Sync + 'static>(&self) -> Reservation<T> {
+        Reservation::new(self, None)
+    }
+
+    /// Reserve an entry by its name and type, without borrowing the context
+    ///
+    /// See [`reserve`](Self::reserve).
+    fn reserve_named<T: Send + Sync + 'static>(&self, name: &'static str) -> Reservation<T> {
+        Reservation::new(self, Some(name))
+    }
+
+    /// Insert an object by type that can only be read once
+    ///
+    /// This models single-use fixtures such as nonces or one-time passwords: reading the object
+    /// a second time, through this context or one that shares its entries, panics. Mutable
+    /// accesses through [`entry`](ContextWrite::entry) count as reads.
+    ///
+    /// ```should_panic
+    /// # use ctxbuilder::{Context, ContextRead};
+    /// struct Nonce(u64);
+    ///
+    /// let mut ctx = ctxbuilder::ctx();
+    /// ctx.insert_once(Nonce(42));
+    /// assert_eq!(ctx.get::<Nonce>().unwrap().0, 42);
+    /// // Reusing the nonce panics
+    /// ctx.get::<Nonce>();
+    /// ```
+    ///
+    /// ## Panics
+    ///
+    /// This panics if this context doesn't support type-erased inserts, like
+    /// [`build_dyn`](Self::build_dyn).
+    fn insert_once<T: Send + Sync + 'static>(&mut self, val: T) -> Option<T> {
+        bind::dyn_context(self)
+            .insert_stored((TypeId::of::<T>(), None), Stored::once(val))
+            .and_then(Stored::into_inner)
+    }
+
+    /// Insert an object by name and type that can only be read once
+    ///
+    /// See [`insert_once`](Self::insert_once).
+    fn insert_once_named<T: Send + Sync + 'static>(
+        &mut self,
+        name: &'static str,
+        val: T,
+    ) -> Option<T> {
+        bind::dyn_context(self)
+            .insert_stored((TypeId::of::<T>(), Some(name)), Stored::once(val))
+            .and_then(Stored::into_inner)
+    }
+
+    /// Insert an object by type, to [finalize](Finalize) it with [`finalize_all`](Self::finalize_all)
+    fn insert_finalized<T: Finalize>(&mut self, val: T) -> Option<T> {
+        finalize::defer::<T, _>(self, None);
+        self.insert(val)
+    }
+
+    /// Insert an object by name and type, to [finalize](Finalize) it with
+    /// [`finalize_all`](Self::finalize_all)
+    fn insert_finalized_named<T: Finalize>(&mut self, name: &'static str, val: T) -> Option<T> {
+        finalize::defer::<T, _>(self, Some(name));
+        self.insert_named(name, val)
+    }
+
+    /// Run [`Finalize::finalize`] on the objects inserted with
+    /// [`insert_finalized`](Self::insert_finalized) since the last call, in insertion order
+    ///
+    /// Objects inserted while finalizing are finalized too. Objects that were removed, or that are
+    /// only stored in a context this one was [forked](MainContext::fork) from, are skipped.
+    ///
+    /// ## Panics
+    ///
+    /// This panics if this context doesn't support type-erased inserts, like
+    /// [`build_dyn`](Self::build_dyn).
+    fn finalize_all(&mut self) {
+        loop {
+            let mut pending = Vec::new();
+            self.entry::<Pending>()
+                .and_modify(|entries| pending = core::mem::take(&mut entries.0));
+            if pending.is_empty() {
+                return;
+            }
+            let mut ctx = DynRef(bind::dyn_context(self));
+            for (name, finalize) in pending {
+                finalize(&mut ctx, name);
+            }
+        }
+    }
+
+    /// Update an existing object with this context
+    fn rebuild<T: Rebuild>(&mut self, obj: &mut T) {
+        obj.rebuild(self);
+    }
+
+    /// Get an object by its type, building and storing it first if needed
+    ///
+    /// Unlike [`build`](Self::build), this returns the same object on every call.
+    fn get_or_build<T: Builder + Send + Sync + 'static>(&mut self) -> &T {
+        if !self.contains::<T>() {
+            let val = self.build::<T>();
+            self.insert(val);
+        } else {
+            #[cfg(feature = "metrics")]
+            if let Some(metrics) = self.metrics() {
+                metrics.cache_hit(core::any::type_name::<T>());
+            }
+        }
+        self.get().expect("value inserted in the context")
+    }
+
+    /// Get an object by its name and type, building and storing it first if needed
+    ///
+    /// Unlike [`build_named`](Self::build_named), this returns the same object on every call.
+    fn get_or_build_named<T: NamedBuilder + Send + Sync + 'static>(
+        &mut self,
+        name: &'static str,
+    ) -> &T {
+        if !self.contains_named::<T>(name) {
+            let val = self.build_named::<T, _>(name);
+            self.insert_named(name, val);
+        } else {
+            #[cfg(feature = "metrics")]
+            if let Some(metrics) = self.metrics() {
+                metrics.cache_hit(core::any::type_name::<T>());
+            }
+        }
+        self.get_named(name).expect("value inserted in the context")
+    }
+
+    /// Register a lazy constructor for the object with this name and type
+    ///
+    /// The closure only runs the first time the object is needed, through
+    /// [`get_or_provide_named`](Self::get_or_provide_named), and the object is then stored in the
+    /// context. This lets tests define objects such as "the admin user" and "the guest user" once,
+    /// and use them anywhere without building them upfront. Registering another constructor for
+    /// the same name replaces the previous one, but not an object that was already created.
+    ///
+    /// The closure receives a type-erased context, like [`FnBuilder`].
+    ///
+    /// ```
+    /// # use ctxbuilder::{Context, ContextRead};
+    /// struct User {
+    ///     login: &'static str,
+    ///     admin: bool,
+    /// }
+    ///
+    /// let mut ctx = ctxbuilder::ctx();
+    /// ctx.provide_named("admin", |_| User { login: "root", admin: true });
+    /// ctx.provide_named("guest", |_| User { login: "guest", admin: false });
+    ///
+    /// assert!(ctx.get_or_provide_named::<User>("admin").admin);
+    /// assert!(ctx.get_named::<User>("guest").is_none());
+    /// ```
+    fn provide_named<T, F>(&mut self, name: &'static str, f: F)
+    where
+        T: Send + Sync + 'static,
+        F: Fn(&mut DynRef<'_>) -> T + Send + Sync + 'static,
+    {
+        self.insert_named(name, Provider(FnBuilder::new(f)));
+    }
+
+    /// Get an object by its name and type, creating and storing it with its
+    /// [provider](Self::provide_named) first if needed
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the object is missing and no provider was registered for it, or if this
+    /// context doesn't support type-erased builds, like [`build_dyn`](Self::build_dyn).
+    fn get_or_provide_named<T: Send + Sync + 'static>(&mut self, name: &'static str) -> &T {
+        if !self.contains_named::<T>(name) {
+            let provider = self
+                .get_named::<Provider<T>>(name)
+                .map(|provider| provider.0.clone())
+                .unwrap_or_else(|| {
+                    panic!(
+                        "no provider for `{}` named `{name}`",
+                        core::any::type_name::<T>()
+                    )
+                });
+            let val = self.build_fn(|ctx| provider.make(ctx));
+            self.insert_named(name, val);
+        } else {
+            #[cfg(feature = "metrics")]
+            if let Some(metrics) = self.metrics() {
+                metrics.cache_hit(core::any::type_name::<T>());
+            }
+        }
+        self.get_named(name).expect("value inserted in the context")
+    }
+
+    /// Get a clone of an object by its type, inserting the default value first if needed
+    ///
+    /// This is a shortcut for `ctx.entry::<T>().or_default().clone()`, which doesn't keep the
+    /// context borrowed.
+    ///
+    /// ```
+    /// # use ctxbuilder::Context;
+    /// let mut ctx = ctxbuilder::ctx().with(3u32);
+    /// assert_eq!(ctx.get_or_default::<u32>(), 3);
+    /// assert_eq!(ctx.get_or_default::<bool>(), false);
+    /// ```
+    fn get_or_default<T: Default + Clone + Send + Sync + 'static>(&mut self) -> T {
+        self.entry::<T>().or_default().clone()
+    }
+
+    /// Get a clone of an object by its name and type, inserting the default value first if
+    /// needed, see [`get_or_default`](Self::get_or_default)
+    fn get_or_default_named<T: Default + Clone + Send + Sync + 'static>(
+        &mut self,
+        name: &'static str,
+    ) -> T {
+        self.entry_named::<T>(name).or_default().clone()
+    }
+
+    /// Build a new object with a [`Factory`]
+    fn make<F: Factory>(&mut self, factory: F) -> F::Output {
+        factory.make(self)
+    }
+
+    /// Build `n` new objects with this context
+    fn build_vec<T: Builder>(&mut self, n: usize) -> Vec<T> {
+        (0..n).map(|_| self.build()).collect()
+    }
+
+    /// Build a [`Dataset`] of `rows` parents, each with a random number of children in the
+    /// `children` range
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the `children` range is empty.
+    fn build_dataset<P, C>(&mut self, rows: usize, children: Range<usize>) -> Dataset<P, C>
+    where
+        P: Builder,
+        C: for<'p> ParamBuilder<&'p P>,
+    {
+        Dataset::build(self, rows, children)
+    }
+
+    /// Build a new named object for each name, in order
+    fn build_all_named<T: NamedBuilder<N>, N: Name>(&mut self, names: &[N]) -> Vec<T> {
+        names.iter().map(|name| self.build_named(*name)).collect()
+    }
+
+    /// Build a new named object for each name, in order, into an array
+    ///
+    /// This is convenient to destructure a known set of fixtures:
+    ///
+    /// ```
+    /// # use ctxbuilder::{Context, ContextRead};
+    /// # #[cfg(feature = "uuid")]
+    /// # {
+    /// let mut ctx = ctxbuilder::ctx();
+    /// let [alice, bob]: [uuid::Uuid; 2] = ctx.build_array_named(["alice", "bob"]);
+    /// assert_eq!(ctx.get_named("alice"), Some(&alice));
+    /// assert_ne!(alice, bob);
+    /// # }
+    /// ```
+    fn build_array_named<T: NamedBuilder<N>, N: Name, const LEN: usize>(
+        &mut self,
+        names: [N; LEN],
+    ) -> [T; LEN] {
+        names.map(|name| self.build_named(name))
+    }
+
+    /// Return the next value of the unnamed [`Sequence`], creating it if needed
+    fn next_seq(&mut self) -> u64 {
+        self.entry::<Sequence>().or_default().next_value()
+    }
+
+    /// Return the next value of the named [`Sequence`], creating it if needed
+    fn next_seq_named(&mut self, name: &'static str) -> u64 {
+        self.entry_named::<Sequence>(name).or_default().next_value()
+    }
+
+    /// Append a value to the list of objects of type `T`, see [`ContextRead::get_all`]
+    ///
+    /// This is useful for builders to record every object they create, so that tests can assert
+    /// over all of them later. The list is separate from the entry of type `T`, and follows the
+    /// same scoping rules: a [`SubContext`] starts with the values of its parent, but the values
+    /// it appends are dropped with it.
+    ///
+    /// ```
+    /// # use ctxbuilder::{Context, ContextRead};
+    /// let mut ctx = ctxbuilder::ctx();
+    /// ctx.push("alice");
+    /// ctx.push("bob");
+    ///
+    /// let names: Vec<_> = ctx.get_all::<&str>().iter().map(|name| **name).collect();
+    /// assert_eq!(names, ["alice", "bob"]);
+    /// ```
+    fn push<T: Send + Sync + 'static>(&mut self, val: T) {
+        let mut val = Some(Arc::new(val));
+        self.entry::<Pushed<T>>()
+            .and_modify(|list| list.0.extend(val.take()));
+        // The list is missing or inherited from a parent context
+        if let Some(val) = val {
+            let mut list = self.get::<Pushed<T>>().cloned().unwrap_or_default();
+            list.0.push(val);
+            self.insert(list);
+        }
+    }
+
+    /// Register a hook to run when the context is torn down
+    ///
+    /// Hooks run in reverse registration order when the context is dropped. See [`Teardown`].
+    ///
+    /// ```
+    /// # use ctxbuilder::Context;
+    /// # use std::sync::{Arc, Mutex};
+    /// let log = Arc::new(Mutex::new(Vec::new()));
+    /// let mut ctx = ctxbuilder::ctx();
+    /// for step in ["create schema", "start server"] {
+    ///     let log = log.clone();
+    ///     ctx.on_teardown(move || log.lock().unwrap().push(step));
+    /// }
+    ///
+    /// drop(ctx);
+    /// assert_eq!(*log.lock().unwrap(), ["start server", "create schema"]);
+    /// ```
+    #[cfg(feature = "std")]
+    fn on_teardown<F: FnOnce() + Send + 'static>(&mut self, hook: F) {
+        self.entry::<Teardown>().or_default().push(hook);
+    }
+
+    /// Keep a value alive until the context is torn down
+    ///
+    /// This is useful for RAII guards, such as temporary directories or child processes, that
+    /// clean up their resource when dropped. Guards are dropped in reverse registration order
+    /// along with the other [teardown hooks](Self::on_teardown).
+    #[cfg(feature = "std")]
+    fn add_guard<T: Send + 'static>(&mut self, guard: T) {
+        self.on_teardown(move || drop(guard));
+    }
+
+    /// Register an async hook to run when the context is [shut down](Self::shutdown)
+    ///
+    /// This is useful for resources that need to be closed gracefully, such as connection pools
+    /// or containers. See [`Teardown`].
+    #[cfg(feature = "std")]
+    fn on_async_teardown<F: core::future::Future<Output = ()> + Send + 'static>(
+        &mut self,
+        hook: F,
+    ) {
+        self.entry::<Teardown>().or_default().push_async(hook);
+    }
+
+    /// Run the teardown hooks of the context, including async ones
+    ///
+    /// Hooks run in reverse registration order. Sync hooks registered after calling this method
+    /// still run when the context is dropped.
+    ///
+    /// ```
+    /// # use ctxbuilder::Context;
+    /// # use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// let closed = Arc::new(AtomicBool::new(false));
+    /// let mut ctx = ctxbuilder::ctx();
+    /// let flag = closed.clone();
+    /// ctx.on_async_teardown(async move {
+    ///     tokio::task::yield_now().await;
+    ///     flag.store(true, Ordering::Relaxed);
+    /// });
+    ///
+    /// ctx.shutdown().await;
+    /// assert!(closed.load(Ordering::Relaxed));
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    fn shutdown(&mut self) -> Shutdown {
+        self.get::<Teardown>()
+            .map(Teardown::shutdown)
+            .unwrap_or_default()
+    }
+
+    /// Get the path of a named temporary directory, creating it if needed
+    ///
+    /// The directory is stored as a [`TempDir`](tempfile::TempDir) entry, and removed with its
+    /// content when the context is [torn down](Self::on_teardown).
+    ///
+    /// ```
+    /// # use ctxbuilder::Context;
+    /// let mut ctx = ctxbuilder::ctx();
+    /// let uploads = ctx.temp_dir("uploads").to_path_buf();
+    /// assert!(uploads.is_dir());
+    /// assert_eq!(ctx.temp_dir("uploads"), uploads);
+    ///
+    /// drop(ctx);
+    /// assert!(!uploads.exists());
+    /// ```
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the directory can't be created.
+    #[cfg(feature = "tempfile")]
+    fn temp_dir(&mut self, name: &'static str) -> &std::path::Path {
+        crate::temp::temp_dir(self, name)
+    }
+
+    /// Get the path of a named temporary file, creating it if needed
+    ///
+    /// The file is stored as a [`NamedTempFile`](tempfile::NamedTempFile) entry, and removed when
+    /// the context is [torn down](Self::on_teardown).
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the file can't be created.
+    #[cfg(feature = "tempfile")]
+    fn temp_file(&mut self, name: &'static str) -> &std::path::Path {
+        crate::temp::temp_file(self, name)
+    }
+
+    /// Generate the next ID of `T` with its [`IdStrategy`]
+    ///
+    /// If the context doesn't have a strategy for `T`, the
+    /// [default one](Identified::default_id_strategy) is inserted first.
+    fn next_id<T: Identified>(&mut self) -> T::Id {
+        if self.get::<IdStrategy<T>>().is_none() {
+            self.insert(T::default_id_strategy());
+        }
+        let now = crate::clock::unix_time(self);
+        self.get::<IdStrategy<T>>()
+            .expect("strategy inserted in the context")
+            .generate(self.rng(), now)
+    }
+
+    /// Pick a value at random according to the weights
+    ///
+    /// If the context contains a [`Weighted<T>`], it is used instead of the default weights. The
+    /// value is not stored in the context.
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the sum of the weights is zero.
+    fn pick_weighted<T: Clone + Send + Sync + 'static>(&mut self, default: &[(T, u32)]) -> T {
+        match self.get::<Weighted<T>>() {
+            Some(weighted) => weighted.pick(self.rng()).clone(),
+            None => crate::rng::pick_weighted(default, self.rng()).clone(),
+        }
+    }
+
+    /// Generate a random value in a range
+    ///
+    /// If the context contains a [`Range<T>`], it is used instead of the default range. The value
+    /// is not stored in the context.
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the range is empty.
+    fn pick_in_range<T: SampleUniform + Clone + Send + Sync + 'static>(
+        &mut self,
+        default: Range<T>,
+    ) -> T {
+        let range = self.get::<Range<T>>().cloned().unwrap_or(default);
+        self.rng().gen_range(range)
+    }
+
+    /// Generate a random value in a range, using the named range from the context if present
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the range is empty.
+    fn pick_in_range_named<T: SampleUniform + Clone + Send + Sync + 'static>(
+        &mut self,
+        name: &'static str,
+        default: Range<T>,
+    ) -> T {
+        let range = self.get_named::<Range<T>>(name).cloned().unwrap_or(default);
+        self.rng().gen_range(range)
+    }
+
+    /// Expand the `{name}` placeholders in a template
+    ///
+    /// Placeholders are resolved from sequences (`{seq}` for the unnamed one), named
+    /// [`Generator`](crate::Generator)s, and named entries of common types such as `String`,
+    /// integers or `Uuid`. With the `uuid` feature, `{uuid}` generates a new random UUID. Use
+    /// `{{` and `}}` for literal braces.
+    ///
+    /// ## Panics
+    ///
+    /// This panics if a placeholder doesn't match anything in the context.
+    fn format(&mut self, template: &'static str) -> String {
+        crate::format::format(self, template)
+    }
+
+    /// Generate a random string matching a regular expression
+    ///
+    /// This is useful for fixture strings that must pass format validations, such as SKUs or
+    /// license plates. Unbounded repetitions such as `*` and `+` repeat at most 8 more times than
+    /// their minimum, and classes such as `\d` or `.` only generate printable ASCII characters
+    /// when they contain any. Anchors are ignored.
+    ///
+    /// ```
+    /// # use ctxbuilder::Context;
+    /// let ctx = ctxbuilder::ctx();
+    /// let sku = ctx.string_matching(r"[A-Z]{3}-\d{4}");
+    /// assert_eq!(sku.len(), 8);
+    /// assert!(sku[..3].chars().all(|c| c.is_ascii_uppercase()));
+    /// assert!(sku[4..].chars().all(|c| c.is_ascii_digit()));
+    /// ```
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the pattern is invalid, or can't match any string.
+    #[cfg(feature = "regex")]
+    fn string_matching(&self, pattern: &str) -> String {
+        crate::pattern::string_matching(self.rng(), pattern)
+    }
 }
 
+impl<C: ContextRead + ContextWrite> Context for C {}
+
 /// Shared context to build objects
 #[derive(Default)]
 pub struct MainContext {
-    map: AnyMap,
+    pub(crate) map: AnyMap,
+    pub(crate) rng: Rng,
+    /// Entries shared with forked contexts, from the oldest to the newest
+    shared: Vec<Arc<AnyMap>>,
+    /// Print the entries that were never read when dropping the context
+    warn_unused: bool,
+    /// Operations registered for the stored types
+    pub(crate) registry: Registry,
+    /// Recorder of the builds and inserts, see [`MainContext::recording`]
+    #[cfg(feature = "std")]
+    recorder: Option<Arc<Recorder>>,
+    /// Counters of the builds and inserts, see [`ContextRead::stats`]
+    #[cfg(feature = "metrics")]
+    metrics: Metrics,
+    /// Tags of the entries, see [`MainContext::insert_tagged`]
+    tags: Vec<(&'static str, Key)>,
+    /// Entries of each tenant, see [`MainContext::tenant`]
+    pub(crate) tenants: BTreeMap<&'static str, AnyMap>,
+    /// Previous values of the entries, see [`MainContext::keep_history`]
+    history: BTreeMap<Key, Vec<Stored>>,
 }
 
 impl MainContext {
@@ -57,108 +1176,960 @@ impl MainContext {
         Self::default()
     }
 
+    /// Create a new [`MainContext`] with a seeded random number generator
+    pub fn seeded(seed: u64) -> Self {
+        Self::from_parts(Default::default(), Rng::seeded(seed))
+    }
+
+    /// Create a new [`MainContext`] with a seeded random number generator, that records the
+    /// objects built with it and inserted in it into a [`Recipe`]
+    ///
+    /// See [`Recipe`] for what is recorded. Forks of this context don't record anything.
+    #[cfg(feature = "std")]
+    pub fn recording(seed: u64) -> Self {
+        let mut ctx = Self::seeded(seed);
+        ctx.recorder = Some(Arc::new(Recorder::new(seed)));
+        ctx
+    }
+
+    /// Return the [`Recipe`] recorded so far, if this context was created with
+    /// [`recording`](Self::recording)
+    #[cfg(feature = "std")]
+    pub fn recipe(&self) -> Option<Recipe> {
+        self.recorder.as_ref().map(|recorder| recorder.recipe())
+    }
+
+    /// Return the [`ObjectGraph`] of the objects built so far, if this context was created with
+    /// [`recording`](Self::recording)
+    #[cfg(feature = "std")]
+    pub fn object_graph(&self) -> Option<ObjectGraph> {
+        self.recorder
+            .as_ref()
+            .map(|recorder| recorder.object_graph())
+    }
+
+    /// Create a new [`MainContext`] with space for at least `capacity` entries
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::from_parts(
+            AnyMap::with_capacity_and_hasher(capacity, Default::default()),
+            Default::default(),
+        )
+    }
+
+    fn from_parts(map: AnyMap, rng: Rng) -> Self {
+        Self::from_shared(map, Vec::new(), rng)
+    }
+
+    pub(crate) fn from_shared(map: AnyMap, shared: Vec<Arc<AnyMap>>, rng: Rng) -> Self {
+        Self {
+            map,
+            rng,
+            shared,
+            warn_unused: false,
+            registry: Default::default(),
+            tags: Vec::new(),
+            tenants: BTreeMap::new(),
+            history: BTreeMap::new(),
+            #[cfg(feature = "std")]
+            recorder: None,
+            #[cfg(feature = "metrics")]
+            metrics: Default::default(),
+        }
+    }
+
+    /// Reserve space for at least `additional` more entries
+    pub fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional);
+    }
+
+    /// Return the number of entries the context can hold without reallocating
+    pub fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    /// Create a new [`MainContext`] seeded from fuzzer-provided bytes
+    ///
+    /// The random number generator is seeded from the input, so that fuzz targets can construct
+    /// structured objects through existing [`Builder`] implementations.
+    #[cfg(feature = "arbitrary")]
+    pub fn from_unstructured(u: &mut arbitrary::Unstructured<'_>) -> arbitrary::Result<Self> {
+        Ok(Self::seeded(u.arbitrary()?))
+    }
+
+    /// Create an independent copy of this context
+    ///
+    /// Existing entries are not copied, but shared between this context and the fork through an
+    /// [`Arc`]. Inserting a value in either context only stores it in that context, shadowing the
+    /// shared entry. This makes forking cheap even when the context holds expensive values that
+    /// are not [`Clone`], such as database handles.
+    ///
+    /// The fork starts with the same random number generator state as this context. Note that
+    /// values with interior mutability, such as [`Sequence`]s, are shared rather than copied. The
+    /// entries of [tenants](Self::tenant) are not shared, so the fork starts without any, and the
+    /// fork doesn't [keep the history](Self::keep_history) of any entry.
+    pub fn fork(&mut self) -> Self {
+        let (shared, rng) = self.share();
+
+        Self {
+            map: Default::default(),
+            rng,
+            shared,
+            warn_unused: self.warn_unused,
+            registry: self.registry.clone(),
+            tags: self.tags.clone(),
+            tenants: BTreeMap::new(),
+            history: BTreeMap::new(),
+            #[cfg(feature = "std")]
+            recorder: None,
+            #[cfg(feature = "metrics")]
+            metrics: Default::default(),
+        }
+    }
+
+    /// Copy the entries of another context into this one
+    ///
+    /// Only the entries whose type was registered with [`register_clone`](Self::register_clone)
+    /// in the other context are copied, replacing the entries of this context with the same name
+    /// and type. This returns the number of copied entries.
+    ///
+    /// This lets fixture libraries build a context once, and tests pour its entries into their own
+    /// contexts.
+    pub fn extend_from(&mut self, other: &MainContext) -> usize {
+        let copies: Vec<_> = other
+            .entries()
+            .into_iter()
+            .filter_map(|(key, val)| {
+                let meta = other.registry.get(key.0);
+                Some((*key, meta.clone.and_then(|clone| clone(val))?, meta))
+            })
+            .collect();
+        let count = copies.len();
+        for (key, val, meta) in copies {
+            // Keep the operations of the copied types, so that this context can copy them further
+            self.registry.inherit(key.0, meta);
+            self.insert_key(key, val);
+        }
+        count
+    }
+
+    /// Return the type and name of the entries that were inserted but never read
+    ///
+    /// Entries are sorted by type name, then by name. This is useful to find values that test
+    /// setups still insert, but that no builder consumes anymore.
+    pub fn unused_entries(&self) -> Vec<(&'static str, Option<&'static str>)> {
+        let mut unused: Vec<_> = self
+            .entries()
+            .into_iter()
+            .filter(|(_, val)| !val.is_read())
+            .map(|(key, val)| (val.type_name(), key.1))
+            .collect();
+        unused.sort_unstable();
+        unused
+    }
+
+    /// Return the names of the entries starting with `prefix`, sorted and without duplicates
+    ///
+    /// Names are plain strings, but dotted paths such as `"order.customer.id"` make it easy to
+    /// group related entries under a common prefix such as `"order."`.
+    pub fn names_with_prefix(&self, prefix: &str) -> Vec<&'static str> {
+        let mut names: Vec<_> = self
+            .entries()
+            .into_iter()
+            .filter_map(|(key, _)| key.1.filter(|name| name.starts_with(prefix)))
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    /// Get the objects of type `T` whose name starts with `prefix`, sorted by name
+    pub fn get_prefixed<T: Send + Sync + 'static>(&self, prefix: &str) -> Vec<(&'static str, &T)> {
+        let mut values: Vec<_> = self
+            .entries()
+            .into_iter()
+            .filter(|(key, _)| key.0 == TypeId::of::<T>())
+            .filter_map(|(key, val)| {
+                let name = key.1.filter(|name| name.starts_with(prefix))?;
+                Some((name, val.downcast_ref()?))
+            })
+            .collect();
+        values.sort_unstable_by_key(|(name, _)| *name);
+        values
+    }
+
+    /// Remove all the entries whose name starts with `prefix`, and return how many were removed
+    ///
+    /// Entries shared with forked contexts are hidden from this context, but stay available in the
+    /// other contexts.
+    pub fn remove_prefix(&mut self, prefix: &str) -> usize {
+        let keys: Vec<Key> = self
+            .entries()
+            .into_iter()
+            .map(|(key, _)| *key)
+            .filter(|key| key.1.is_some_and(|name| name.starts_with(prefix)))
+            .collect();
+        for key in &keys {
+            self.remove_key(*key);
+        }
+        keys.len()
+    }
+
+    /// Remove all the entries from this context
+    ///
+    /// Entries shared with forked contexts stay available in the other contexts. The entries of
+    /// [tenants](Self::tenant) and the [history](Self::history) of the entries are removed too.
+    /// The random number generator is left untouched.
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.shared.clear();
+        self.tags.clear();
+        self.tenants.clear();
+        self.history.values_mut().for_each(Vec::clear);
+    }
+
+    /// Insert an object by type, and attach tags to its entry
+    ///
+    /// Tags group related entries of different types, such as all the fixtures of a feature, so
+    /// that they can be inspected with [`iter_tagged`](Self::iter_tagged) or removed together with
+    /// [`remove_tagged`](Self::remove_tagged). Tags stay attached to the entry when it is
+    /// replaced by a plain insert.
+    ///
+    /// ```
+    /// # use ctxbuilder::{ContextRead, MainContext};
+    /// let mut ctx = MainContext::new();
+    /// ctx.insert_tagged("EUR", &["billing"]);
+    /// ctx.insert_named_tagged("net_days", 30u32, &["billing", "invoices"]);
+    /// ctx.insert_named_tagged("region", "eu-west-1", &["infra"]);
+    ///
+    /// assert_eq!(ctx.iter_tagged("billing").count(), 2);
+    /// assert_eq!(ctx.remove_tagged("billing"), 2);
+    /// assert!(ctx.get_named::<u32>("net_days").is_none());
+    /// assert!(ctx.get_named::<&str>("region").is_some());
+    /// ```
+    pub fn insert_tagged<T: Send + Sync + 'static>(
+        &mut self,
+        val: T,
+        tags: &[&'static str],
+    ) -> Option<T> {
+        self.tag((TypeId::of::<T>(), None), tags);
+        self.insert(val)
+    }
+
+    /// Insert an object by name and type, and attach tags to its entry, see
+    /// [`insert_tagged`](Self::insert_tagged)
+    pub fn insert_named_tagged<T: Send + Sync + 'static>(
+        &mut self,
+        name: &'static str,
+        val: T,
+        tags: &[&'static str],
+    ) -> Option<T> {
+        self.tag((TypeId::of::<T>(), Some(name)), tags);
+        self.insert_named(name, val)
+    }
+
+    /// Iterate over the type and name of the entries with this tag
+    ///
+    /// Entries are sorted by type name, then by name.
+    pub fn iter_tagged(
+        &self,
+        tag: &str,
+    ) -> impl Iterator<Item = (&'static str, Option<&'static str>)> {
+        let mut keys: Vec<_> = self
+            .tagged_keys(tag)
+            .filter_map(|key| Some((self.visible(&key)?.type_name(), key.1)))
+            .collect();
+        keys.sort_unstable();
+        keys.into_iter()
+    }
+
+    /// Remove all the entries with this tag, and return how many were removed
+    ///
+    /// Entries shared with forked contexts are hidden from this context, but stay available in the
+    /// other contexts.
+    pub fn remove_tagged(&mut self, tag: &str) -> usize {
+        let keys: Vec<Key> = self
+            .tagged_keys(tag)
+            .filter(|key| self.visible(key).is_some())
+            .collect();
+        for key in &keys {
+            self.remove_key(*key);
+        }
+        self.tags.retain(|(_, key)| !keys.contains(key));
+        keys.len()
+    }
+
+    fn tag(&mut self, key: Key, tags: &[&'static str]) {
+        for tag in tags {
+            if !self.tags.contains(&(tag, key)) {
+                self.tags.push((tag, key));
+            }
+        }
+    }
+
+    fn tagged_keys<'t>(&'t self, tag: &'t str) -> impl Iterator<Item = Key> + 't {
+        self.tags
+            .iter()
+            .filter(move |(t, _)| *t == tag)
+            .map(|(_, key)| *key)
+    }
+
+    /// Get the stored value of a visible entry, skipping tombstones
+    pub(crate) fn visible(&self, key: &Key) -> Option<&Stored> {
+        self.get_stored(key).filter(|val| !val.is_removed())
+    }
+
+    /// Keep the previous values of an entry when it is replaced
+    ///
+    /// Once enabled, every value replaced by an insert is cloned into the history of the entry,
+    /// which [`history`](Self::history) returns. This is useful to assert on the state transitions
+    /// of a shared fixture value over the course of a scenario. Values modified in place, for
+    /// example through [`Entry::and_modify`], are not recorded.
+    ///
+    /// ```
+    /// # use ctxbuilder::{ContextRead, ContextWrite};
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// enum Status {
+    ///     Pending,
+    ///     Paid,
+    ///     Shipped,
+    /// }
+    ///
+    /// let mut ctx = ctxbuilder::ctx();
+    /// ctx.keep_history::<Status>("order");
+    /// ctx.insert_named("order", Status::Pending);
+    /// ctx.insert_named("order", Status::Paid);
+    /// ctx.insert_named("order", Status::Shipped);
+    ///
+    /// assert_eq!(
+    ///     ctx.history::<Status>("order"),
+    ///     vec![&Status::Pending, &Status::Paid]
+    /// );
+    /// assert_eq!(ctx.get_named("order"), Some(&Status::Shipped));
+    /// ```
+    pub fn keep_history<T: Clone + Send + Sync + 'static>(&mut self, name: &'static str) {
+        self.register_clone::<T>();
+        self.history
+            .entry((TypeId::of::<T>(), Some(name)))
+            .or_default();
+    }
+
+    /// Get the previous values of an entry, from the oldest to the newest
+    ///
+    /// The current value is not included. This is empty unless the history of the entry is kept
+    /// with [`keep_history`](Self::keep_history).
+    pub fn history<T: Send + Sync + 'static>(&self, name: &'static str) -> Vec<&T> {
+        self.history
+            .get(&(TypeId::of::<T>(), Some(name)))
+            .into_iter()
+            .flatten()
+            .filter_map(Stored::peek)
+            .collect()
+    }
+
+    /// Only keep the entries for which `f` returns `true`
+    ///
+    /// `f` receives the type ID and the name of each entry. Entries shared with forked contexts
+    /// are hidden from this context, but stay available in the other contexts.
+    pub fn retain<F: FnMut(TypeId, Option<&'static str>) -> bool>(&mut self, mut f: F) {
+        let keys: Vec<Key> = self
+            .entries()
+            .into_iter()
+            .map(|(key, _)| *key)
+            .filter(|key| !f(key.0, key.1))
+            .collect();
+        for key in keys {
+            self.remove_key(key);
+        }
+    }
+
+    /// Return the visible entries of this context, in no particular order
+    ///
+    /// With the `ordered` feature, entries are in insertion order, newest layer first.
+    pub(crate) fn entries(&self) -> Vec<(&Key, &Stored)> {
+        let mut seen = KeySet::default();
+        let mut entries = Vec::new();
+        for map in core::iter::once(&self.map).chain(self.shared.iter().rev().map(|m| &**m)) {
+            for (key, val) in map {
+                // Only the newest layer of an entry is visible
+                if seen.insert(*key) && !val.is_removed() {
+                    entries.push((key, val));
+                }
+            }
+        }
+        entries
+    }
+
+    /// Insert a stored value, recording it if this context is recording
+    pub(crate) fn insert_key(&mut self, key: Key, val: Stored) -> Option<Stored> {
+        #[cfg(feature = "std")]
+        if let Some(recorder) = &self.recorder {
+            recorder.insert(&key, &val, &self.registry);
+        }
+        #[cfg(feature = "metrics")]
+        self.metrics.insert(val.type_name());
+        if self.history.contains_key(&key) {
+            let clone = self.registry.get(key.0).clone;
+            let previous = self
+                .visible(&key)
+                .zip(clone)
+                .and_then(|(old, clone)| clone(old));
+            if let (Some(previous), Some(history)) = (previous, self.history.get_mut(&key)) {
+                history.push(previous);
+            }
+        }
+        self.map.insert(key, val)
+    }
+
+    /// Remove an entry, leaving a tombstone if it is shared with forked contexts
+    pub(crate) fn remove_key(&mut self, key: Key) {
+        if self.shared.iter().any(|map| map.contains_key(&key)) {
+            self.map.insert(key, Stored::removed());
+        } else {
+            remove_stored(&mut self.map, &key);
+        }
+    }
+
+    /// Report the values that were not derived from the seeded random number generator or the
+    /// [`Clock`](crate::Clock) of this context, when it is dropped
+    ///
+    /// This is useful to find out why fixtures are not reproducible. See
+    /// [`nondeterministic_sources`](Self::nondeterministic_sources). Nothing is printed if the
+    /// thread is panicking.
+    #[cfg(feature = "std")]
+    pub fn audit_determinism(mut self) -> Self {
+        self.insert(crate::audit::Audit::default());
+        self
+    }
+
+    /// Return the sources of the values that were not derived from the seeded random number
+    /// generator or the [`Clock`](crate::Clock) of this context
+    ///
+    /// Sources are only tracked for contexts created with
+    /// [`audit_determinism`](Self::audit_determinism), and listed in the order they were first
+    /// used. An [`Rng`] with a random seed is listed first.
+    #[cfg(feature = "std")]
+    pub fn nondeterministic_sources(&self) -> Vec<&'static str> {
+        let Some(audit) = self.get::<crate::audit::Audit>() else {
+            return Vec::new();
+        };
+        let mut sources = Vec::new();
+        if self.rng.is_from_entropy() {
+            sources.push("random seed, use `MainContext::seeded` or `Rng::reseed`");
+        }
+        sources.extend(audit.sources());
+        sources
+    }
+
+    /// Print the entries that were never read when this context is dropped
+    ///
+    /// See [`unused_entries`](Self::unused_entries). Nothing is printed if the thread is
+    /// panicking.
+    #[cfg(feature = "std")]
+    pub fn warn_unused(mut self) -> Self {
+        self.warn_unused = true;
+        self
+    }
+
+    /// Register `T` as cloneable, so that [`SubContext::detach`] can copy its values
+    pub fn register_clone<T: Clone + Send + Sync + 'static>(&mut self) {
+        self.registry.register_clone::<T>();
+    }
+
+    /// Register `T` as printable, so that [`ContextDiff`](crate::ContextDiff) can show its values
+    pub fn register_debug<T: core::fmt::Debug + 'static>(&mut self) {
+        self.registry.register_debug::<T>();
+    }
+
+    /// Register `T` as comparable, so that [`ContextDiff`](crate::ContextDiff) can compare its
+    /// values
+    pub fn register_eq<T: PartialEq + 'static>(&mut self) {
+        self.registry.register_eq::<T>();
+    }
+
+    /// Register `T` as displayable, so that [`Recipe`]s record its inserted values
+    #[cfg(feature = "std")]
+    pub fn register_display<T: core::fmt::Display + 'static>(&mut self) {
+        self.registry.register_display::<T>();
+    }
+
+    /// Move the entries of this context into a new shared layer
+    pub(crate) fn share(&mut self) -> (Vec<Arc<AnyMap>>, Rng) {
+        if !self.map.is_empty() {
+            let map = core::mem::take(&mut self.map);
+            self.shared.push(Arc::new(map));
+        }
+        (self.shared.clone(), self.rng.clone())
+    }
+
     /// Create a [`SubContext`] from this context
-    pub fn sub(&self) -> SubContext {
+    pub fn sub(&self) -> SubContext<'_> {
+        SubContext {
+            parent: Parent::Shared(self),
+            map: Default::default(),
+            rng: None,
+            write_through: false,
+        }
+    }
+
+    /// Create a [`SubContext`] that holds this context mutably
+    ///
+    /// This behaves like [`sub`](Self::sub), but also supports
+    /// [write-through](SubContext::set_write_through).
+    pub fn sub_mut(&mut self) -> SubContext<'_> {
         SubContext {
-            ctx: self,
+            parent: Parent::Exclusive(self),
             map: Default::default(),
+            rng: None,
+            write_through: false,
         }
     }
+
+    /// Create a [`SubContext`] with its own random number generator
+    #[cfg_attr(not(feature = "rayon"), allow(dead_code))]
+    pub(crate) fn sub_seeded(&self, seed: u64) -> SubContext<'_> {
+        SubContext {
+            parent: Parent::Shared(self),
+            map: Default::default(),
+            rng: Some(Rng::seeded(seed)),
+            write_through: false,
+        }
+    }
+
+    /// Layers of this context, with the entries inserted in it listed as `kind`
+    pub(crate) fn layers(&self, kind: LayerKind) -> impl Iterator<Item = Layer<'_>> {
+        Layer::with_shared(kind, &self.map, &self.shared)
+    }
+
+    /// Get an object by its type ID and name, for callers without `Send + Sync` bounds on `T`
+    pub(crate) fn get_any<T: 'static>(
+        &self,
+        type_id: TypeId,
+        name: Option<&'static str>,
+    ) -> Option<&T> {
+        self.get_stored(&(type_id, name))
+            .and_then(Stored::downcast_ref)
+    }
 }
 
-impl Context for MainContext {
-    fn entry<T: Send + Sync + 'static>(&mut self) -> Entry<'_, T> {
-        Entry::new(None, self.map.entry((TypeId::of::<T>(), None)))
+pub(crate) fn get_shared<'c, T: 'static>(
+    shared: &'c [Arc<AnyMap>],
+    key: &(TypeId, Option<&'static str>),
+) -> Option<&'c T> {
+    shared
+        .iter()
+        .rev()
+        .find_map(|map| map.get(key))
+        .and_then(Stored::downcast_ref)
+}
+
+impl Extend<(Option<&'static str>, BoxedValue)> for MainContext {
+    fn extend<I: IntoIterator<Item = (Option<&'static str>, BoxedValue)>>(&mut self, iter: I) {
+        for (name, val) in iter {
+            self.insert_key((val.type_id, name), val.stored);
+        }
     }
+}
 
-    fn entry_named<T: Send + Sync + 'static>(&mut self, name: &'static str) -> Entry<'_, T> {
-        Entry::new(None, self.map.entry((TypeId::of::<T>(), Some(name))))
+#[cfg(feature = "std")]
+impl Drop for MainContext {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            return;
+        }
+        for source in self.nondeterministic_sources() {
+            eprintln!("non-deterministic context value from {source}");
+        }
+        if !self.warn_unused {
+            return;
+        }
+        for (type_name, name) in self.unused_entries() {
+            match name {
+                Some(name) => eprintln!("unused context entry `{name}` of type `{type_name}`"),
+                None => eprintln!("unused context entry of type `{type_name}`"),
+            }
+        }
     }
+}
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for MainContext {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Self::from_unstructured(u)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <u64 as arbitrary::Arbitrary>::size_hint(depth)
+    }
+}
+
+impl ContextRead for MainContext {
     fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
-        self.map
-            .get(&(TypeId::of::<T>(), None))
-            .and_then(|boxed| (**boxed).downcast_ref())
+        self.get_any(TypeId::of::<T>(), None)
     }
 
     fn get_named<T: Send + Sync + 'static>(&self, name: &'static str) -> Option<&T> {
-        self.map
-            .get(&(TypeId::of::<T>(), Some(name)))
-            .and_then(|boxed| (**boxed).downcast_ref())
+        self.get_any(TypeId::of::<T>(), Some(name))
+    }
+
+    fn contains<T: Send + Sync + 'static>(&self) -> bool {
+        self.visible(&(TypeId::of::<T>(), None)).is_some()
+    }
+
+    fn contains_named<T: Send + Sync + 'static>(&self, name: &'static str) -> bool {
+        self.visible(&(TypeId::of::<T>(), Some(name))).is_some()
+    }
+
+    fn rng(&self) -> &Rng {
+        &self.rng
+    }
+
+    fn entry_keys(&self) -> Vec<(&'static str, Option<&'static str>)> {
+        self.entries()
+            .into_iter()
+            .map(|(key, val)| (val.type_name(), key.1))
+            .collect()
+    }
+
+    fn ancestors(&self) -> Ancestors<'_> {
+        Ancestors::new(self.layers(LayerKind::Local).collect())
+    }
+
+    #[cfg(feature = "std")]
+    fn recorder(&self) -> Option<&Arc<Recorder>> {
+        self.recorder.as_ref()
+    }
+
+    #[cfg(feature = "metrics")]
+    fn metrics(&self) -> Option<&Metrics> {
+        Some(&self.metrics)
+    }
+}
+
+impl ContextWrite for MainContext {
+    fn entry<T: Send + Sync + 'static>(&mut self) -> Entry<'_, T> {
+        let key = (TypeId::of::<T>(), None);
+        Entry::inherit(
+            || get_shared(&self.shared, &key),
+            self.map.entry(key),
+            &self.rng,
+        )
+    }
+
+    fn entry_named<T: Send + Sync + 'static>(&mut self, name: &'static str) -> Entry<'_, T> {
+        let key = (TypeId::of::<T>(), Some(name));
+        Entry::inherit(
+            || get_shared(&self.shared, &key),
+            self.map.entry(key),
+            &self.rng,
+        )
     }
 
     fn insert<T: Send + Sync + 'static>(&mut self, val: T) -> Option<T> {
-        self.map
-            .insert((TypeId::of::<T>(), None), Box::new(val))
-            .and_then(|boxed| boxed.downcast().ok().map(|boxed| *boxed))
+        let old = self.insert_key((TypeId::of::<T>(), None), Stored::new(val));
+        trace::insert::<T>(None, old.is_some());
+        old.and_then(Stored::into_inner)
     }
 
     fn insert_named<T: Send + Sync + 'static>(&mut self, name: &'static str, val: T) -> Option<T> {
+        let old = self.insert_key((TypeId::of::<T>(), Some(name)), Stored::new(val));
+        trace::insert::<T>(Some(name), old.is_some());
+        old.and_then(Stored::into_inner)
+    }
+
+    fn as_dyn(&mut self) -> Option<&mut dyn DynContext> {
+        Some(self)
+    }
+}
+
+impl DynContext for MainContext {
+    fn get_stored(&self, key: &Key) -> Option<&Stored> {
         self.map
-            .insert((TypeId::of::<T>(), Some(name)), Box::new(val))
-            .and_then(|boxed| boxed.downcast().ok().map(|boxed| *boxed))
+            .get(key)
+            .or_else(|| self.shared.iter().rev().find_map(|map| map.get(key)))
+    }
+
+    fn entry_stored(&mut self, key: Key) -> RawEntry<'_> {
+        RawEntry::inherit(
+            || self.shared.iter().rev().find_map(|map| map.get(&key)),
+            self.map.entry(key),
+            &self.rng,
+        )
+    }
+
+    fn insert_stored(&mut self, key: Key, val: Stored) -> Option<Stored> {
+        self.insert_key(key, val)
+    }
+
+    fn dyn_rng(&self) -> &Rng {
+        &self.rng
+    }
+
+    fn dyn_ancestors(&self) -> Ancestors<'_> {
+        self.ancestors()
+    }
+
+    #[cfg(feature = "metrics")]
+    fn dyn_metrics(&self) -> Option<&Metrics> {
+        self.metrics()
     }
 }
 
 /// Sub-context that inherits from another context
 pub struct SubContext<'c> {
-    ctx: &'c MainContext,
-    map: AnyMap,
+    parent: Parent<'c>,
+    pub(crate) map: AnyMap,
+    rng: Option<Rng>,
+    /// Insert values in the parent context rather than in this one
+    write_through: bool,
 }
 
-impl<'c> Context for SubContext<'c> {
-    fn entry<T: Send + Sync + 'static>(&mut self) -> Entry<'_, T> {
-        Entry::new(self.ctx.get(), self.map.entry((TypeId::of::<T>(), None)))
+/// Parent of a [`SubContext`]
+enum Parent<'c> {
+    Shared(&'c MainContext),
+    Exclusive(&'c mut MainContext),
+}
+
+impl Parent<'_> {
+    fn ctx(&self) -> &MainContext {
+        match self {
+            Self::Shared(ctx) => ctx,
+            Self::Exclusive(ctx) => ctx,
+        }
     }
+}
 
-    fn entry_named<T: Send + Sync + 'static>(&mut self, name: &'static str) -> Entry<'_, T> {
-        Entry::new(
-            self.ctx.get_named(name),
-            self.map.entry((TypeId::of::<T>(), Some(name))),
+impl<'c> SubContext<'c> {
+    /// Insert values in the parent context rather than in this one
+    ///
+    /// This is useful when builders running in the sub-context discover values that the rest of
+    /// the test needs. Values inserted in this context before enabling write-through stay local
+    /// overrides, unless they are replaced.
+    ///
+    /// ## Panics
+    ///
+    /// This panics if this sub-context was not created with [`MainContext::sub_mut`].
+    pub fn set_write_through(&mut self, write_through: bool) {
+        assert!(
+            matches!(self.parent, Parent::Exclusive(_)) || !write_through,
+            "write-through requires a sub-context created with `MainContext::sub_mut`"
+        );
+        self.write_through = write_through;
+    }
+
+    /// Turn this sub-context into a standalone [`MainContext`]
+    ///
+    /// The entries inserted in this sub-context are moved into the new context. Inherited entries
+    /// are shared with the parent context when they come from a [fork](MainContext::fork), or
+    /// copied if their type was registered with [`MainContext::register_clone`]. Other inherited
+    /// entries are left out.
+    ///
+    /// The new context continues from the state of the random number generator of this
+    /// sub-context.
+    pub fn detach(self) -> MainContext {
+        let parent = self.parent.ctx();
+        let mut map =
+            AnyMap::with_capacity_and_hasher(parent.map.len() + self.map.len(), Default::default());
+        for (key, val) in &parent.map {
+            if self.map.contains_key(key) {
+                continue;
+            }
+            let copy = if val.is_removed() {
+                Some(Stored::removed())
+            } else {
+                parent
+                    .registry
+                    .get(key.0)
+                    .clone
+                    .and_then(|clone| clone(val))
+            };
+            if let Some(copy) = copy {
+                map.insert(*key, copy);
+            } else if parent.shared.iter().any(|shared| shared.contains_key(key)) {
+                // Hide the older shared value that this entry was shadowing
+                map.insert(*key, Stored::removed());
+            }
+        }
+        map.extend(self.map);
+
+        let rng = self.rng.unwrap_or_else(|| parent.rng.clone());
+        let mut ctx = MainContext::from_shared(map, parent.shared.clone(), rng);
+        ctx.registry = parent.registry.clone();
+        ctx
+    }
+
+    fn sub_entry<T: Send + Sync + 'static>(&mut self, name: Option<&'static str>) -> Entry<'_, T> {
+        let key = (TypeId::of::<T>(), name);
+        if self.write_through && !self.map.contains_key(&key) {
+            let Parent::Exclusive(ctx) = &mut self.parent else {
+                unreachable!("write-through sub-contexts hold their parent mutably");
+            };
+            return match name {
+                Some(name) => ctx.entry_named(name),
+                None => ctx.entry(),
+            };
+        }
+
+        let ctx = self.parent.ctx();
+        Entry::inherit(
+            || ctx.get_any(key.0, name),
+            self.map.entry(key),
+            self.rng.as_ref().unwrap_or(&ctx.rng),
         )
     }
 
+    fn sub_insert<T: Send + Sync + 'static>(
+        &mut self,
+        name: Option<&'static str>,
+        val: T,
+    ) -> Option<T> {
+        let key = (TypeId::of::<T>(), name);
+        if let (true, Parent::Exclusive(ctx)) = (self.write_through, &mut self.parent) {
+            let local = remove_stored(&mut self.map, &key).and_then(Stored::into_inner);
+            let old = match name {
+                Some(name) => ctx.insert_named(name, val),
+                None => ctx.insert(val),
+            };
+            return local.or(old);
+        }
+
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = self.metrics() {
+            metrics.insert(core::any::type_name::<T>());
+        }
+        let old = self.map.insert(key, Stored::new(val));
+        trace::insert::<T>(name, old.is_some());
+        old.and_then(Stored::into_inner)
+    }
+}
+
+impl<'c> ContextRead for SubContext<'c> {
     fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
         self.map
             .get(&(TypeId::of::<T>(), None))
-            .and_then(|boxed| (**boxed).downcast_ref())
-            .or_else(|| self.ctx.get())
+            .and_then(Stored::downcast_ref)
+            .or_else(|| self.parent.ctx().get())
     }
 
     fn get_named<T: Send + Sync + 'static>(&self, name: &'static str) -> Option<&T> {
         self.map
             .get(&(TypeId::of::<T>(), Some(name)))
-            .and_then(|boxed| (**boxed).downcast_ref())
-            .or_else(|| self.ctx.get_named(name))
+            .and_then(Stored::downcast_ref)
+            .or_else(|| self.parent.ctx().get_named(name))
+    }
+
+    fn contains<T: Send + Sync + 'static>(&self) -> bool {
+        self.map.contains_key(&(TypeId::of::<T>(), None)) || self.parent.ctx().contains::<T>()
+    }
+
+    fn contains_named<T: Send + Sync + 'static>(&self, name: &'static str) -> bool {
+        self.map.contains_key(&(TypeId::of::<T>(), Some(name)))
+            || self.parent.ctx().contains_named::<T>(name)
+    }
+
+    fn rng(&self) -> &Rng {
+        self.rng.as_ref().unwrap_or(&self.parent.ctx().rng)
+    }
+
+    fn entry_keys(&self) -> Vec<(&'static str, Option<&'static str>)> {
+        let mut keys: Vec<_> = self
+            .map
+            .iter()
+            .map(|(key, val)| (val.type_name(), key.1))
+            .collect();
+        let parent = self.parent.ctx();
+        keys.extend(
+            parent
+                .entries()
+                .into_iter()
+                .filter(|(key, _)| !self.map.contains_key(*key))
+                .map(|(key, val)| (val.type_name(), key.1)),
+        );
+        keys
+    }
+
+    fn ancestors(&self) -> Ancestors<'_> {
+        let parent = self.parent.ctx().layers(LayerKind::Parent);
+        Ancestors::new(
+            core::iter::once(Layer::new(LayerKind::Local, &self.map))
+                .chain(parent)
+                .collect(),
+        )
+    }
+
+    #[cfg(feature = "metrics")]
+    fn metrics(&self) -> Option<&Metrics> {
+        self.parent.ctx().metrics()
+    }
+}
+
+impl<'c> ContextWrite for SubContext<'c> {
+    fn entry<T: Send + Sync + 'static>(&mut self) -> Entry<'_, T> {
+        self.sub_entry(None)
+    }
+
+    fn entry_named<T: Send + Sync + 'static>(&mut self, name: &'static str) -> Entry<'_, T> {
+        self.sub_entry(Some(name))
     }
 
     fn insert<T: Send + Sync + 'static>(&mut self, val: T) -> Option<T> {
-        self.map
-            .insert((TypeId::of::<T>(), None), Box::new(val))
-            .and_then(|boxed| boxed.downcast().ok().map(|boxed| *boxed))
+        self.sub_insert(None, val)
     }
 
     fn insert_named<T: Send + Sync + 'static>(&mut self, name: &'static str, val: T) -> Option<T> {
+        self.sub_insert(Some(name), val)
+    }
+
+    fn as_dyn(&mut self) -> Option<&mut dyn DynContext> {
+        Some(self)
+    }
+}
+
+impl<'c> DynContext for SubContext<'c> {
+    fn get_stored(&self, key: &Key) -> Option<&Stored> {
         self.map
-            .insert((TypeId::of::<T>(), Some(name)), Box::new(val))
-            .and_then(|boxed| boxed.downcast().ok().map(|boxed| *boxed))
+            .get(key)
+            .or_else(|| self.parent.ctx().get_stored(key))
     }
 
-    fn with<T: Send + Sync + 'static>(mut self, val: T) -> Self {
-        self.insert(val);
-        self
+    fn entry_stored(&mut self, key: Key) -> RawEntry<'_> {
+        if self.write_through && !self.map.contains_key(&key) {
+            let Parent::Exclusive(ctx) = &mut self.parent else {
+                unreachable!("write-through sub-contexts hold their parent mutably");
+            };
+            return ctx.entry_stored(key);
+        }
+
+        let ctx = self.parent.ctx();
+        RawEntry::inherit(
+            || ctx.get_stored(&key),
+            self.map.entry(key),
+            self.rng.as_ref().unwrap_or(&ctx.rng),
+        )
     }
 
-    fn with_named<T: Send + Sync + 'static>(mut self, name: &'static str, val: T) -> Self {
-        self.insert_named(name, val);
-        self
+    fn insert_stored(&mut self, key: Key, val: Stored) -> Option<Stored> {
+        if let (true, Parent::Exclusive(ctx)) = (self.write_through, &mut self.parent) {
+            let local = remove_stored(&mut self.map, &key);
+            let old = ctx.insert_stored(key, val);
+            return local.or(old);
+        }
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = self.metrics() {
+            metrics.insert(val.type_name());
+        }
+        self.map.insert(key, val)
     }
 
-    fn build<T: Builder>(&mut self) -> T {
-        T::build(self)
+    fn dyn_rng(&self) -> &Rng {
+        self.rng()
     }
 
-    fn build_named<T: NamedBuilder>(&mut self, name: &'static str) -> T {
-        T::build_with_name(self, name)
+    fn dyn_ancestors(&self) -> Ancestors<'_> {
+        self.ancestors()
+    }
+
+    #[cfg(feature = "metrics")]
+    fn dyn_metrics(&self) -> Option<&Metrics> {
+        self.metrics()
     }
 }
@@ -0,0 +1,55 @@
+//! Export of built collections to CSV
+//!
+//! This writes the objects built with [`Context::build_vec`](crate::Context::build_vec) as CSV
+//! rows with a header, so fixtures can be fed to external tools such as a database `COPY` or a
+//! spreadsheet. Objects are serialized with [`serde`], and their field names are used as the
+//! header, so they should be flat structs: maps and nested structs are not supported by CSV.
+//!
+//! ```
+//! # use ctxbuilder::{Builder, Context};
+//! #[derive(serde::Serialize)]
+//! struct Product {
+//!     id: u64,
+//!     name: String,
+//! }
+//!
+//! impl Builder for Product {
+//!     fn build<C: Context>(ctx: &mut C) -> Self {
+//!         let id = ctx.next_seq();
+//!         Self { id, name: format!("product-{id}") }
+//!     }
+//! }
+//!
+//! let mut ctx = ctxbuilder::ctx();
+//! let products = ctx.build_vec::<Product>(2);
+//! assert_eq!(
+//!     ctxbuilder::csv::to_string(&products).unwrap(),
+//!     "id,name\n1,product-1\n2,product-2\n"
+//! );
+//! ```
+
+use std::io;
+
+use serde::Serialize;
+
+/// Write the rows as CSV with a header into `writer`
+///
+/// Nothing is written if there are no rows, as the header comes from the first row.
+pub fn to_writer<T: Serialize, W: io::Write>(rows: &[T], writer: W) -> ::csv::Result<()> {
+    let mut writer = ::csv::Writer::from_writer(writer);
+    for row in rows {
+        writer.serialize(row)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Write the rows as CSV with a header into a string
+///
+/// See [`to_writer`].
+pub fn to_string<T: Serialize>(rows: &[T]) -> ::csv::Result<String> {
+    let mut out = Vec::new();
+    to_writer(rows, &mut out)?;
+    // Byte fields are written as they are, and may not be valid UTF-8
+    String::from_utf8(out).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err).into())
+}
@@ -0,0 +1,133 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::{Builder, Context, ParamBuilder};
+
+/// Relational dataset of parent rows, and of child rows that reference them
+///
+/// Datasets are built with [`Context::build_dataset`], which builds each parent with its
+/// [`Builder`], and a random number of children for each parent with a [`ParamBuilder`] that
+/// receives it. Children can only reference parents that exist, so the dataset keeps its
+/// referential integrity at any size, which makes it suitable for load tests and benchmarks.
+///
+/// ```
+/// # use ctxbuilder::{Builder, Context, ParamBuilder};
+/// struct Person {
+///     id: u64,
+/// }
+///
+/// impl Builder for Person {
+///     fn build<C: Context>(ctx: &mut C) -> Self {
+///         Self { id: ctx.next_seq_named("person") }
+///     }
+/// }
+///
+/// struct Pet {
+///     owner_id: u64,
+/// }
+///
+/// impl ParamBuilder<&Person> for Pet {
+///     fn build_with<C: Context>(_ctx: &mut C, owner: &Person) -> Self {
+///         Self { owner_id: owner.id }
+///     }
+/// }
+///
+/// let mut ctx = ctxbuilder::ctx();
+/// let dataset = ctx.build_dataset::<Person, Pet>(1_000, 0..4);
+/// assert_eq!(dataset.parents().len(), 1_000);
+/// for (i, person) in dataset.parents().iter().enumerate() {
+///     assert!(dataset.children_of(i).iter().all(|pet| pet.owner_id == person.id));
+/// }
+/// ```
+///
+/// With the `serde` feature, datasets can be serialized as a map with the
+/// [names](Self::with_names) of both tables as keys, to export them to JSON. With the `csv`
+/// feature, each table can be exported on its own with `csv::to_string`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dataset<P, C> {
+    parents: Vec<P>,
+    children: Vec<C>,
+    /// Index of the first child of each parent
+    offsets: Vec<usize>,
+    names: [&'static str; 2],
+}
+
+impl<P, C> Dataset<P, C> {
+    pub(crate) fn build<X: Context>(ctx: &mut X, rows: usize, children: Range<usize>) -> Self
+    where
+        P: Builder,
+        C: for<'p> ParamBuilder<&'p P>,
+    {
+        let mut dataset = Self {
+            parents: Vec::with_capacity(rows),
+            children: Vec::new(),
+            offsets: Vec::with_capacity(rows),
+            names: ["parents", "children"],
+        };
+        for _ in 0..rows {
+            let parent = ctx.build::<P>();
+            let count = ctx.rng().gen_range(children.clone());
+            dataset.offsets.push(dataset.children.len());
+            for _ in 0..count {
+                let child = ctx.build_with(&parent);
+                dataset.children.push(child);
+            }
+            dataset.parents.push(parent);
+        }
+        dataset
+    }
+
+    /// Set the names of the parent and child tables, used when serializing the dataset
+    ///
+    /// The names are `parents` and `children` by default.
+    pub fn with_names(mut self, parents: &'static str, children: &'static str) -> Self {
+        self.names = [parents, children];
+        self
+    }
+
+    /// Names of the parent and child tables
+    pub fn names(&self) -> [&'static str; 2] {
+        self.names
+    }
+
+    /// Parent rows, in the order they were built
+    pub fn parents(&self) -> &[P] {
+        &self.parents
+    }
+
+    /// Child rows, grouped by parent in the order of the parents
+    pub fn children(&self) -> &[C] {
+        &self.children
+    }
+
+    /// Child rows of the parent at `index`
+    ///
+    /// ## Panics
+    ///
+    /// This panics if `index` is out of bounds.
+    pub fn children_of(&self, index: usize) -> &[C] {
+        let end = self
+            .offsets
+            .get(index + 1)
+            .copied()
+            .unwrap_or(self.children.len());
+        &self.children[self.offsets[index]..end]
+    }
+
+    /// Return the parent and child rows
+    pub fn into_parts(self) -> (Vec<P>, Vec<C>) {
+        (self.parents, self.children)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<P: serde::Serialize, C: serde::Serialize> serde::Serialize for Dataset<P, C> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry(self.names[0], &self.parents)?;
+        map.serialize_entry(self.names[1], &self.children)?;
+        map.end()
+    }
+}
@@ -0,0 +1,168 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+use crate::{value::Stored, Key, MainContext};
+
+/// Differences between the entries of two contexts
+///
+/// This is useful to assert that a builder only touched the entries it should:
+///
+/// ```
+/// # use ctxbuilder::{Context, ContextDiff, ContextWrite};
+/// let mut ctx = ctxbuilder::ctx().with(1u32);
+/// let before = ctx.fork();
+/// ctx.insert_named("name", "alice");
+///
+/// let diff = ContextDiff::between(&before, &ctx);
+/// assert_eq!(diff.added().len(), 1);
+/// assert!(diff.changed().is_empty());
+/// ```
+///
+/// Values are compared and shown when their type was registered with
+/// [`MainContext::register_eq`] and [`MainContext::register_debug`]. Without a registered
+/// comparison, an entry is considered changed if it was replaced, even with an equal value.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ContextDiff {
+    added: Vec<DiffEntry>,
+    removed: Vec<DiffEntry>,
+    changed: Vec<DiffEntry>,
+}
+
+/// Single entry in a [`ContextDiff`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiffEntry {
+    type_name: &'static str,
+    name: Option<&'static str>,
+    before: Option<String>,
+    after: Option<String>,
+}
+
+impl ContextDiff {
+    /// Compare the entries of two contexts
+    pub fn between(before: &MainContext, after: &MainContext) -> Self {
+        let mut diff = Self::default();
+        let old = before.entries();
+        let new = after.entries();
+        for (key, val) in &new {
+            let entry = |before: Option<&Stored>| DiffEntry {
+                type_name: val.type_name(),
+                name: key.1,
+                before: before.and_then(|before| debug(before, key, after)),
+                after: debug(val, key, after),
+            };
+            match find(&old, key) {
+                None => diff.added.push(entry(None)),
+                Some(prev) if !same(prev, val, key, after) => diff.changed.push(entry(Some(prev))),
+                Some(_) => {}
+            }
+        }
+        for (key, val) in &old {
+            if find(&new, key).is_none() {
+                diff.removed.push(DiffEntry {
+                    type_name: val.type_name(),
+                    name: key.1,
+                    before: debug(val, key, before),
+                    after: None,
+                });
+            }
+        }
+
+        for entries in [&mut diff.added, &mut diff.removed, &mut diff.changed] {
+            entries.sort_unstable_by_key(|entry| (entry.type_name, entry.name));
+        }
+        diff
+    }
+
+    /// Entries that are only in the second context
+    pub fn added(&self) -> &[DiffEntry] {
+        &self.added
+    }
+
+    /// Entries that are only in the first context
+    pub fn removed(&self) -> &[DiffEntry] {
+        &self.removed
+    }
+
+    /// Entries that are in both contexts, with different values
+    pub fn changed(&self) -> &[DiffEntry] {
+        &self.changed
+    }
+
+    /// Whether both contexts have the same entries
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl DiffEntry {
+    /// Type name of the entry
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Name of the entry, if it is a named entry
+    pub fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+
+    /// Value in the first context, if its type was registered with
+    /// [`MainContext::register_debug`]
+    pub fn before(&self) -> Option<&str> {
+        self.before.as_deref()
+    }
+
+    /// Value in the second context, if its type was registered with
+    /// [`MainContext::register_debug`]
+    pub fn after(&self) -> Option<&str> {
+        self.after.as_deref()
+    }
+}
+
+fn find<'c>(entries: &[(&Key, &'c Stored)], key: &Key) -> Option<&'c Stored> {
+    entries.iter().find(|(k, _)| *k == key).map(|(_, val)| *val)
+}
+
+/// Whether two stored values are the same
+fn same(a: &Stored, b: &Stored, key: &Key, ctx: &MainContext) -> bool {
+    if core::ptr::eq(a, b) {
+        return true;
+    }
+    ctx.registry
+        .get(key.0)
+        .eq
+        .and_then(|eq| eq(a, b))
+        .unwrap_or(false)
+}
+
+fn debug(val: &Stored, key: &Key, ctx: &MainContext) -> Option<String> {
+    ctx.registry.get(key.0).debug.and_then(|debug| debug(val))
+}
+
+impl fmt::Display for ContextDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (sign, entries) in [
+            ('+', &self.added),
+            ('-', &self.removed),
+            ('~', &self.changed),
+        ] {
+            for entry in entries {
+                writeln!(f, "{sign} {entry}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DiffEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name {
+            Some(name) => write!(f, "`{name}`: {}", self.type_name)?,
+            None => write!(f, "{}", self.type_name)?,
+        }
+        match (&self.before, &self.after) {
+            (Some(before), Some(after)) => write!(f, " ({before} -> {after})"),
+            (Some(val), None) | (None, Some(val)) => write!(f, " ({val})"),
+            (None, None) => Ok(()),
+        }
+    }
+}
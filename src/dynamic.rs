@@ -0,0 +1,197 @@
+use core::any::TypeId;
+
+use crate::{
+    entry::InnerEntry, trace, value::Stored, Ancestors, BoxedValue, ContextRead, ContextWrite,
+    Entry, Key, Rng,
+};
+
+/// Type-erased access to the entries of a context, by key
+///
+/// Builders are generic over the context, so code that stores builders for later use (such as
+/// [`Context::bind`](crate::Context::bind)) runs them with a [`DynRef`] over this trait instead.
+///
+/// This trait is object safe, so contexts can also be passed across crate boundaries as
+/// `&mut dyn DynContext`, for example to the plugins of a test harness. Contexts provide one with
+/// [`ContextWrite::as_dyn`], and `as_context` turns it back into a
+/// [`Context`](crate::Context):
+///
+/// ```
+/// # use core::any::TypeId;
+/// # use ctxbuilder::{BoxedValue, Context, ContextRead, ContextWrite, DynContext, MainContext};
+/// fn plugin(ctx: &mut dyn DynContext) {
+///     ctx.insert_boxed(Some("plugin"), BoxedValue::new("ready"));
+///     let id = ctx.as_context().build::<uuid::Uuid>();
+///     ctx.as_context().insert(id);
+/// }
+///
+/// let mut ctx = MainContext::new();
+/// plugin(ctx.as_dyn().unwrap());
+///
+/// assert_eq!(ctx.get_named::<&str>("plugin"), Some(&"ready"));
+/// let erased = ctx.as_dyn().unwrap().get_erased(TypeId::of::<uuid::Uuid>(), None).unwrap();
+/// assert_eq!(erased.type_name(), "uuid::Uuid");
+/// ```
+///
+/// The methods of this trait use types that can only be created by this crate, so it is only
+/// implemented by the contexts of this crate.
+pub trait DynContext {
+    /// Get the stored value for a key, including tombstones
+    fn get_stored(&self, key: &Key) -> Option<&Stored>;
+
+    /// Get the parts to create an [`Entry`] for a key
+    fn entry_stored(&mut self, key: Key) -> RawEntry<'_>;
+
+    /// Insert a stored value, returning the previous one
+    fn insert_stored(&mut self, key: Key, val: Stored) -> Option<Stored>;
+
+    /// Get the random number generator of the context
+    fn dyn_rng(&self) -> &Rng;
+
+    /// Iterate over the layers of the context
+    fn dyn_ancestors(&self) -> Ancestors<'_>;
+
+    /// Counters of the builds and inserts of the context
+    #[doc(hidden)]
+    #[cfg(feature = "metrics")]
+    fn dyn_metrics(&self) -> Option<&crate::metrics::Metrics> {
+        None
+    }
+}
+
+impl dyn DynContext + '_ {
+    /// Use this type-erased context as a [`Context`](crate::Context)
+    pub fn as_context(&mut self) -> DynRef<'_> {
+        DynRef(self)
+    }
+
+    /// Get the stored value for a type ID and name, if the entry exists
+    pub fn get_erased(&self, type_id: TypeId, name: Option<&'static str>) -> Option<&Stored> {
+        self.get_stored(&(type_id, name))
+            .filter(|val| !val.is_removed())
+    }
+
+    /// Insert a type-erased value by name, returning the previous one
+    pub fn insert_boxed(
+        &mut self,
+        name: Option<&'static str>,
+        val: BoxedValue,
+    ) -> Option<BoxedValue> {
+        let type_id = val.type_id;
+        self.insert_stored((type_id, name), val.stored)
+            .filter(|old| !old.is_removed())
+            .map(|stored| BoxedValue { type_id, stored })
+    }
+}
+
+/// Untyped parts of an [`Entry`]
+pub struct RawEntry<'c> {
+    pub(crate) main: Option<&'c Stored>,
+    pub(crate) inner: InnerEntry<'c>,
+    pub(crate) rng: &'c Rng,
+    /// Entry of a [`FrozenContext`](crate::FrozenContext), that can't be modified
+    pub(crate) frozen: bool,
+}
+
+impl<'c> RawEntry<'c> {
+    /// Create the parts of an entry, only looking up the inherited value if the inner entry is
+    /// vacant, like [`Entry::inherit`]
+    pub(crate) fn inherit<F: FnOnce() -> Option<&'c Stored>>(
+        main: F,
+        inner: InnerEntry<'c>,
+        rng: &'c Rng,
+    ) -> Self {
+        let main = match &inner {
+            InnerEntry::Occupied(_) => None,
+            InnerEntry::Vacant(_) => main(),
+        };
+        Self {
+            main,
+            inner,
+            rng,
+            frozen: false,
+        }
+    }
+}
+
+/// Context over a [`DynContext`]
+pub struct DynRef<'c>(pub(crate) &'c mut dyn DynContext);
+
+impl<'c> DynRef<'c> {
+    /// Create a context over a type-erased one
+    pub fn new(ctx: &'c mut dyn DynContext) -> Self {
+        Self(ctx)
+    }
+}
+
+impl DynRef<'_> {
+    fn dyn_entry<T: Send + Sync + 'static>(&mut self, name: Option<&'static str>) -> Entry<'_, T> {
+        let raw = self.0.entry_stored((TypeId::of::<T>(), name));
+        let entry = Entry::new(raw.main.and_then(Stored::downcast_ref), raw.inner, raw.rng);
+        if raw.frozen {
+            entry.frozen()
+        } else {
+            entry
+        }
+    }
+
+    fn dyn_insert<T: Send + Sync + 'static>(
+        &mut self,
+        name: Option<&'static str>,
+        val: T,
+    ) -> Option<T> {
+        let old = self
+            .0
+            .insert_stored((TypeId::of::<T>(), name), Stored::new(val));
+        trace::insert::<T>(name, old.is_some());
+        old.and_then(Stored::into_inner)
+    }
+}
+
+impl ContextRead for DynRef<'_> {
+    fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.0
+            .get_stored(&(TypeId::of::<T>(), None))
+            .and_then(Stored::downcast_ref)
+    }
+
+    fn get_named<T: Send + Sync + 'static>(&self, name: &'static str) -> Option<&T> {
+        self.0
+            .get_stored(&(TypeId::of::<T>(), Some(name)))
+            .and_then(Stored::downcast_ref)
+    }
+
+    fn rng(&self) -> &Rng {
+        self.0.dyn_rng()
+    }
+
+    fn ancestors(&self) -> Ancestors<'_> {
+        self.0.dyn_ancestors()
+    }
+
+    #[cfg(feature = "metrics")]
+    fn metrics(&self) -> Option<&crate::metrics::Metrics> {
+        self.0.dyn_metrics()
+    }
+}
+
+impl ContextWrite for DynRef<'_> {
+    fn entry<T: Send + Sync + 'static>(&mut self) -> Entry<'_, T> {
+        self.dyn_entry(None)
+    }
+
+    fn entry_named<T: Send + Sync + 'static>(&mut self, name: &'static str) -> Entry<'_, T> {
+        self.dyn_entry(Some(name))
+    }
+
+    fn insert<T: Send + Sync + 'static>(&mut self, val: T) -> Option<T> {
+        self.dyn_insert(None, val)
+    }
+
+    fn insert_named<T: Send + Sync + 'static>(&mut self, name: &'static str, val: T) -> Option<T> {
+        self.dyn_insert(Some(name), val)
+    }
+
+    fn as_dyn(&mut self) -> Option<&mut dyn DynContext> {
+        Some(&mut *self.0)
+    }
+}
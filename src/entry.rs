@@ -1,85 +1,387 @@
-use std::{
-    any::{Any, TypeId},
-    collections::hash_map,
-    marker::PhantomData,
+use core::{any::TypeId, marker::PhantomData, ops::Range};
+
+use crate::{
+    rng::pick_weighted, trace, value::Stored, Builder, Context, ContextRead, HashMapEntry, Rng,
+    SampleUniform,
 };
 
-type InnerEntry<'c> =
-    hash_map::Entry<'c, (TypeId, Option<&'static str>), Box<dyn Any + Send + Sync>>;
+pub(crate) type InnerEntry<'c> = HashMapEntry<'c, (TypeId, Option<&'static str>), Stored>;
+
+/// Whether an [`Entry`] method inserted a new value or found an existing one
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntryState {
+    /// The entry was empty, and the default value was inserted
+    Inserted,
+    /// The entry already had a value
+    Existing,
+}
+
+impl EntryState {
+    /// Whether the default value was inserted
+    pub fn is_inserted(self) -> bool {
+        self == Self::Inserted
+    }
+}
 
 /// View into a single entry in a context
 #[derive(Debug)]
 pub struct Entry<'c, T> {
     main: Option<&'c T>,
     inner: InnerEntry<'c>,
+    rng: &'c Rng,
+    /// Entry of a [`FrozenContext`](crate::FrozenContext), that can't be modified
+    frozen: bool,
     _phantom_data: PhantomData<T>,
 }
 
 impl<'c, T> Entry<'c, T> {
-    pub(crate) fn new(main: Option<&'c T>, inner: InnerEntry<'c>) -> Self {
+    pub(crate) fn new(main: Option<&'c T>, inner: InnerEntry<'c>, rng: &'c Rng) -> Self {
+        let hit = match &inner {
+            InnerEntry::Occupied(entry) => !entry.get().is_removed(),
+            InnerEntry::Vacant(_) => main.is_some(),
+        };
+        trace::entry::<T>(inner.key().1, hit);
         Self {
             main,
             inner,
+            rng,
+            frozen: false,
             _phantom_data: PhantomData,
         }
     }
+
+    /// Mark this entry as belonging to a frozen context, so that modifying it panics
+    pub(crate) fn frozen(mut self) -> Self {
+        self.frozen = true;
+        self
+    }
+
+    /// Create an entry, only looking up the inherited value if the inner entry is vacant
+    ///
+    /// An occupied inner entry always shadows the inherited one, so this skips the lookups in the
+    /// parent layers when the context already holds the entry.
+    pub(crate) fn inherit<F: FnOnce() -> Option<&'c T>>(
+        main: F,
+        inner: InnerEntry<'c>,
+        rng: &'c Rng,
+    ) -> Self {
+        let main = match &inner {
+            InnerEntry::Occupied(_) => None,
+            InnerEntry::Vacant(_) => main(),
+        };
+        Self::new(main, inner, rng)
+    }
 }
 
 impl<'c, T: Send + Sync + 'static> Entry<'c, T> {
     /// Ensures a value is in the entry by inserting the default if empty, and returns a reference
     /// to the value in the entry
     pub fn or_insert(self, default: T) -> &'c T {
-        match (self.main, self.inner) {
-            // entry is vacant, but main contains something: return main
-            (Some(main), InnerEntry::Vacant(_)) => main,
-            // entry is occuped: return inner
-            // main is empty: insert inner
-            (_, inner) => inner
-                .or_insert(Box::new(default))
-                .downcast_ref()
-                .expect("downcast_ref on T"),
-        }
+        self.or_insert_with(|| default)
     }
 
     /// Ensures a value is in the entry by inserting the result of the default function if empty,
     /// and returns a reference to the value in the entry
     pub fn or_insert_with<F: FnOnce() -> T>(self, default: F) -> &'c T {
+        self.or_insert_with_entry(default).0
+    }
+
+    /// Ensures a value is in the entry by inserting the default if empty, and returns a reference
+    /// to the value in the entry along with whether it was inserted
+    pub fn or_insert_entry(self, default: T) -> (&'c T, EntryState) {
+        self.or_insert_with_entry(|| default)
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the default function if empty,
+    /// and returns a reference to the value in the entry along with whether it was inserted
+    pub fn or_insert_with_entry<F: FnOnce() -> T>(self, default: F) -> (&'c T, EntryState) {
+        let name = self.inner.key().1;
         match (self.main, self.inner) {
             // entry is vacant, but main contains something: return main
-            (Some(main), InnerEntry::Vacant(_)) => main,
-            // entry is occuped: return inner
+            (Some(main), InnerEntry::Vacant(_)) => (main, EntryState::Existing),
+            // entry is occupied: return inner
+            (_, InnerEntry::Occupied(entry)) if !entry.get().is_removed() => {
+                (entry.into_mut().expect_ref(name), EntryState::Existing)
+            }
+            // entry was removed, hiding main: replace inner
+            (_, InnerEntry::Occupied(mut entry)) => {
+                entry.insert(Stored::new(default()));
+                (entry.into_mut().expect_ref(name), EntryState::Inserted)
+            }
             // main is empty: insert inner
-            (_, inner) => inner
-                .or_insert_with(|| Box::new(default()))
-                .downcast_ref()
-                .expect("downcast_ref on T"),
+            (None, InnerEntry::Vacant(entry)) => (
+                entry.insert(Stored::new(default())).expect_ref(name),
+                EntryState::Inserted,
+            ),
         }
     }
 
+    /// Ensures a value is in the entry by inserting the result of the default function if empty,
+    /// and returns a reference to the value in the entry
+    ///
+    /// The default function receives the context's [`Rng`].
+    pub fn or_insert_with_rng<F: FnOnce(&Rng) -> T>(self, default: F) -> &'c T {
+        let rng = self.rng;
+        self.or_insert_with(|| default(rng))
+    }
+
     /// Provides in-place mutable access to an occupied entry before any potential inserts into the
     /// context
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the entry belongs to a [`FrozenContext`](crate::FrozenContext).
     pub fn and_modify<F: FnOnce(&mut T)>(self, f: F) -> Self {
+        let name = self.inner.key().1;
+        if self.frozen {
+            match name {
+                Some(name) => panic!(
+                    "cannot modify `{name}` of type `{}` in a frozen context",
+                    core::any::type_name::<T>()
+                ),
+                None => panic!(
+                    "cannot modify `{}` in a frozen context",
+                    core::any::type_name::<T>()
+                ),
+            }
+        }
         Entry::new(
             // Since `and_modify` ensures we transition into `Occupied` state, we don't need to
             // keep the inherited value.
             None,
-            self.inner
-                .and_modify(|v| f(v.downcast_mut().expect("downcast_mut on T"))),
+            self.inner.and_modify(|v| {
+                if !v.is_removed() {
+                    f(v.expect_mut(name))
+                }
+            }),
+            self.rng,
         )
     }
 }
 
+impl<'c, T: Clone + Send + Sync + 'static> Entry<'c, T> {
+    /// Ensures a value is in the entry by inserting one of the weighted values if empty, and
+    /// returns a reference to the value in the entry
+    ///
+    /// The value is picked with the context's [`Rng`].
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the entry is empty and the sum of the weights is zero.
+    pub fn or_insert_weighted(self, weights: &[(T, u32)]) -> &'c T {
+        self.or_insert_with_rng(|rng| pick_weighted(weights, rng).clone())
+    }
+}
+
+impl<'c, T: SampleUniform + Send + Sync + 'static> Entry<'c, T> {
+    /// Ensures a value is in the entry by inserting a random value in the range if empty, and
+    /// returns a reference to the value in the entry
+    ///
+    /// The value is generated with the context's [`Rng`].
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the entry is empty and the range is empty.
+    pub fn or_insert_in_range(self, range: Range<T>) -> &'c T {
+        self.or_insert_with_rng(|rng| rng.gen_range(range))
+    }
+}
+
+#[cfg(feature = "regex")]
+impl<'c> Entry<'c, alloc::string::String> {
+    /// Ensures a value is in the entry by inserting a random string matching the regular
+    /// expression if empty, and returns a reference to the value in the entry
+    ///
+    /// The string is generated with the context's [`Rng`], see
+    /// [`Context::string_matching`](crate::Context::string_matching).
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the entry is empty and the pattern is invalid, or can't match any string.
+    pub fn or_insert_matching(self, pattern: &str) -> &'c alloc::string::String {
+        self.or_insert_with_rng(|rng| crate::pattern::string_matching(rng, pattern))
+    }
+}
+
 impl<'c, T: Default + Send + Sync + 'static> Entry<'c, T> {
     /// Ensures a value is in the entry by inserting the default value if empty, and returns a
     /// reference to the value in the entry
     pub fn or_default(self) -> &'c T {
-        // We need to use `or_insert` here, because we need to build a `Box` for `T` specifically
-        #[allow(clippy::unwrap_or_default)]
-        self.main.unwrap_or_else(|| {
-            self.inner
-                .or_insert(Box::<T>::default())
-                .downcast_ref()
-                .expect("downcast_ref on T")
-        })
+        self.or_insert_with(T::default)
+    }
+}
+
+/// Entry whose default value can depend on the rest of the context
+///
+/// Unlike an [`Entry`], which borrows the context until it is used, the lookup is deferred until
+/// the default value is needed. This lets the default function read other entries and build
+/// other objects. See [`Context::deferred_entry`].
+pub struct DeferredEntry<'c, C, T> {
+    ctx: &'c mut C,
+    name: Option<&'static str>,
+    _phantom_data: PhantomData<fn() -> T>,
+}
+
+impl<'c, C: Context, T: Send + Sync + 'static> DeferredEntry<'c, C, T> {
+    pub(crate) fn new(ctx: &'c mut C, name: Option<&'static str>) -> Self {
+        Self {
+            ctx,
+            name,
+            _phantom_data: PhantomData,
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the default function if empty,
+    /// and returns a reference to the value in the entry
+    ///
+    /// The default function receives the context.
+    pub fn or_insert_with_ctx<F: FnOnce(&mut C) -> T>(self, default: F) -> &'c T {
+        let exists = match self.name {
+            Some(name) => self.ctx.contains_named::<T>(name),
+            None => self.ctx.contains::<T>(),
+        };
+        let val = (!exists).then(|| default(&mut *self.ctx));
+        let entry = match self.name {
+            Some(name) => self.ctx.entry_named::<T>(name),
+            None => self.ctx.entry::<T>(),
+        };
+        entry.or_insert_with(|| val.expect("entry was empty when looked up"))
+    }
+}
+
+impl<'c, C: Context, T: Builder + Send + Sync + 'static> DeferredEntry<'c, C, T> {
+    /// Ensures a value is in the entry by building one with the [`Builder`] implementation of `T`
+    /// if empty, and returns a reference to the value in the entry
+    ///
+    /// This is the entry counterpart of [`Context::get_or_build`], which also works for named
+    /// entries:
+    ///
+    /// ```
+    /// # use ctxbuilder::{Builder, Context};
+    /// struct Account {
+    ///     id: u64,
+    /// }
+    ///
+    /// impl Builder for Account {
+    ///     fn build<C: Context>(ctx: &mut C) -> Self {
+    ///         Self { id: ctx.next_seq() }
+    ///     }
+    /// }
+    ///
+    /// let mut ctx = ctxbuilder::ctx();
+    /// let billing = ctx.deferred_entry_named::<Account>("billing").or_build().id;
+    /// let shipping = ctx.deferred_entry_named::<Account>("shipping").or_build().id;
+    /// assert_ne!(billing, shipping);
+    /// assert_eq!(ctx.deferred_entry_named::<Account>("billing").or_build().id, billing);
+    /// ```
+    pub fn or_build(self) -> &'c T {
+        self.or_insert_with_ctx(|ctx| ctx.build())
+    }
+}
+
+/// Reservation of an entry, that doesn't borrow the context
+///
+/// An [`Entry`] borrows the context until it is used, so builders can't build other objects
+/// while holding one. A reservation only records whether the entry was vacant when it was
+/// created, so the builder can keep using the context, and fill the entry afterwards. See
+/// [`Context::reserve`].
+///
+/// ```
+/// # use ctxbuilder::{Builder, Context};
+/// struct Owner {
+///     id: u64,
+/// }
+///
+/// impl Builder for Owner {
+///     fn build<C: Context>(ctx: &mut C) -> Self {
+///         Self { id: ctx.next_seq() }
+///     }
+/// }
+///
+/// struct Pet {
+///     owner_id: u64,
+/// }
+///
+/// impl Builder for Pet {
+///     fn build<C: Context>(ctx: &mut C) -> Self {
+///         let label = ctx.reserve_named::<String>("label");
+///         let owner = ctx.build::<Owner>();
+///         if label.is_vacant() {
+///             label.or_insert(ctx, format!("pet of {}", owner.id));
+///         }
+///         Self { owner_id: owner.id }
+///     }
+/// }
+///
+/// let mut ctx = ctxbuilder::ctx();
+/// let pet = ctx.build::<Pet>();
+/// assert_eq!(ctx.reserve_named::<String>("label").get(&ctx).unwrap(), "pet of 1");
+/// ```
+pub struct Reservation<T> {
+    name: Option<&'static str>,
+    vacant: bool,
+    _phantom_data: PhantomData<fn() -> T>,
+}
+
+impl<T: Send + Sync + 'static> Reservation<T> {
+    pub(crate) fn new<C: ContextRead + ?Sized>(ctx: &C, name: Option<&'static str>) -> Self {
+        let vacant = match name {
+            Some(name) => !ctx.contains_named::<T>(name),
+            None => !ctx.contains::<T>(),
+        };
+        Self {
+            name,
+            vacant,
+            _phantom_data: PhantomData,
+        }
+    }
+
+    /// Whether the entry was vacant when it was reserved
+    pub fn is_vacant(&self) -> bool {
+        self.vacant
+    }
+
+    /// Get the value of the entry in the context, if any
+    pub fn get<'c, C: ContextRead + ?Sized>(&self, ctx: &'c C) -> Option<&'c T> {
+        match self.name {
+            Some(name) => ctx.get_named(name),
+            None => ctx.get(),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the default if empty, and returns a reference
+    /// to the value in the entry
+    ///
+    /// If the entry was filled since it was reserved, for example by a nested build, the existing
+    /// value is kept.
+    pub fn or_insert<C: Context>(self, ctx: &mut C, default: T) -> &T {
+        self.or_insert_with(ctx, |_| default)
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the default function if empty,
+    /// and returns a reference to the value in the entry
+    ///
+    /// The default function receives the context, like
+    /// [`DeferredEntry::or_insert_with_ctx`].
+    pub fn or_insert_with<C: Context, F: FnOnce(&mut C) -> T>(self, ctx: &mut C, default: F) -> &T {
+        DeferredEntry::new(ctx, self.name).or_insert_with_ctx(default)
+    }
+}
+
+impl<T> Clone for Reservation<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Reservation<T> {}
+
+impl<T> core::fmt::Debug for Reservation<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Reservation")
+            .field("type", &core::any::type_name::<T>())
+            .field("name", &self.name)
+            .field("vacant", &self.vacant)
+            .finish()
     }
 }
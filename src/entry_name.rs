@@ -0,0 +1,50 @@
+/// Name of context entries, checked at compile time
+///
+/// String names are easy to mistype, and a typo silently creates a different entry. Entry names
+/// declared with [`entry_name!`](crate::entry_name) are types instead, so a typo fails to compile.
+/// They are used with [`ContextRead::get_const`](crate::ContextRead::get_const),
+/// [`ContextWrite::entry_const`](crate::ContextWrite::entry_const) and
+/// [`ContextWrite::insert_const`](crate::ContextWrite::insert_const), and can be passed as a
+/// [`Name`](crate::Name) to [`NamedBuilder`](crate::NamedBuilder)s.
+///
+/// Const generic `&'static str` parameters are not available on stable Rust, which is why names
+/// are types rather than string literals.
+pub trait EntryName {
+    /// Name of the entries
+    const NAME: &'static str;
+}
+
+/// Declare types implementing [`EntryName`]
+///
+/// ```
+/// # use ctxbuilder::{ContextRead, ContextWrite};
+/// ctxbuilder::entry_name! {
+///     /// Person in the test fixtures
+///     pub Person = "person";
+/// }
+///
+/// let mut ctx = ctxbuilder::ctx();
+/// ctx.insert_const::<Person, _>(42u64);
+/// assert_eq!(ctx.get_named::<u64>("person"), Some(&42));
+/// assert_eq!(ctx.get_const::<Person, u64>(), Some(&42));
+/// ```
+#[macro_export]
+macro_rules! entry_name {
+    ($($(#[$attr:meta])* $vis:vis $ident:ident = $name:literal;)*) => {
+        $(
+            $(#[$attr])*
+            #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+            $vis struct $ident;
+
+            impl $crate::EntryName for $ident {
+                const NAME: &'static str = $name;
+            }
+
+            impl $crate::Name for $ident {
+                fn as_str(&self) -> ::core::option::Option<&'static str> {
+                    ::core::option::Option::Some($name)
+                }
+            }
+        )*
+    };
+}
@@ -0,0 +1,52 @@
+use core::{any::type_name, fmt};
+
+use crate::ContextRead;
+
+/// Trait to extract a typed snapshot of the entries in a context
+pub trait FromContext: Sized {
+    /// Extract a new object from the context
+    fn from_context<C: ContextRead>(ctx: &C) -> Result<Self, MissingEntry>;
+}
+
+/// Error returned when an entry is missing from a context
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MissingEntry {
+    type_name: &'static str,
+    name: Option<&'static str>,
+}
+
+impl MissingEntry {
+    /// Create a new [`MissingEntry`] for an entry of type `T`
+    pub fn new<T: ?Sized>(name: Option<&'static str>) -> Self {
+        Self::from_parts(type_name::<T>(), name)
+    }
+
+    pub(crate) fn from_parts(type_name: &'static str, name: Option<&'static str>) -> Self {
+        Self { type_name, name }
+    }
+
+    /// Type name of the missing entry
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Name of the missing entry, if it was looked up by name
+    pub fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+}
+
+impl fmt::Display for MissingEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name {
+            Some(name) => write!(
+                f,
+                "missing context entry `{name}` of type `{}`",
+                self.type_name
+            ),
+            None => write!(f, "missing context entry of type `{}`", self.type_name),
+        }
+    }
+}
+
+impl core::error::Error for MissingEntry {}
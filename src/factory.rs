@@ -0,0 +1,256 @@
+//! Composable builder values
+//!
+//! [`Builder`] and [`NamedBuilder`] are implemented on the types they build. A [`Factory`] is a
+//! value that builds objects from a context, which makes it possible to compose builders without
+//! writing new trait implementations:
+//!
+//! ```
+//! use ctxbuilder::{prelude::*, Builder, Context};
+//!
+//! #[derive(Clone, Copy)]
+//! struct Person {
+//!     age: u32,
+//! }
+//!
+//! impl Builder for Person {
+//!     fn build<C: Context>(ctx: &mut C) -> Self {
+//!         Self {
+//!             age: *ctx.entry_named("age").or_insert(42),
+//!         }
+//!     }
+//! }
+//!
+//! struct PersonDto {
+//!     age: String,
+//! }
+//!
+//! let mut ctx = ctxbuilder::ctx();
+//! let dto = ctx.make(Person::builder().map(|p| PersonDto {
+//!     age: p.age.to_string(),
+//! }));
+//! assert_eq!(dto.age, "42");
+//! ```
+
+use alloc::sync::Arc;
+use core::{fmt, marker::PhantomData};
+
+use crate::{bind, dynamic::DynRef, Builder, Context, Name, NamedBuilder};
+
+/// Value that builds objects from a [`Context`]
+pub trait Factory: Sized {
+    /// Type of the objects built by this factory
+    type Output;
+
+    /// Build a new object with the context
+    fn make<C: Context>(&self, ctx: &mut C) -> Self::Output;
+
+    /// Transform the objects built by this factory
+    fn map<U, F: Fn(Self::Output) -> U>(self, f: F) -> Map<Self, F> {
+        Map { factory: self, f }
+    }
+
+    /// Build objects with a second factory created from the objects built by this factory
+    ///
+    /// Both factories use the same context.
+    fn and_then<B: Factory, F: Fn(Self::Output) -> B>(self, f: F) -> AndThen<Self, F> {
+        AndThen { factory: self, f }
+    }
+
+    /// Build pairs of objects with this factory and another one
+    ///
+    /// Both factories use the same context, this factory building its object first.
+    fn zip<B: Factory>(self, other: B) -> Zip<Self, B> {
+        Zip { a: self, b: other }
+    }
+}
+
+impl<F: Factory> Factory for &F {
+    type Output = F::Output;
+
+    fn make<C: Context>(&self, ctx: &mut C) -> Self::Output {
+        (**self).make(ctx)
+    }
+}
+
+/// Extension trait to create [`Factory`] values from [`Builder`]s and [`NamedBuilder`]s
+pub trait BuilderExt: Sized {
+    /// Create a [`Factory`] using the [`Builder`] implementation of this type
+    fn builder() -> Of<Self>
+    where
+        Self: Builder,
+    {
+        Of(PhantomData)
+    }
+
+    /// Create a [`Factory`] using the [`NamedBuilder`] implementation of this type
+    fn named_builder<N: Name>(name: N) -> Named<Self, N>
+    where
+        Self: NamedBuilder<N>,
+    {
+        Named(name, PhantomData)
+    }
+}
+
+impl<T> BuilderExt for T {}
+
+/// Factory using the [`Builder`] implementation of `T`
+///
+/// See [`BuilderExt::builder`].
+#[derive(Debug)]
+pub struct Of<T>(PhantomData<fn() -> T>);
+
+impl<T> Clone for Of<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Of<T> {}
+
+impl<T: Builder> Factory for Of<T> {
+    type Output = T;
+
+    fn make<C: Context>(&self, ctx: &mut C) -> T {
+        ctx.build()
+    }
+}
+
+/// Factory using the [`NamedBuilder`] implementation of `T`
+///
+/// See [`BuilderExt::named_builder`].
+#[derive(Debug)]
+pub struct Named<T, N = &'static str>(N, PhantomData<fn() -> T>);
+
+impl<T, N: Name> Clone for Named<T, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, N: Name> Copy for Named<T, N> {}
+
+impl<T: NamedBuilder<N>, N: Name> Factory for Named<T, N> {
+    type Output = T;
+
+    fn make<C: Context>(&self, ctx: &mut C) -> T {
+        ctx.build_named(self.0)
+    }
+}
+
+/// Factory transforming the objects of another factory
+///
+/// See [`Factory::map`].
+#[derive(Clone, Copy, Debug)]
+pub struct Map<B, F> {
+    factory: B,
+    f: F,
+}
+
+impl<B: Factory, U, F: Fn(B::Output) -> U> Factory for Map<B, F> {
+    type Output = U;
+
+    fn make<C: Context>(&self, ctx: &mut C) -> U {
+        (self.f)(self.factory.make(ctx))
+    }
+}
+
+/// Factory chaining two factories
+///
+/// See [`Factory::and_then`].
+#[derive(Clone, Copy, Debug)]
+pub struct AndThen<B, F> {
+    factory: B,
+    f: F,
+}
+
+impl<A: Factory, B: Factory, F: Fn(A::Output) -> B> Factory for AndThen<A, F> {
+    type Output = B::Output;
+
+    fn make<C: Context>(&self, ctx: &mut C) -> B::Output {
+        (self.f)(self.factory.make(ctx)).make(ctx)
+    }
+}
+
+/// Factory building pairs of objects
+///
+/// See [`Factory::zip`].
+#[derive(Clone, Copy, Debug)]
+pub struct Zip<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A: Factory, B: Factory> Factory for Zip<A, B> {
+    type Output = (A::Output, B::Output);
+
+    fn make<C: Context>(&self, ctx: &mut C) -> Self::Output {
+        let a = self.a.make(ctx);
+        (a, self.b.make(ctx))
+    }
+}
+
+type MakeFn<T> = dyn Fn(&mut DynRef<'_>) -> T + Send + Sync;
+
+/// Factory backed by a closure, for one-off fixture shapes
+///
+/// This avoids defining a type and a [`Builder`] implementation for objects that are only built
+/// in a few tests. Builders are generic over the context, which closures can't be, so the
+/// closure receives a type-erased context instead. For a closure that only runs once, see
+/// [`Context::build_fn`].
+///
+/// [`FnBuilder`]s are cheap to clone, so they can be stored in a context and shared with the
+/// tests that use it:
+///
+/// ```
+/// # use ctxbuilder::{factory::FnBuilder, prelude::*, Context, ContextRead};
+/// struct Person {
+///     name: &'static str,
+///     age: u32,
+/// }
+///
+/// let adult = FnBuilder::new(|ctx| Person {
+///     name: ctx.entry_named("name").or_insert("Alice"),
+///     age: 18 + ctx.rng().below(50) as u32,
+/// });
+///
+/// let mut ctx = ctxbuilder::ctx();
+/// ctx.insert_named("adult", adult);
+///
+/// let adult = ctx.get_named::<FnBuilder<Person>>("adult").unwrap().clone();
+/// let person = ctx.make(&adult);
+/// assert_eq!(person.name, "Alice");
+/// assert!(person.age >= 18);
+/// ```
+///
+/// ## Panics
+///
+/// Building panics with contexts that don't support type-erased builds, like
+/// [`Context::build_dyn`].
+pub struct FnBuilder<T>(Arc<MakeFn<T>>);
+
+impl<T> FnBuilder<T> {
+    /// Create a new [`FnBuilder`] from a closure
+    pub fn new<F: Fn(&mut DynRef<'_>) -> T + Send + Sync + 'static>(f: F) -> Self {
+        Self(Arc::new(f))
+    }
+}
+
+impl<T> Clone for FnBuilder<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> fmt::Debug for FnBuilder<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("FnBuilder").finish_non_exhaustive()
+    }
+}
+
+impl<T> Factory for FnBuilder<T> {
+    type Output = T;
+
+    fn make<C: Context>(&self, ctx: &mut C) -> T {
+        (self.0)(&mut DynRef(bind::dyn_context(ctx)))
+    }
+}
@@ -0,0 +1,75 @@
+use alloc::vec::Vec;
+use core::any::TypeId;
+
+use crate::{dynamic::DynRef, entry::InnerEntry, Context, ContextWrite, Key};
+
+/// Trait for objects that are patched once the other objects of a build exist
+///
+/// Builders run in a single forward pass, so two objects can't reference each other directly.
+/// Builders can instead insert a placeholder with
+/// [`Context::insert_finalized`](crate::Context::insert_finalized), and fill the references in
+/// [`finalize`](Self::finalize) when [`Context::finalize_all`](crate::Context::finalize_all)
+/// runs.
+///
+/// ```
+/// # use ctxbuilder::{Context, ContextRead, Finalize};
+/// struct User {
+///     id: u64,
+///     manager: Option<u64>,
+/// }
+///
+/// impl Finalize for User {
+///     fn finalize<C: Context>(&mut self, ctx: &mut C) {
+///         // Alice and Bob manage each other
+///         let peer = if self.id == 1 { "bob" } else { "alice" };
+///         self.manager = ctx.get_named::<User>(peer).map(|user| user.id);
+///     }
+/// }
+///
+/// let mut ctx = ctxbuilder::ctx();
+/// ctx.insert_finalized_named("alice", User { id: 1, manager: None });
+/// ctx.insert_finalized_named("bob", User { id: 2, manager: None });
+/// ctx.finalize_all();
+///
+/// assert_eq!(ctx.get_named::<User>("alice").unwrap().manager, Some(2));
+/// assert_eq!(ctx.get_named::<User>("bob").unwrap().manager, Some(1));
+/// ```
+pub trait Finalize: Send + Sync + 'static {
+    /// Patch this object with the rest of the context
+    ///
+    /// The object itself is not in the context while it is finalized.
+    fn finalize<C: Context>(&mut self, ctx: &mut C);
+}
+
+/// Entries waiting for [`Context::finalize_all`](crate::Context::finalize_all), in insertion
+/// order
+#[derive(Default)]
+pub(crate) struct Pending(pub(crate) Vec<(Option<&'static str>, FinalizeFn)>);
+
+type FinalizeFn = fn(&mut DynRef<'_>, Option<&'static str>);
+
+/// Register the entry of type `T` with the given name for finalization
+pub(crate) fn defer<T: Finalize, C: ContextWrite + ?Sized>(
+    ctx: &mut C,
+    name: Option<&'static str>,
+) {
+    let finalize = (name, finalize_entry::<T> as FinalizeFn);
+    ctx.entry::<Pending>()
+        .and_modify(|pending| pending.0.push(finalize))
+        .or_insert_with(|| Pending(alloc::vec![finalize]));
+}
+
+/// Finalize the entry of type `T` with the given name, if it is stored in this context
+fn finalize_entry<T: Finalize>(ctx: &mut DynRef<'_>, name: Option<&'static str>) {
+    let key: Key = (TypeId::of::<T>(), name);
+    let Some(mut val) = (match ctx.0.entry_stored(key).inner {
+        InnerEntry::Occupied(mut entry) => entry.get_mut().take::<T>(),
+        InnerEntry::Vacant(_) => None,
+    }) else {
+        return;
+    };
+    val.finalize(ctx);
+    if let InnerEntry::Occupied(mut entry) = ctx.0.entry_stored(key).inner {
+        entry.get_mut().restore(val);
+    }
+}
@@ -0,0 +1,89 @@
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+};
+use core::fmt;
+
+use crate::{placeholder::Placeholder, Context, Sequence};
+
+/// Function generating a new string every time a placeholder is expanded by
+/// [`Context::format`]
+pub struct Generator(Box<dyn Fn() -> String + Send + Sync>);
+
+impl Generator {
+    /// Create a new [`Generator`]
+    pub fn new<F: Fn() -> String + Send + Sync + 'static>(f: F) -> Self {
+        Self(Box::new(f))
+    }
+
+    /// Generate a new string
+    pub fn generate(&self) -> String {
+        (self.0)()
+    }
+}
+
+impl fmt::Debug for Generator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Generator").finish_non_exhaustive()
+    }
+}
+
+/// Expand the `{name}` placeholders in the template
+pub(crate) fn format<C: Context>(ctx: &mut C, template: &'static str) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find(['{', '}']) {
+        out.push_str(&rest[..start]);
+        let after = &rest[start..];
+
+        // Escaped braces
+        if after.starts_with("{{") || after.starts_with("}}") {
+            out.push_str(&after[..1]);
+            rest = &after[2..];
+            continue;
+        }
+
+        let end = match (after.starts_with('{'), after.find('}')) {
+            (true, Some(end)) => end,
+            _ => panic!("invalid format template `{template}`"),
+        };
+        let name = &after[1..end];
+        out.push_str(
+            &expand(ctx, name)
+                .unwrap_or_else(|| panic!("missing context entry for placeholder `{{{name}}}`")),
+        );
+        rest = &after[end + 1..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// Expand a single placeholder
+///
+/// The placeholder is resolved, in order, from:
+/// * `seq`, which is the next value of the unnamed [`Sequence`]
+/// * a named [`Sequence`]
+/// * a named [`Generator`]
+/// * a named entry of a common type, such as `String` or an integer
+/// * `uuid`, which is a new random UUID
+fn expand<C: Context>(ctx: &mut C, name: &'static str) -> Option<String> {
+    if name == "seq" {
+        return Some(ctx.next_seq().to_string());
+    }
+    if let Some(seq) = ctx.get_named::<Sequence>(name) {
+        return Some(seq.next_value().to_string());
+    }
+    if let Some(generator) = ctx.get_named::<Generator>(name) {
+        return Some(generator.generate());
+    }
+    if let Some(v) = Placeholder::lookup(ctx, name) {
+        return Some(v.to_string());
+    }
+    #[cfg(feature = "uuid")]
+    if name == "uuid" {
+        return Some(crate::uuid_strategy::next_uuid(ctx).to_string());
+    }
+    None
+}
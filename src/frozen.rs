@@ -0,0 +1,207 @@
+use alloc::{sync::Arc, vec::Vec};
+use core::any::{type_name, TypeId};
+
+use crate::{
+    ancestors::{Layer, LayerKind},
+    context::get_shared,
+    dynamic::{DynContext, RawEntry},
+    meta::Registry,
+    value::Stored,
+    Ancestors, AnyMap, ContextRead, ContextWrite, Entry, Key, KeySet, MainContext, Rng,
+};
+
+/// Read-only context, created with [`MainContext::freeze`]
+///
+/// Frozen contexts are cheap to clone and can be shared between tests, for example as a "golden"
+/// base context. They implement [`Context`](crate::Context) so that builders can read from them, but inserting a
+/// value panics. Use [`fork`](Self::fork) to get a context that can be modified.
+pub struct FrozenContext {
+    shared: Vec<Arc<AnyMap>>,
+    rng: Rng,
+    registry: Registry,
+    /// Always-empty map to create vacant entries from
+    scratch: AnyMap,
+}
+
+impl MainContext {
+    /// Freeze this context, preventing any further inserts
+    pub fn freeze(mut self) -> FrozenContext {
+        let (shared, rng) = self.share();
+        FrozenContext {
+            shared,
+            rng,
+            registry: self.registry.clone(),
+            scratch: Default::default(),
+        }
+    }
+}
+
+impl Clone for FrozenContext {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+            rng: self.rng.clone(),
+            registry: self.registry.clone(),
+            scratch: Default::default(),
+        }
+    }
+}
+
+impl FrozenContext {
+    /// Create a [`MainContext`] that shares the entries of this context
+    ///
+    /// See [`MainContext::fork`].
+    pub fn fork(&self) -> MainContext {
+        let mut ctx =
+            MainContext::from_shared(Default::default(), self.shared.clone(), self.rng.clone());
+        ctx.registry = self.registry.clone();
+        ctx
+    }
+
+    fn frozen_entry<T: Send + Sync + 'static>(
+        &mut self,
+        name: Option<&'static str>,
+    ) -> Entry<'_, T> {
+        let key = (TypeId::of::<T>(), name);
+        let Some(main) = get_shared(&self.shared, &key) else {
+            match name {
+                Some(name) => panic!(
+                    "cannot insert `{name}` of type `{}` into a frozen context",
+                    type_name::<T>()
+                ),
+                None => panic!("cannot insert `{}` into a frozen context", type_name::<T>()),
+            }
+        };
+        self.scratch.clear();
+        Entry::new(Some(main), self.scratch.entry(key), &self.rng).frozen()
+    }
+}
+
+impl ContextRead for FrozenContext {
+    fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        get_shared(&self.shared, &(TypeId::of::<T>(), None))
+    }
+
+    fn get_named<T: Send + Sync + 'static>(&self, name: &'static str) -> Option<&T> {
+        get_shared(&self.shared, &(TypeId::of::<T>(), Some(name)))
+    }
+
+    fn rng(&self) -> &Rng {
+        &self.rng
+    }
+
+    fn entry_keys(&self) -> Vec<(&'static str, Option<&'static str>)> {
+        let mut seen = KeySet::default();
+        self.shared
+            .iter()
+            .rev()
+            .flat_map(|map| &**map)
+            .filter(|(key, val)| seen.insert(**key) && !val.is_removed())
+            .map(|(key, val)| (val.type_name(), key.1))
+            .collect()
+    }
+
+    fn ancestors(&self) -> Ancestors<'_> {
+        let layers = self.shared.iter().rev();
+        Ancestors::new(
+            layers
+                .map(|map| Layer::new(LayerKind::Shared, map))
+                .collect(),
+        )
+    }
+}
+
+/// Frozen contexts implement [`ContextWrite`] so that builders can read from them, but every
+/// write panics.
+impl ContextWrite for FrozenContext {
+    /// Get an entry in the context by its type
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the entry is missing, as it would need to be inserted. Modifying the entry
+    /// with [`Entry::and_modify`] panics as well.
+    fn entry<T: Send + Sync + 'static>(&mut self) -> Entry<'_, T> {
+        self.frozen_entry(None)
+    }
+
+    /// Get an entry in the context by its name and type
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the entry is missing, as it would need to be inserted. Modifying the entry
+    /// with [`Entry::and_modify`] panics as well.
+    fn entry_named<T: Send + Sync + 'static>(&mut self, name: &'static str) -> Entry<'_, T> {
+        self.frozen_entry(Some(name))
+    }
+
+    /// Insert an object by type
+    ///
+    /// ## Panics
+    ///
+    /// This always panics, as frozen contexts are read-only.
+    fn insert<T: Send + Sync + 'static>(&mut self, _val: T) -> Option<T> {
+        panic!("cannot insert `{}` into a frozen context", type_name::<T>());
+    }
+
+    /// Insert an object by type and name
+    ///
+    /// ## Panics
+    ///
+    /// This always panics, as frozen contexts are read-only.
+    fn insert_named<T: Send + Sync + 'static>(&mut self, name: &'static str, _val: T) -> Option<T> {
+        panic!(
+            "cannot insert `{name}` of type `{}` into a frozen context",
+            type_name::<T>()
+        );
+    }
+
+    fn as_dyn(&mut self) -> Option<&mut dyn DynContext> {
+        Some(self)
+    }
+}
+
+impl DynContext for FrozenContext {
+    fn get_stored(&self, key: &Key) -> Option<&Stored> {
+        self.shared.iter().rev().find_map(|map| map.get(key))
+    }
+
+    /// ## Panics
+    ///
+    /// This panics if the entry is missing, as it would need to be inserted.
+    fn entry_stored(&mut self, key: Key) -> RawEntry<'_> {
+        let Some(main) = self.shared.iter().rev().find_map(|map| map.get(&key)) else {
+            match key.1 {
+                Some(name) => panic!("cannot insert `{name}` into a frozen context"),
+                None => panic!("cannot insert into a frozen context"),
+            }
+        };
+        self.scratch.clear();
+        RawEntry {
+            main: Some(main),
+            inner: self.scratch.entry(key),
+            rng: &self.rng,
+            frozen: true,
+        }
+    }
+
+    /// ## Panics
+    ///
+    /// This always panics, as frozen contexts are read-only.
+    fn insert_stored(&mut self, key: Key, val: Stored) -> Option<Stored> {
+        match key.1 {
+            Some(name) => panic!(
+                "cannot insert `{name}` of type `{}` into a frozen context",
+                val.type_name()
+            ),
+            None => panic!("cannot insert `{}` into a frozen context", val.type_name()),
+        }
+    }
+
+    fn dyn_rng(&self) -> &Rng {
+        &self.rng
+    }
+
+    fn dyn_ancestors(&self) -> Ancestors<'_> {
+        self.ancestors()
+    }
+}
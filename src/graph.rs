@@ -0,0 +1,139 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt::{self, Write};
+
+/// Objects built with a recording [`MainContext`](crate::MainContext), and the objects built
+/// while building them
+///
+/// Each distinct type and name built with [`Context::build`](crate::Context::build) or
+/// [`Context::build_named`](crate::Context::build_named) is a node of the graph, and there is an
+/// edge from a node to every object built by its builder. Unlike [`Recipe`](crate::Recipe)s, the
+/// graph also contains nested builds.
+///
+/// ```
+/// # use ctxbuilder::{Builder, Context, MainContext};
+/// struct Owner(uuid::Uuid);
+///
+/// impl Builder for Owner {
+///     fn build<C: Context>(ctx: &mut C) -> Self {
+///         Self(ctx.build_named("owner_id"))
+///     }
+/// }
+///
+/// let mut ctx = MainContext::recording(42);
+/// ctx.build::<Owner>();
+///
+/// let graph = ctx.object_graph().unwrap();
+/// assert_eq!(graph.nodes().len(), 2);
+/// assert_eq!(graph.children(0).next().unwrap().name(), Some("owner_id"));
+/// assert!(graph.to_dot().starts_with("digraph objects {"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ObjectGraph {
+    nodes: Vec<ObjectNode>,
+    edges: Vec<(usize, usize)>,
+}
+
+/// Node of an [`ObjectGraph`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ObjectNode {
+    type_name: &'static str,
+    name: Option<&'static str>,
+}
+
+impl ObjectNode {
+    pub(crate) fn new(type_name: &'static str, name: Option<&'static str>) -> Self {
+        Self { type_name, name }
+    }
+
+    /// Type name of the object
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Name of the object, if it was built by name
+    pub fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+}
+
+impl ObjectGraph {
+    /// Nodes of the graph, in the order they were first built
+    pub fn nodes(&self) -> &[ObjectNode] {
+        &self.nodes
+    }
+
+    /// Edges of the graph, as indices of the parent and child nodes
+    pub fn edges(&self) -> &[(usize, usize)] {
+        &self.edges
+    }
+
+    /// Nodes that were built by the builder of the node at `index`
+    pub fn children(&self, index: usize) -> impl Iterator<Item = &ObjectNode> + '_ {
+        self.edges
+            .iter()
+            .filter(move |(parent, _)| *parent == index)
+            .map(|(_, child)| &self.nodes[*child])
+    }
+
+    /// Nodes that are not built by any other builder
+    pub fn roots(&self) -> impl Iterator<Item = &ObjectNode> + '_ {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.edges.iter().all(|(_, child)| child != i))
+            .map(|(_, node)| node)
+    }
+
+    /// Render the graph in the DOT language of Graphviz
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph objects {\n");
+        for (i, node) in self.nodes.iter().enumerate() {
+            let _ = write!(out, "    n{i} [label=\"");
+            let _ = match node.name {
+                Some(name) => write!(out, "{}\\n{}", Quoted(name), Quoted(node.type_name)),
+                None => write!(out, "{}", Quoted(node.type_name)),
+            };
+            out.push_str("\"];\n");
+        }
+        for (parent, child) in &self.edges {
+            let _ = writeln!(out, "    n{parent} -> n{child};");
+        }
+        out.push('}');
+        out.push('\n');
+        out
+    }
+
+    /// Add a build of `node`, nested in the build of `parent`, and return its index
+    pub(crate) fn add(&mut self, node: ObjectNode, parent: Option<usize>) -> usize {
+        let index = match self.nodes.iter().position(|n| *n == node) {
+            Some(index) => index,
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        };
+        if let Some(parent) = parent {
+            if !self.edges.contains(&(parent, index)) {
+                self.edges.push((parent, index));
+            }
+        }
+        index
+    }
+}
+
+/// String escaped for a quoted DOT identifier
+struct Quoted<'a>(&'a str);
+
+impl fmt::Display for Quoted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                c => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+use core::hash::{BuildHasherDefault, Hasher};
+
+/// Multiplier from FxHash, as used in `rustc`
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+/// Fast hasher for context keys
+///
+/// Keys are made of a [`TypeId`](core::any::TypeId), which is already a hash, and an optional
+/// short name. There is no need for SipHash's protection against collision attacks there, so this
+/// uses FxHash instead: `TypeId` only contributes a single multiply, and names are hashed eight
+/// bytes at a time.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct KeyHasher {
+    hash: u64,
+}
+
+impl KeyHasher {
+    #[inline]
+    fn add_to_hash(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(SEED);
+    }
+}
+
+impl Hasher for KeyHasher {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.add_to_hash(u64::from_le_bytes(chunk.try_into().expect("8 bytes chunk")));
+        }
+
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut word = [0u8; 8];
+            word[..rest.len()].copy_from_slice(rest);
+            self.add_to_hash(u64::from_le_bytes(word));
+        }
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.add_to_hash(i as u64);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.add_to_hash(i as u64);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.add_to_hash(i as u64);
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.add_to_hash(i);
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.add_to_hash(i as u64);
+        self.add_to_hash((i >> 64) as u64);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.add_to_hash(i as u64);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+pub(crate) type BuildKeyHasher = BuildHasherDefault<KeyHasher>;
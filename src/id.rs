@@ -0,0 +1,90 @@
+use alloc::boxed::Box;
+use core::{
+    fmt,
+    marker::PhantomData,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use crate::Rng;
+
+/// Trait for objects with IDs generated by an [`IdStrategy`]
+///
+/// See [`Context::next_id`](crate::Context::next_id).
+pub trait Identified: Sized + 'static {
+    /// Type of the IDs
+    type Id;
+
+    /// Return the strategy to use when the context doesn't have one for this type
+    fn default_id_strategy() -> IdStrategy<Self>;
+}
+
+type GenerateFn<T> = dyn Fn(&Rng, Duration) -> <T as Identified>::Id + Send + Sync;
+
+/// Strategy to generate the IDs of `T`, stored in a context
+///
+/// Builders request IDs with [`Context::next_id`](crate::Context::next_id), so that tests can
+/// swap the strategy for a context without changing the builders.
+pub struct IdStrategy<T: Identified> {
+    generate: Box<GenerateFn<T>>,
+    _phantom_data: PhantomData<fn() -> T>,
+}
+
+impl<T: Identified> IdStrategy<T> {
+    /// Create a new [`IdStrategy`] from a function
+    ///
+    /// The function receives the context's [`Rng`] and the time of its [`Clock`](crate::Clock)
+    /// since the UNIX epoch.
+    pub fn new<F: Fn(&Rng, Duration) -> T::Id + Send + Sync + 'static>(f: F) -> Self {
+        Self {
+            generate: Box::new(f),
+            _phantom_data: PhantomData,
+        }
+    }
+
+    /// Generate IDs counting up from 1
+    pub fn sequence() -> Self
+    where
+        T::Id: From<u64>,
+    {
+        let next = AtomicU64::new(1);
+        Self::new(move |_, _| next.fetch_add(1, Ordering::Relaxed).into())
+    }
+
+    /// Generate random version 4 UUIDs
+    #[cfg(feature = "uuid")]
+    pub fn uuid() -> Self
+    where
+        T::Id: From<uuid::Uuid>,
+    {
+        Self::new(|rng, _| rng.next_uuid().into())
+    }
+
+    /// Generate snowflake-like IDs
+    ///
+    /// IDs are made of the milliseconds since the UNIX epoch in the upper 42 bits, the lower 10
+    /// bits of `node`, and a 12-bit counter. They are sorted by time.
+    pub fn snowflake(node: u16) -> Self
+    where
+        T::Id: From<u64>,
+    {
+        let counter = AtomicU64::new(0);
+        let node = u64::from(node & 0x3ff);
+        Self::new(move |_, now| {
+            let millis = now.as_millis() as u64;
+            let seq = counter.fetch_add(1, Ordering::Relaxed) & 0xfff;
+            ((millis << 22) | (node << 12) | seq).into()
+        })
+    }
+
+    /// Generate a new ID
+    pub fn generate(&self, rng: &Rng, now: Duration) -> T::Id {
+        (self.generate)(rng, now)
+    }
+}
+
+impl<T: Identified> fmt::Debug for IdStrategy<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IdStrategy").finish_non_exhaustive()
+    }
+}
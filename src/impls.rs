@@ -1,16 +1,99 @@
+use alloc::vec::Vec;
+
 #[allow(unused)]
-use crate::{Builder, Context, NamedBuilder};
+use crate::{Builder, Context, Name, NamedBuilder, Rebuild, Rng};
+
+impl Name for &'static str {
+    fn as_str(&self) -> Option<&'static str> {
+        Some(self)
+    }
+}
+
+macro_rules! impl_name {
+    ($($ty:ty),*) => {
+        $(
+            impl Name for $ty {
+                fn as_str(&self) -> Option<&'static str> {
+                    None
+                }
+            }
+        )*
+    };
+}
+
+impl_name!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl<T: Rebuild> Rebuild for Option<T> {
+    fn rebuild<C: Context>(&mut self, ctx: &mut C) {
+        if let Some(val) = self {
+            val.rebuild(ctx);
+        }
+    }
+}
+
+impl<T: Rebuild> Rebuild for Vec<T> {
+    fn rebuild<C: Context>(&mut self, ctx: &mut C) {
+        for val in self {
+            val.rebuild(ctx);
+        }
+    }
+}
 
 #[cfg(feature = "uuid")]
 impl Builder for uuid::Uuid {
     fn build<C: Context>(ctx: &mut C) -> Self {
-        *ctx.entry::<Self>().or_insert_with(uuid::Uuid::new_v4)
+        if let Some(uuid) = ctx.get::<Self>() {
+            return *uuid;
+        }
+        let uuid = crate::uuid_strategy::next_uuid(ctx);
+        *ctx.entry::<Self>().or_insert(uuid)
     }
 }
 #[cfg(feature = "uuid")]
 impl NamedBuilder for uuid::Uuid {
     fn build_with_name<C: Context>(ctx: &mut C, name: &'static str) -> Self {
+        if let Some(uuid) = ctx.get_named::<Self>(name) {
+            return *uuid;
+        }
+        let uuid = crate::uuid_strategy::next_uuid(ctx);
+        *ctx.entry_named::<Self>(name).or_insert(uuid)
+    }
+}
+
+#[cfg(feature = "semver")]
+impl Builder for semver::Version {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        if let Some(version) = ctx.get::<Self>() {
+            return version.clone();
+        }
+        let version = crate::semver_strategy::next_version(ctx);
+        ctx.entry::<Self>().or_insert(version).clone()
+    }
+}
+#[cfg(feature = "semver")]
+impl NamedBuilder for semver::Version {
+    fn build_with_name<C: Context>(ctx: &mut C, name: &'static str) -> Self {
+        if let Some(version) = ctx.get_named::<Self>(name) {
+            return version.clone();
+        }
+        let version = crate::semver_strategy::next_version(ctx);
+        ctx.entry_named::<Self>(name).or_insert(version).clone()
+    }
+}
+
+#[cfg(feature = "ulid")]
+impl Builder for ulid::Ulid {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        let millis = crate::clock::unix_time(ctx).as_millis() as u64;
+        *ctx.entry::<Self>()
+            .or_insert_with_rng(|rng| rng.next_ulid(millis))
+    }
+}
+#[cfg(feature = "ulid")]
+impl NamedBuilder for ulid::Ulid {
+    fn build_with_name<C: Context>(ctx: &mut C, name: &'static str) -> Self {
+        let millis = crate::clock::unix_time(ctx).as_millis() as u64;
         *ctx.entry_named::<Self>(name)
-            .or_insert_with(uuid::Uuid::new_v4)
+            .or_insert_with_rng(|rng| rng.next_ulid(millis))
     }
 }
@@ -0,0 +1,21 @@
+use std::{collections::BTreeSet, sync::Mutex};
+
+/// Names leaked by [`intern`], shared by all contexts
+static NAMES: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+
+/// Get the `&'static str` for a name only known at runtime
+///
+/// Context keys only hold `&'static str` names, which keeps lookups fast. Names that come from
+/// runtime data, such as the ones in a [`Recipe`](crate::Recipe), are leaked the first time they
+/// are seen and reused afterwards, so that repeating the same names doesn't leak more memory.
+pub(crate) fn intern(name: &str) -> &'static str {
+    let mut names = NAMES
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    if let Some(name) = names.get(name) {
+        return name;
+    }
+    let name: &'static str = Box::leak(name.into());
+    names.insert(name);
+    name
+}
@@ -0,0 +1,135 @@
+//! [`serde_json::Value`] builder based on templates
+//!
+//! A [`JsonTemplate`] is a JSON document where string values can contain `{{name}}`
+//! placeholders. When building a [`Value`], each placeholder is replaced by the named entry with
+//! the same name in the context.
+//!
+//! A string that only contains a placeholder is replaced by the entry itself, which keeps numbers
+//! and booleans as such. Otherwise, the entry is formatted into the surrounding string.
+
+use serde_json::Value;
+
+use crate::{placeholder::Placeholder, Builder, Context, ContextRead, NamedBuilder};
+
+/// JSON document with `{{name}}` placeholders resolved from named entries in a [`Context`]
+#[derive(Clone, Debug)]
+pub struct JsonTemplate {
+    value: Value,
+    names: Vec<&'static str>,
+}
+
+impl JsonTemplate {
+    /// Parse a new [`JsonTemplate`]
+    pub fn new(template: &'static str) -> serde_json::Result<Self> {
+        let value = serde_json::from_str(template)?;
+
+        // Entry names are `&'static str`, so we collect them from the template source rather than
+        // from the parsed value.
+        let mut names = Vec::new();
+        let mut rest = template;
+        while let Some((name, after)) = next_placeholder(rest) {
+            names.push(name);
+            rest = after;
+        }
+
+        Ok(Self { value, names })
+    }
+
+    /// Render the template with the entries in the context
+    ///
+    /// ## Panics
+    ///
+    /// This panics if a placeholder doesn't match any named entry in the context.
+    pub fn render<C: ContextRead>(&self, ctx: &C) -> Value {
+        self.render_value(ctx, &self.value)
+    }
+
+    fn render_value<C: ContextRead>(&self, ctx: &C, value: &Value) -> Value {
+        match value {
+            Value::String(s) => self.render_str(ctx, s),
+            Value::Array(values) => values.iter().map(|v| self.render_value(ctx, v)).collect(),
+            Value::Object(map) => map
+                .iter()
+                .map(|(k, v)| (k.clone(), self.render_value(ctx, v)))
+                .collect(),
+            other => other.clone(),
+        }
+    }
+
+    fn render_str<C: ContextRead>(&self, ctx: &C, s: &str) -> Value {
+        // The whole string is a placeholder: keep the type of the entry
+        if let Some((name, "")) = next_placeholder(s) {
+            if s.starts_with("{{") {
+                return self.lookup(ctx, name);
+            }
+        }
+
+        let mut out = String::with_capacity(s.len());
+        let mut rest = s;
+        while let Some(start) = rest.find("{{") {
+            let Some((name, after)) = next_placeholder(&rest[start..]) else {
+                break;
+            };
+            out.push_str(&rest[..start]);
+            match self.lookup(ctx, name) {
+                Value::String(v) => out.push_str(&v),
+                v => out.push_str(&v.to_string()),
+            }
+            rest = after;
+        }
+        out.push_str(rest);
+
+        Value::String(out)
+    }
+
+    fn lookup<C: ContextRead>(&self, ctx: &C, name: &str) -> Value {
+        let name = self
+            .names
+            .iter()
+            .find(|n| **n == name)
+            .copied()
+            .unwrap_or_else(|| panic!("unknown placeholder `{{{{{name}}}}}`"));
+
+        Placeholder::lookup(ctx, name)
+            .map(Value::from)
+            .unwrap_or_else(|| panic!("missing context entry for placeholder `{{{{{name}}}}}`"))
+    }
+}
+
+/// Find the next `{{name}}` placeholder, and return its name and the rest of the string
+fn next_placeholder(s: &str) -> Option<(&str, &str)> {
+    let start = s.find("{{")? + 2;
+    let len = s[start..].find("}}")?;
+    Some((s[start..start + len].trim(), &s[start + len + 2..]))
+}
+
+impl From<Placeholder<'_>> for Value {
+    fn from(placeholder: Placeholder<'_>) -> Self {
+        match placeholder {
+            Placeholder::Json(v) => v.clone(),
+            Placeholder::Str(v) => Value::from(v),
+            #[cfg(feature = "uuid")]
+            Placeholder::Uuid(v) => Value::from(v.to_string()),
+            Placeholder::Bool(v) => Value::from(v),
+            Placeholder::Int(v) => Value::from(v),
+            Placeholder::Uint(v) => Value::from(v),
+            Placeholder::Float(v) => Value::from(v),
+        }
+    }
+}
+
+impl Builder for Value {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        ctx.get::<JsonTemplate>()
+            .map(|template| template.render(ctx))
+            .unwrap_or_default()
+    }
+}
+
+impl NamedBuilder for Value {
+    fn build_with_name<C: Context>(ctx: &mut C, name: &'static str) -> Self {
+        ctx.get_named::<JsonTemplate>(name)
+            .map(|template| template.render(ctx))
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,196 @@
+use alloc::vec::Vec;
+use core::any::TypeId;
+
+#[cfg(feature = "metrics")]
+use crate::metrics::Metrics;
+use crate::{
+    ancestors::{Layer, LayerKind},
+    dynamic::{DynContext, RawEntry},
+    trace,
+    value::Stored,
+    Ancestors, AnyMap, ContextRead, ContextWrite, Entry, Key, KeySet, MainContext, Rng,
+};
+
+/// Context that inherits from multiple parent contexts
+///
+/// Lookups are resolved from the entries inserted in this context first, then from the parents,
+/// from the last one added to the first one. This makes it possible to compose fixtures from, for
+/// example, global defaults, team defaults, and test-specific overrides.
+///
+/// Inserted values are only stored in this context. The random number generator is the one of the
+/// first parent.
+pub struct LayeredContext<'c> {
+    parents: Vec<&'c MainContext>,
+    map: AnyMap,
+}
+
+impl<'c> LayeredContext<'c> {
+    /// Create a new [`LayeredContext`] on top of a base context
+    pub fn new(base: &'c MainContext) -> Self {
+        Self {
+            parents: alloc::vec![base],
+            map: Default::default(),
+        }
+    }
+
+    /// Add a parent context on top of the existing ones
+    pub fn push_layer(&mut self, parent: &'c MainContext) {
+        self.parents.push(parent);
+    }
+
+    /// Convenience method to add a parent context on top of the existing ones
+    pub fn with_layer(mut self, parent: &'c MainContext) -> Self {
+        self.push_layer(parent);
+        self
+    }
+
+    /// Count an insert in the metrics of the first parent
+    #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+    fn count_insert(&self, type_name: &'static str) {
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = self.metrics() {
+            metrics.insert(type_name);
+        }
+    }
+}
+
+fn get_parent<'c, T: 'static>(
+    parents: &[&'c MainContext],
+    name: Option<&'static str>,
+) -> Option<&'c T> {
+    parents
+        .iter()
+        .rev()
+        .find_map(|parent| parent.get_any(TypeId::of::<T>(), name))
+}
+
+fn get_parent_stored<'c>(parents: &[&'c MainContext], key: &Key) -> Option<&'c Stored> {
+    parents
+        .iter()
+        .rev()
+        .find_map(|parent| parent.get_stored(key).filter(|val| !val.is_removed()))
+}
+
+impl<'c> ContextRead for LayeredContext<'c> {
+    fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.map
+            .get(&(TypeId::of::<T>(), None))
+            .and_then(Stored::downcast_ref)
+            .or_else(|| get_parent(&self.parents, None))
+    }
+
+    fn get_named<T: Send + Sync + 'static>(&self, name: &'static str) -> Option<&T> {
+        self.map
+            .get(&(TypeId::of::<T>(), Some(name)))
+            .and_then(Stored::downcast_ref)
+            .or_else(|| get_parent(&self.parents, Some(name)))
+    }
+
+    fn rng(&self) -> &Rng {
+        &self.parents[0].rng
+    }
+
+    fn entry_keys(&self) -> Vec<(&'static str, Option<&'static str>)> {
+        let mut seen = KeySet::default();
+        let local = self.map.iter();
+        let parents = self
+            .parents
+            .iter()
+            .rev()
+            .flat_map(|parent| parent.entries());
+        local
+            .chain(parents)
+            .filter(|(key, _)| seen.insert(**key))
+            .map(|(key, val)| (val.type_name(), key.1))
+            .collect()
+    }
+
+    fn ancestors(&self) -> Ancestors<'_> {
+        let parents = self
+            .parents
+            .iter()
+            .rev()
+            .flat_map(|parent| parent.layers(LayerKind::Parent));
+        Ancestors::new(
+            core::iter::once(Layer::new(LayerKind::Local, &self.map))
+                .chain(parents)
+                .collect(),
+        )
+    }
+    #[cfg(feature = "metrics")]
+    fn metrics(&self) -> Option<&Metrics> {
+        self.parents[0].metrics()
+    }
+}
+
+impl<'c> ContextWrite for LayeredContext<'c> {
+    fn entry<T: Send + Sync + 'static>(&mut self) -> Entry<'_, T> {
+        Entry::inherit(
+            || get_parent(&self.parents, None),
+            self.map.entry((TypeId::of::<T>(), None)),
+            &self.parents[0].rng,
+        )
+    }
+
+    fn entry_named<T: Send + Sync + 'static>(&mut self, name: &'static str) -> Entry<'_, T> {
+        Entry::inherit(
+            || get_parent(&self.parents, Some(name)),
+            self.map.entry((TypeId::of::<T>(), Some(name))),
+            &self.parents[0].rng,
+        )
+    }
+
+    fn insert<T: Send + Sync + 'static>(&mut self, val: T) -> Option<T> {
+        self.count_insert(core::any::type_name::<T>());
+        let old = self.map.insert((TypeId::of::<T>(), None), Stored::new(val));
+        trace::insert::<T>(None, old.is_some());
+        old.and_then(Stored::into_inner)
+    }
+
+    fn insert_named<T: Send + Sync + 'static>(&mut self, name: &'static str, val: T) -> Option<T> {
+        self.count_insert(core::any::type_name::<T>());
+        let old = self
+            .map
+            .insert((TypeId::of::<T>(), Some(name)), Stored::new(val));
+        trace::insert::<T>(Some(name), old.is_some());
+        old.and_then(Stored::into_inner)
+    }
+
+    fn as_dyn(&mut self) -> Option<&mut dyn DynContext> {
+        Some(self)
+    }
+}
+
+impl<'c> DynContext for LayeredContext<'c> {
+    fn get_stored(&self, key: &Key) -> Option<&Stored> {
+        self.map
+            .get(key)
+            .or_else(|| get_parent_stored(&self.parents, key))
+    }
+
+    fn entry_stored(&mut self, key: Key) -> RawEntry<'_> {
+        RawEntry::inherit(
+            || get_parent_stored(&self.parents, &key),
+            self.map.entry(key),
+            &self.parents[0].rng,
+        )
+    }
+
+    fn insert_stored(&mut self, key: Key, val: Stored) -> Option<Stored> {
+        self.count_insert(val.type_name());
+        self.map.insert(key, val)
+    }
+
+    fn dyn_rng(&self) -> &Rng {
+        &self.parents[0].rng
+    }
+
+    fn dyn_ancestors(&self) -> Ancestors<'_> {
+        self.ancestors()
+    }
+
+    #[cfg(feature = "metrics")]
+    fn dyn_metrics(&self) -> Option<&Metrics> {
+        self.metrics()
+    }
+}
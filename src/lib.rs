@@ -1,37 +1,418 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 //! # Context-Based Builders
 //!
 //! Build Rust objects based on shared [`Context`]. This is useful when you need to generate
 //! multiple objects based on a set of similar properties, such as in preparation for unit
 //! tests.
+//!
+//! ## `no_std` support
+//!
+//! The `std` feature is enabled by default. Without it, this crate only depends on `alloc` and
+//! uses [`hashbrown`] for its maps. Contexts then start with a fixed seed for their
+//! [`Rng`] instead of a random one.
+//!
+//! ## Tracing
+//!
+//! With the `tracing` feature, builds are wrapped in `build` spans, and inserts and entry lookups
+//! emit events with the type name, the entry name, and whether the entry was already present.
+//!
+//! ## Metrics
+//!
+//! With the `metrics` feature, contexts count the builds, cache hits and inserts of each type,
+//! which [`ContextRead::stats`] returns.
+//!
+//! ## Deterministic ordering
+//!
+//! Entries are stored in hash maps, so listing them returns them in an order that depends on
+//! their type IDs, which can change between builds. With the `ordered` feature, contexts store
+//! their entries in insertion order instead, so that exports and debug output such as
+//! [`ContextRead::entry_keys`] are stable across runs.
+
+extern crate alloc;
 
-use std::{
-    any::{Any, TypeId},
-    collections::HashMap,
-};
+use alloc::boxed::Box;
+use core::any::{Any, TypeId};
 
+#[cfg(not(feature = "std"))]
+use hashbrown::{HashMap, HashSet};
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+
+mod ancestors;
+pub use ancestors::{Ancestors, Layer, LayerKind};
+pub mod assertions;
+#[cfg(feature = "std")]
+mod audit;
+#[cfg(feature = "aws_lambda_events")]
+pub mod aws_lambda_events;
+#[cfg(feature = "axum")]
+pub mod axum;
+#[cfg(feature = "postcard")]
+mod binary;
+#[cfg(feature = "postcard")]
+pub use binary::BinaryError;
+mod bind;
+mod clock;
+pub use clock::Clock;
+#[cfg(feature = "std")]
+pub use clock::FrozenInstant;
 mod context;
-pub use context::{Context, MainContext, SubContext};
+pub use context::{Context, ContextRead, ContextWrite, MainContext, SubContext};
+#[cfg(feature = "derive")]
+pub use ctxbuilder_derive::{Builder, DefaultBuilder, FromContext, IntoContext};
+#[cfg(feature = "csv")]
+pub mod csv;
+mod dataset;
+pub use dataset::Dataset;
+mod diff;
+pub use diff::{ContextDiff, DiffEntry};
+mod dynamic;
+pub use dynamic::{DynContext, DynRef, RawEntry};
 mod entry;
-pub use entry::Entry;
+pub use entry::{DeferredEntry, Entry, EntryState, Reservation};
+mod entry_name;
+pub use entry_name::EntryName;
+mod extract;
+pub use extract::{FromContext, MissingEntry};
+pub mod factory;
+pub use factory::{BuilderExt, Factory};
+mod finalize;
+pub use finalize::Finalize;
+mod format;
+pub use format::Generator;
+mod frozen;
+pub use frozen::FrozenContext;
+#[cfg(feature = "std")]
+mod graph;
+#[cfg(feature = "std")]
+pub use graph::{ObjectGraph, ObjectNode};
+mod hash;
+mod id;
+pub use id::{IdStrategy, Identified};
 mod impls;
+#[cfg(feature = "std")]
+mod intern;
+#[cfg(feature = "json")]
+pub mod json;
+mod layered;
+pub use layered::LayeredContext;
+mod local;
+pub use local::LocalContext;
+mod meta;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod placeholder;
+#[cfg(feature = "metrics")]
+pub use metrics::{Stats, TypeStats};
+mod overrides;
+pub use overrides::OverrideGuard;
+#[cfg(feature = "rayon")]
+mod par;
+#[cfg(feature = "regex")]
+mod pattern;
+#[cfg(feature = "bytes")]
+mod payload;
+#[cfg(feature = "bytes")]
+pub use payload::PayloadSpec;
+mod plan;
+pub use plan::{BuildPlan, BuildStep, PlanError, Planned};
+#[cfg(feature = "std")]
+mod pool;
+#[cfg(feature = "std")]
+pub use pool::{ContextPool, PooledContext};
 pub mod prelude;
+pub mod preset;
+mod provider;
+mod pushed;
+#[cfg(feature = "std")]
+mod recipe;
+#[cfg(feature = "std")]
+pub use recipe::{ParseRecipeError, Recipe, ReplayError, Replayer, Step};
+#[cfg(feature = "proptest")]
+pub mod proptest;
+#[cfg(feature = "quickcheck")]
+pub mod quickcheck;
+#[cfg(feature = "std")]
+mod report;
+#[cfg(feature = "std")]
+pub use report::{BuildProblem, BuildReport};
+mod require;
+pub use require::{MissingEntries, Requirement};
+mod rng;
+pub use rng::{Rng, SampleUniform, Weighted};
+pub mod scenario;
+#[cfg(feature = "semver")]
+mod semver_strategy;
+#[cfg(feature = "semver")]
+pub use semver_strategy::{VersionBump, VersionStrategy};
+mod sequence;
+pub use sequence::Sequence;
+#[cfg(feature = "std")]
+mod shrink;
+#[cfg(feature = "std")]
+pub use shrink::{Shrink, ShrinkReport, ShrunkEntry};
+#[cfg(feature = "insta")]
+mod snapshot;
+#[cfg(feature = "insta")]
+pub use snapshot::ContextSnapshot;
+#[cfg(feature = "std")]
+mod teardown;
+#[cfg(feature = "std")]
+pub use teardown::{Shutdown, Teardown};
+#[cfg(feature = "tempfile")]
+mod temp;
+mod tenant;
+pub use tenant::TenantContext;
+mod text;
+pub use text::{Email, FullName, Locale, PhoneNumber, Slug, Username};
+mod trace;
+#[cfg(feature = "uuid")]
+mod uuid_strategy;
+#[cfg(feature = "uuid")]
+pub use uuid_strategy::UuidStrategy;
+mod value;
+pub use value::{BoxedValue, Stored};
+mod variant;
+pub use variant::Variant;
+#[cfg(feature = "wiremock")]
+pub mod wiremock;
 
 /// Trait to build an object based on a shared [`Context`]
 pub trait Builder: Sized {
     /// Build a new object based on the [`Context`]
     fn build<C: Context>(ctx: &mut C) -> Self;
+
+    /// Entries that this builder expects to find in the [`Context`]
+    ///
+    /// This is only used for diagnostics with [`ContextRead::validate_for`], to report all the
+    /// missing entries at once instead of failing on the first one. The default implementation
+    /// requires nothing.
+    ///
+    /// ```
+    /// # use ctxbuilder::{Builder, Context, ContextRead, Requirement};
+    /// struct Order {
+    ///     customer: String,
+    ///     quantity: u32,
+    /// }
+    ///
+    /// impl Builder for Order {
+    ///     fn build<C: Context>(ctx: &mut C) -> Self {
+    ///         Self {
+    ///             customer: ctx.get::<String>().unwrap().clone(),
+    ///             quantity: *ctx.get_named("quantity").unwrap(),
+    ///         }
+    ///     }
+    ///
+    ///     fn requires() -> &'static [Requirement] {
+    ///         const REQUIRES: &[Requirement] =
+    ///             &[Requirement::of::<String>(), Requirement::named::<u32>("quantity")];
+    ///         REQUIRES
+    ///     }
+    /// }
+    ///
+    /// let ctx = ctxbuilder::ctx();
+    /// let err = ctx.validate_for::<Order>().unwrap_err();
+    /// assert_eq!(err.entries().len(), 2);
+    /// ```
+    fn requires() -> &'static [Requirement] {
+        &[]
+    }
 }
 
 /// Trait to build an object based on a shared [`Context`] and name
-pub trait NamedBuilder: Sized {
-    /// Build a new object based on a static name and the [`Context`]
-    fn build_with_name<C: Context>(ctx: &mut C, name: &'static str) -> Self;
+///
+/// Names are `&'static str` by default, but can be any type implementing [`Name`], such as an
+/// enum of roles.
+pub trait NamedBuilder<N: Name = &'static str>: Sized {
+    /// Build a new object based on a name and the [`Context`]
+    fn build_with_name<C: Context>(ctx: &mut C, name: N) -> Self;
 }
 
-type AnyMap = HashMap<(TypeId, Option<&'static str>), Box<dyn Any + Send + Sync>>;
+/// Trait for the names passed to a [`NamedBuilder`]
+///
+/// ```
+/// # use ctxbuilder::{Context, Name, NamedBuilder};
+/// #[derive(Clone, Copy)]
+/// enum Role {
+///     Admin,
+///     Guest,
+/// }
+///
+/// impl Name for Role {
+///     fn as_str(&self) -> Option<&'static str> {
+///         Some(match self {
+///             Self::Admin => "admin",
+///             Self::Guest => "guest",
+///         })
+///     }
+/// }
+///
+/// struct User {
+///     login: &'static str,
+/// }
+///
+/// impl NamedBuilder<Role> for User {
+///     fn build_with_name<C: Context>(_ctx: &mut C, role: Role) -> Self {
+///         Self {
+///             login: role.as_str().unwrap(),
+///         }
+///     }
+/// }
+///
+/// let mut ctx = ctxbuilder::ctx();
+/// assert_eq!(ctx.build_named::<User, _>(Role::Admin).login, "admin");
+/// ```
+pub trait Name: Copy {
+    /// Return this name as a string, if it has one
+    ///
+    /// This is the name recorded in traces.
+    fn as_str(&self) -> Option<&'static str>;
+}
+
+/// Trait to build an object based on a shared [`Context`] and per-call parameters
+///
+/// This is useful for arguments that don't belong in the shared context, such as a count or a
+/// status.
+pub trait ParamBuilder<P>: Sized {
+    /// Build a new object based on the parameters and the [`Context`]
+    fn build_with<C: Context>(ctx: &mut C, params: P) -> Self;
+}
+
+/// Trait to build a set of related objects at once, based on a shared [`Context`]
+///
+/// This is useful for setups where one call should produce a coherent group of objects, such
+/// as a person with their pets and account. It is usually implemented on a marker type:
+///
+/// ```
+/// # use ctxbuilder::{Context, FixtureSet};
+/// struct Person {
+///     id: u64,
+/// }
+///
+/// struct Pet {
+///     owner_id: u64,
+/// }
+///
+/// struct Household;
+///
+/// impl FixtureSet for Household {
+///     type Output = (Person, Vec<Pet>);
+///
+///     fn build_set<C: Context>(ctx: &mut C) -> Self::Output {
+///         let person = Person { id: ctx.next_seq() };
+///         let pets = (0..2).map(|_| Pet { owner_id: person.id }).collect();
+///         (person, pets)
+///     }
+/// }
+///
+/// let mut ctx = ctxbuilder::ctx();
+/// let (person, pets) = ctx.build_set::<Household>();
+/// assert!(pets.iter().all(|pet| pet.owner_id == person.id));
+/// ```
+pub trait FixtureSet {
+    /// Objects built together, usually a tuple
+    type Output;
+
+    /// Build a new set of objects based on the [`Context`]
+    fn build_set<C: Context>(ctx: &mut C) -> Self::Output;
+}
+
+/// Trait to update an existing object based on a shared [`Context`]
+///
+/// This is useful to refresh objects after the context changed, without building new ones.
+pub trait Rebuild {
+    /// Update this object based on the [`Context`]
+    fn rebuild<C: Context>(&mut self, ctx: &mut C);
+}
+
+type Key = (TypeId, Option<&'static str>);
+#[cfg(not(feature = "ordered"))]
+type AnyMap = HashMap<Key, value::Stored, hash::BuildKeyHasher>;
+#[cfg(feature = "ordered")]
+type AnyMap = indexmap::IndexMap<Key, value::Stored, hash::BuildKeyHasher>;
+type LocalMap = HashMap<Key, Box<dyn Any>, hash::BuildKeyHasher>;
+type KeySet = HashSet<Key, hash::BuildKeyHasher>;
+#[cfg(all(not(feature = "std"), not(feature = "ordered")))]
+type HashMapEntry<'c, K, V> = hashbrown::hash_map::Entry<'c, K, V, hash::BuildKeyHasher>;
+#[cfg(all(feature = "std", not(feature = "ordered")))]
+type HashMapEntry<'c, K, V> = std::collections::hash_map::Entry<'c, K, V>;
+#[cfg(feature = "ordered")]
+type HashMapEntry<'c, K, V> = indexmap::map::Entry<'c, K, V>;
+
+/// Remove an entry from a map, keeping the order of the other entries
+fn remove_stored(map: &mut AnyMap, key: &Key) -> Option<value::Stored> {
+    #[cfg(feature = "ordered")]
+    return map.shift_remove(key);
+    #[cfg(not(feature = "ordered"))]
+    map.remove(key)
+}
 
 /// Create a new [`MainContext`]
 pub fn ctx() -> MainContext {
     MainContext::new()
 }
+
+#[cfg(feature = "insta")]
+#[doc(hidden)]
+pub use insta as __insta;
+
+/// Get the name of an entry namespaced by a type, for `#[derive(DefaultBuilder)]`
+#[doc(hidden)]
+pub fn __namespaced(namespace: &'static str, name: &'static str) -> &'static str {
+    let name = alloc::format!("{namespace}.{name}");
+    #[cfg(feature = "std")]
+    return intern::intern(&name);
+    // Without `std`, there is no lock to intern names with, so every call leaks its name
+    #[cfg(not(feature = "std"))]
+    Box::leak(name.into_boxed_str())
+}
+
+/// Create a new [`MainContext`] from a list of entries
+///
+/// Each item is either a value inserted by type, a `"name" => value` pair inserted by name and
+/// type, or `seed: value` to seed the random number generator. This expands to the same inserts as
+/// a chain of [`Context::with`] and [`Context::with_named`] calls, but keeps dense test setups
+/// easier to scan.
+///
+/// ```
+/// # use ctxbuilder::ContextRead;
+/// #[derive(Debug, PartialEq)]
+/// enum PetType {
+///     Cat,
+///     Dog,
+/// }
+///
+/// let ctx = ctxbuilder::ctx! {
+///     PetType::Cat,
+///     "person" => 42u64,
+///     "owner" => "Alice",
+///     seed: 42,
+/// };
+///
+/// assert_eq!(ctx.get(), Some(&PetType::Cat));
+/// assert_eq!(ctx.get_named("person"), Some(&42u64));
+/// assert_eq!(ctx.rng().next_u64(), ctxbuilder::MainContext::seeded(42).rng().next_u64());
+/// ```
+#[macro_export]
+macro_rules! ctx {
+    (@items $ctx:ident;) => {};
+    (@items $ctx:ident; seed: $seed:expr $(, $($rest:tt)*)?) => {
+        $crate::ContextRead::rng(&$ctx).reseed($seed);
+        $crate::ctx!(@items $ctx; $($($rest)*)?);
+    };
+    (@items $ctx:ident; $name:literal => $val:expr $(, $($rest:tt)*)?) => {
+        $crate::ContextWrite::insert_named(&mut $ctx, $name, $val);
+        $crate::ctx!(@items $ctx; $($($rest)*)?);
+    };
+    (@items $ctx:ident; $val:expr $(, $($rest:tt)*)?) => {
+        $crate::ContextWrite::insert(&mut $ctx, $val);
+        $crate::ctx!(@items $ctx; $($($rest)*)?);
+    };
+    ($($items:tt)*) => {{
+        #[allow(unused_mut)]
+        let mut ctx = $crate::MainContext::new();
+        $crate::ctx!(@items ctx; $($items)*);
+        ctx
+    }};
+}
@@ -0,0 +1,151 @@
+use alloc::{boxed::Box, vec::Vec};
+use core::any::TypeId;
+
+use crate::{
+    dynamic::DynContext, Ancestors, ContextRead, ContextWrite, Entry, LocalMap, MainContext, Rng,
+};
+
+/// Context that can also hold values that are not `Send` or `Sync`
+///
+/// Values inserted through the [`Context`](crate::Context) trait are shared with builders as usual. Values
+/// inserted with [`insert_local`](Self::insert_local) or
+/// [`insert_local_named`](Self::insert_local_named), such as `Rc` or `RefCell`, are only
+/// available through [`get_local`](Self::get_local) and
+/// [`get_local_named`](Self::get_local_named).
+#[derive(Default)]
+pub struct LocalContext {
+    ctx: MainContext,
+    local: LocalMap,
+}
+
+impl LocalContext {
+    /// Create a new [`LocalContext`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new [`LocalContext`] with a seeded random number generator
+    pub fn seeded(seed: u64) -> Self {
+        Self {
+            ctx: MainContext::seeded(seed),
+            local: Default::default(),
+        }
+    }
+
+    /// Get a local object by its type
+    ///
+    /// This falls back to objects inserted through the [`Context`](crate::Context) trait.
+    pub fn get_local<T: 'static>(&self) -> Option<&T> {
+        self.local
+            .get(&(TypeId::of::<T>(), None))
+            .and_then(|boxed| (**boxed).downcast_ref())
+            .or_else(|| self.ctx.get_any(TypeId::of::<T>(), None))
+    }
+
+    /// Get a local object by its name and type
+    ///
+    /// This falls back to objects inserted through the [`Context`](crate::Context) trait.
+    pub fn get_local_named<T: 'static>(&self, name: &'static str) -> Option<&T> {
+        self.local
+            .get(&(TypeId::of::<T>(), Some(name)))
+            .and_then(|boxed| (**boxed).downcast_ref())
+            .or_else(|| self.ctx.get_any(TypeId::of::<T>(), Some(name)))
+    }
+
+    /// Insert a local object by type
+    pub fn insert_local<T: 'static>(&mut self, val: T) -> Option<T> {
+        self.local
+            .insert((TypeId::of::<T>(), None), Box::new(val))
+            .and_then(|boxed| boxed.downcast().ok().map(|boxed| *boxed))
+    }
+
+    /// Insert a local object by type and name
+    pub fn insert_local_named<T: 'static>(&mut self, name: &'static str, val: T) -> Option<T> {
+        self.local
+            .insert((TypeId::of::<T>(), Some(name)), Box::new(val))
+            .and_then(|boxed| boxed.downcast().ok().map(|boxed| *boxed))
+    }
+
+    /// Remove all the entries from this context, including local ones
+    ///
+    /// See [`MainContext::clear`].
+    pub fn clear(&mut self) {
+        self.ctx.clear();
+        self.local.clear();
+    }
+
+    /// Only keep the entries, including local ones, for which `f` returns `true`
+    ///
+    /// See [`MainContext::retain`].
+    pub fn retain<F: FnMut(TypeId, Option<&'static str>) -> bool>(&mut self, mut f: F) {
+        self.ctx.retain(&mut f);
+        self.local.retain(|key, _| f(key.0, key.1));
+    }
+
+    /// Convenience method to add local objects by type while constructing the [`LocalContext`]
+    pub fn with_local<T: 'static>(mut self, val: T) -> Self {
+        self.insert_local(val);
+        self
+    }
+
+    /// Convenience method to add local objects by name and type while constructing the
+    /// [`LocalContext`]
+    pub fn with_local_named<T: 'static>(mut self, name: &'static str, val: T) -> Self {
+        self.insert_local_named(name, val);
+        self
+    }
+}
+
+impl ContextRead for LocalContext {
+    fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.ctx.get()
+    }
+
+    fn get_named<T: Send + Sync + 'static>(&self, name: &'static str) -> Option<&T> {
+        self.ctx.get_named(name)
+    }
+
+    fn rng(&self) -> &Rng {
+        self.ctx.rng()
+    }
+
+    fn entry_keys(&self) -> Vec<(&'static str, Option<&'static str>)> {
+        self.ctx.entry_keys()
+    }
+
+    fn ancestors(&self) -> Ancestors<'_> {
+        self.ctx.ancestors()
+    }
+
+    #[cfg(feature = "std")]
+    fn recorder(&self) -> Option<&alloc::sync::Arc<crate::recipe::Recorder>> {
+        self.ctx.recorder()
+    }
+
+    #[cfg(feature = "metrics")]
+    fn metrics(&self) -> Option<&crate::metrics::Metrics> {
+        self.ctx.metrics()
+    }
+}
+
+impl ContextWrite for LocalContext {
+    fn entry<T: Send + Sync + 'static>(&mut self) -> Entry<'_, T> {
+        self.ctx.entry()
+    }
+
+    fn entry_named<T: Send + Sync + 'static>(&mut self, name: &'static str) -> Entry<'_, T> {
+        self.ctx.entry_named(name)
+    }
+
+    fn insert<T: Send + Sync + 'static>(&mut self, val: T) -> Option<T> {
+        self.ctx.insert(val)
+    }
+
+    fn insert_named<T: Send + Sync + 'static>(&mut self, name: &'static str, val: T) -> Option<T> {
+        self.ctx.insert_named(name, val)
+    }
+
+    fn as_dyn(&mut self) -> Option<&mut dyn DynContext> {
+        Some(&mut self.ctx)
+    }
+}
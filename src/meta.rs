@@ -0,0 +1,78 @@
+use alloc::{format, string::String};
+use core::{any::TypeId, fmt::Debug};
+
+use crate::{hash::BuildKeyHasher, value::Stored, HashMap};
+
+/// Operations registered for a type, to apply on type-erased values
+#[derive(Clone, Copy, Default)]
+pub(crate) struct TypeMeta {
+    pub(crate) clone: Option<fn(&Stored) -> Option<Stored>>,
+    pub(crate) debug: Option<fn(&Stored) -> Option<String>>,
+    pub(crate) eq: Option<fn(&Stored, &Stored) -> Option<bool>>,
+    #[cfg(feature = "std")]
+    pub(crate) display: Option<fn(&Stored) -> Option<String>>,
+    #[cfg(feature = "postcard")]
+    pub(crate) binary: Option<crate::binary::Codec>,
+    #[cfg(feature = "std")]
+    pub(crate) shrink: Option<fn(&Stored) -> alloc::vec::Vec<Stored>>,
+}
+
+/// Operations registered for the types stored in a context
+#[derive(Clone, Default)]
+pub(crate) struct Registry(HashMap<TypeId, TypeMeta, BuildKeyHasher>);
+
+impl Registry {
+    /// Iterate over the types with registered operations
+    #[cfg(feature = "postcard")]
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&TypeId, &TypeMeta)> {
+        self.0.iter()
+    }
+
+    pub(crate) fn get(&self, type_id: TypeId) -> TypeMeta {
+        self.0.get(&type_id).copied().unwrap_or_default()
+    }
+
+    /// Copy the operations registered for a type in another registry, if there are none here
+    pub(crate) fn inherit(&mut self, type_id: TypeId, meta: TypeMeta) {
+        self.0.entry(type_id).or_insert(meta);
+    }
+
+    pub(crate) fn register_clone<T: Clone + Send + Sync + 'static>(&mut self) {
+        self.0.entry(TypeId::of::<T>()).or_default().clone =
+            Some(|stored| stored.peek::<T>().cloned().map(Stored::new));
+    }
+
+    pub(crate) fn register_debug<T: Debug + 'static>(&mut self) {
+        self.0.entry(TypeId::of::<T>()).or_default().debug =
+            Some(|stored| stored.peek::<T>().map(|val| format!("{val:?}")));
+    }
+
+    pub(crate) fn register_eq<T: PartialEq + 'static>(&mut self) {
+        self.0.entry(TypeId::of::<T>()).or_default().eq =
+            Some(|a, b| Some(a.peek::<T>()? == b.peek::<T>()?));
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn register_display<T: core::fmt::Display + 'static>(&mut self) {
+        self.0.entry(TypeId::of::<T>()).or_default().display =
+            Some(|stored| stored.peek::<T>().map(|val| format!("{val}")));
+    }
+
+    #[cfg(feature = "postcard")]
+    pub(crate) fn register_binary<T>(&mut self)
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned + Send + Sync + 'static,
+    {
+        self.0.entry(TypeId::of::<T>()).or_default().binary = Some(crate::binary::Codec::of::<T>());
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn register_shrink<T: crate::Shrink + Send + Sync + 'static>(&mut self) {
+        self.0.entry(TypeId::of::<T>()).or_default().shrink = Some(|stored| {
+            stored
+                .peek::<T>()
+                .map(|val| val.shrink().into_iter().map(Stored::new).collect())
+                .unwrap_or_default()
+        });
+    }
+}
@@ -0,0 +1,85 @@
+use alloc::collections::BTreeMap;
+use core::{any::type_name, fmt};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// Counters of a context, updated as objects are built and inserted
+#[derive(Default)]
+pub struct Metrics {
+    types: Mutex<BTreeMap<&'static str, TypeStats>>,
+}
+
+impl Metrics {
+    pub(crate) fn build(&self, type_name: &'static str) {
+        self.lock().entry(type_name).or_default().builds += 1;
+    }
+
+    pub(crate) fn cache_hit(&self, type_name: &'static str) {
+        self.lock().entry(type_name).or_default().cache_hits += 1;
+    }
+
+    pub(crate) fn insert(&self, type_name: &'static str) {
+        self.lock().entry(type_name).or_default().inserts += 1;
+    }
+
+    pub(crate) fn stats(&self) -> Stats {
+        Stats {
+            types: self.lock().clone(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<&'static str, TypeStats>> {
+        self.types.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Build and insert counters per type, returned by
+/// [`ContextRead::stats`](crate::ContextRead::stats)
+///
+/// This is useful to find fixtures that are rebuilt excessively in slow test suites. Counters
+/// include the builds and inserts of the sub-contexts of a context, but not of its forks.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    types: BTreeMap<&'static str, TypeStats>,
+}
+
+/// Counters for a single type, see [`Stats`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TypeStats {
+    /// Number of objects built with [`Context::build`](crate::Context::build) or
+    /// [`Context::build_named`](crate::Context::build_named)
+    pub builds: u64,
+    /// Number of calls to [`Context::get_or_build`](crate::Context::get_or_build) or
+    /// [`Context::get_or_build_named`](crate::Context::get_or_build_named) that returned an
+    /// existing object
+    pub cache_hits: u64,
+    /// Number of objects inserted in the context
+    pub inserts: u64,
+}
+
+impl Stats {
+    /// Counters for the type `T`
+    pub fn get<T: ?Sized>(&self) -> TypeStats {
+        self.types
+            .get(type_name::<T>())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Iterate over the counters of each type, sorted by type name
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, TypeStats)> + '_ {
+        self.types.iter().map(|(name, stats)| (*name, *stats))
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (type_name, stats) in self.iter() {
+            writeln!(
+                f,
+                "{type_name}: {} builds, {} cache hits, {} inserts",
+                stats.builds, stats.cache_hits, stats.inserts
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+use core::{
+    any::TypeId,
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{remove_stored, trace, value::Stored, Key, MainContext};
+
+impl MainContext {
+    /// Replace an object by type while running `f`, then restore the previous value
+    ///
+    /// The previous value is restored even if `f` panics, which makes this safer than
+    /// inserting an override and removing it afterwards. See
+    /// [`override_guard`](Self::override_guard) to keep the override for the rest of a scope
+    /// instead.
+    ///
+    /// ```
+    /// # use ctxbuilder::{Context, ContextRead};
+    /// let mut ctx = ctxbuilder::ctx().with("eu-west-1");
+    ///
+    /// let region = ctx.with_override("us-east-1", |ctx| *ctx.get::<&str>().unwrap());
+    /// assert_eq!(region, "us-east-1");
+    /// assert_eq!(ctx.get(), Some(&"eu-west-1"));
+    /// ```
+    pub fn with_override<T: Send + Sync + 'static, R>(
+        &mut self,
+        val: T,
+        f: impl FnOnce(&mut MainContext) -> R,
+    ) -> R {
+        f(&mut self.override_guard(val))
+    }
+
+    /// Replace an object by name and type while running `f`, then restore the previous value
+    ///
+    /// See [`with_override`](Self::with_override).
+    pub fn with_override_named<T: Send + Sync + 'static, R>(
+        &mut self,
+        name: &'static str,
+        val: T,
+        f: impl FnOnce(&mut MainContext) -> R,
+    ) -> R {
+        f(&mut self.override_guard_named(name, val))
+    }
+
+    /// Replace an object by type until the returned guard is dropped
+    ///
+    /// The guard gives access to the context, and restores the previous value when it is
+    /// dropped, including during a panic. If there was no previous value, the entry is removed.
+    ///
+    /// The override and the restored value are inserts like any other: they are recorded in the
+    /// [recipe](Self::recipe) and the [history](Self::history) of the entry. Removing the entry
+    /// is not recorded, as recipes have no step for it.
+    ///
+    /// ```
+    /// # use ctxbuilder::{Context, ContextRead};
+    /// let mut ctx = ctxbuilder::ctx().with(3u32);
+    /// {
+    ///     let ctx = ctx.override_guard(5u32);
+    ///     assert_eq!(ctx.get(), Some(&5u32));
+    /// }
+    /// assert_eq!(ctx.get(), Some(&3u32));
+    /// ```
+    pub fn override_guard<T: Send + Sync + 'static>(&mut self, val: T) -> OverrideGuard<'_> {
+        OverrideGuard::new(self, None, val)
+    }
+
+    /// Replace an object by name and type until the returned guard is dropped
+    ///
+    /// See [`override_guard`](Self::override_guard).
+    pub fn override_guard_named<T: Send + Sync + 'static>(
+        &mut self,
+        name: &'static str,
+        val: T,
+    ) -> OverrideGuard<'_> {
+        OverrideGuard::new(self, Some(name), val)
+    }
+}
+
+/// Temporary override of an entry of a [`MainContext`], restored when dropped
+///
+/// Returned by [`MainContext::override_guard`] and [`MainContext::override_guard_named`].
+pub struct OverrideGuard<'c> {
+    ctx: &'c mut MainContext,
+    key: Key,
+    /// Value stored in the context itself before the override, if any
+    ///
+    /// Values shared with forked contexts are left untouched, so restoring the entry only needs
+    /// to put back the local value.
+    previous: Option<Stored>,
+}
+
+impl<'c> OverrideGuard<'c> {
+    fn new<T: Send + Sync + 'static>(
+        ctx: &'c mut MainContext,
+        name: Option<&'static str>,
+        val: T,
+    ) -> Self {
+        let key = (TypeId::of::<T>(), name);
+        let previous = ctx.insert_key(key, Stored::new(val));
+        trace::insert::<T>(name, previous.is_some());
+        Self { ctx, key, previous }
+    }
+}
+
+impl Deref for OverrideGuard<'_> {
+    type Target = MainContext;
+
+    fn deref(&self) -> &MainContext {
+        self.ctx
+    }
+}
+
+impl DerefMut for OverrideGuard<'_> {
+    fn deref_mut(&mut self) -> &mut MainContext {
+        self.ctx
+    }
+}
+
+impl Drop for OverrideGuard<'_> {
+    fn drop(&mut self) {
+        match self.previous.take() {
+            Some(previous) => {
+                self.ctx.insert_key(self.key, previous);
+            }
+            None => {
+                remove_stored(&mut self.ctx.map, &self.key);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for OverrideGuard<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OverrideGuard").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,52 @@
+//! Parallel batch building with [`rayon`](::rayon)
+
+use rayon::prelude::*;
+
+use crate::{AnyMap, Builder, Context, MainContext, Rng};
+
+impl MainContext {
+    /// Build `n` new objects in parallel
+    ///
+    /// The first object is built with this context, so that the shared values it generates are
+    /// used by all the other objects, as with [`Context::build_vec`].
+    /// The remaining objects are then built in parallel, each in its own
+    /// [`SubContext`](crate::SubContext) with a random number generator seeded from this context.
+    /// Generated values thus don't depend on thread scheduling, with the exception of
+    /// [`Sequence`](crate::Sequence)s, which are shared by all threads.
+    ///
+    /// Values generated by the parallel builds are then merged into this context in order: when
+    /// multiple objects generated the same entry, the one from the first object is kept. They are
+    /// inserted like any other value, so they are [recorded](Self::recording) and counted in the
+    /// metrics of this context.
+    pub fn build_par_vec<T: Builder + Send>(&mut self, n: usize) -> Vec<T> {
+        if n == 0 {
+            return Vec::new();
+        }
+
+        let first = self.build::<T>();
+        let base = self.rng.next_u64();
+
+        let ctx = &*self;
+        let rest: Vec<(T, AnyMap)> = (1..n)
+            .into_par_iter()
+            .map(|i| {
+                let seed = Rng::seeded(base.wrapping_add(i as u64)).next_u64();
+                let mut sub = ctx.sub_seeded(seed);
+                let value = sub.build::<T>();
+                (value, sub.map)
+            })
+            .collect();
+
+        let mut values = Vec::with_capacity(n);
+        values.push(first);
+        for (value, map) in rest {
+            for (key, val) in map {
+                if self.visible(&key).is_none() {
+                    self.insert_key(key, val);
+                }
+            }
+            values.push(value);
+        }
+        values
+    }
+}
@@ -0,0 +1,98 @@
+use alloc::string::String;
+
+use regex_syntax::hir::{Class, ClassBytes, ClassUnicode, ClassUnicodeRange, Hir, HirKind};
+
+use crate::Rng;
+
+/// Upper bound above the minimum for unbounded repetitions, such as `*` and `+`
+const MAX_EXTRA_REPEAT: u32 = 8;
+
+/// Generate a random string matching a regular expression
+///
+/// ## Panics
+///
+/// This panics if the pattern is invalid, or can't match any string.
+pub(crate) fn string_matching(rng: &Rng, pattern: &str) -> String {
+    let hir = regex_syntax::parse(pattern)
+        .unwrap_or_else(|err| panic!("invalid pattern `{pattern}`: {err}"));
+    let mut out = String::new();
+    generate(rng, &hir, pattern, &mut out);
+    out
+}
+
+fn generate(rng: &Rng, hir: &Hir, pattern: &str, out: &mut String) {
+    match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => {}
+        HirKind::Literal(literal) => {
+            out.push_str(core::str::from_utf8(&literal.0).expect("literals of patterns are UTF-8"))
+        }
+        HirKind::Class(Class::Unicode(class)) => out.push(pick_char(rng, class, pattern)),
+        HirKind::Class(Class::Bytes(class)) => out.push(pick_byte(rng, class, pattern)),
+        HirKind::Repetition(rep) => {
+            let max = rep.max.unwrap_or(rep.min.saturating_add(MAX_EXTRA_REPEAT));
+            let count = rng.gen_range(rep.min..max.saturating_add(1));
+            for _ in 0..count {
+                generate(rng, &rep.sub, pattern, out);
+            }
+        }
+        HirKind::Capture(capture) => generate(rng, &capture.sub, pattern, out),
+        HirKind::Concat(subs) => {
+            for sub in subs {
+                generate(rng, sub, pattern, out);
+            }
+        }
+        HirKind::Alternation(subs) => {
+            let sub = &subs[rng.below(subs.len() as u64) as usize];
+            generate(rng, sub, pattern, out);
+        }
+    }
+}
+
+/// Pick a character in the class, preferring printable ASCII characters
+///
+/// Unicode classes such as `\d` or `.` also match digits and letters from other scripts, which
+/// are rarely what format validations expect.
+fn pick_char(rng: &Rng, class: &ClassUnicode, pattern: &str) -> char {
+    let mut printable = ClassUnicode::new([ClassUnicodeRange::new(' ', '~')]);
+    printable.intersect(class);
+    let class = if printable.ranges().is_empty() {
+        class
+    } else {
+        &printable
+    };
+
+    let ranges = class
+        .ranges()
+        .iter()
+        .map(|range| (u32::from(range.start()), u32::from(range.end())));
+    let code = pick(rng, ranges, pattern);
+    char::from_u32(code).expect("unicode classes only contain scalar values")
+}
+
+/// Pick an ASCII character in the class
+fn pick_byte(rng: &Rng, class: &ClassBytes, pattern: &str) -> char {
+    let ranges = class
+        .ranges()
+        .iter()
+        .filter(|range| range.start().is_ascii())
+        .map(|range| (u32::from(range.start()), u32::from(range.end().min(0x7f))));
+    char::from(pick(rng, ranges, pattern) as u8)
+}
+
+/// Pick a value uniformly in a set of inclusive ranges
+fn pick(rng: &Rng, ranges: impl Iterator<Item = (u32, u32)> + Clone, pattern: &str) -> u32 {
+    let total: u64 = ranges
+        .clone()
+        .map(|(start, end)| u64::from(end - start) + 1)
+        .sum();
+    assert!(total > 0, "pattern `{pattern}` can't match any string");
+    let mut n = rng.below(total);
+    for (start, end) in ranges {
+        let len = u64::from(end - start) + 1;
+        if n < len {
+            return start + n as u32;
+        }
+        n -= len;
+    }
+    unreachable!("the index is below the total size of the ranges")
+}
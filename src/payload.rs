@@ -0,0 +1,124 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use bytes::{Bytes, BytesMut};
+
+use crate::{Builder, Context, NamedBuilder, Rng};
+
+/// Size and content of the payloads built for [`Bytes`] and [`BytesMut`], stored in a context
+///
+/// The [`Builder`] implementations for `Bytes` and `BytesMut` return the payload stored in the
+/// context if there is one. Otherwise, each build generates a new payload with the spec in the
+/// context and its [`Rng`], so seeded contexts always build the same sequence of payloads. The
+/// [`NamedBuilder`] implementations look for a payload with the same name, and for a spec with
+/// the same name first.
+/// Without a spec, payloads are empty:
+///
+/// ```
+/// # use bytes::Bytes;
+/// # use ctxbuilder::{Context, MainContext, PayloadSpec};
+/// let mut ctx = MainContext::seeded(42)
+///     .with(PayloadSpec::sized(16))
+///     .with_named("chunk", PayloadSpec::between(1024..4096).printable());
+///
+/// let body = ctx.build::<Bytes>();
+/// let chunk = ctx.build_named::<Bytes, _>("chunk");
+/// assert_eq!(body.len(), 16);
+/// assert!((1024..4096).contains(&chunk.len()));
+/// assert!(chunk.iter().all(u8::is_ascii_graphic));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PayloadSpec {
+    len: Range<usize>,
+    printable: bool,
+}
+
+impl PayloadSpec {
+    /// Generate payloads of exactly `len` bytes
+    pub fn sized(len: usize) -> Self {
+        Self::between(len..len + 1)
+    }
+
+    /// Generate payloads with a random length in the range
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the range is empty.
+    pub fn between(len: Range<usize>) -> Self {
+        assert!(len.start < len.end, "payload length range is empty");
+        Self {
+            len,
+            printable: false,
+        }
+    }
+
+    /// Only generate printable ASCII characters, which are easier to read in test failures
+    pub fn printable(mut self) -> Self {
+        self.printable = true;
+        self
+    }
+
+    /// Generate a payload with the random number generator
+    pub fn generate(&self, rng: &Rng) -> Vec<u8> {
+        let len = rng.gen_range(self.len.clone());
+        let mut payload = Vec::with_capacity(len);
+        while payload.len() < len {
+            let chunk = rng.next_u64().to_le_bytes();
+            let remaining = len - payload.len();
+            if self.printable {
+                // Map each byte onto `!..=~`
+                payload.extend(chunk.iter().take(remaining).map(|b| b'!' + b % 94));
+            } else {
+                payload.extend_from_slice(&chunk[..remaining.min(chunk.len())]);
+            }
+        }
+        payload
+    }
+}
+
+/// Get the payload stored in the context, or generate one with the payload spec
+fn payload<T, C>(ctx: &C, name: Option<&'static str>) -> T
+where
+    T: Clone + From<Bytes> + Send + Sync + 'static,
+    C: Context,
+{
+    let stored = match name {
+        Some(name) => ctx.get_named::<T>(name),
+        None => ctx.get::<T>(),
+    };
+    if let Some(stored) = stored {
+        return stored.clone();
+    }
+    let spec = name
+        .and_then(|name| ctx.get_named::<PayloadSpec>(name))
+        .or_else(|| ctx.get::<PayloadSpec>());
+    Bytes::from(
+        spec.map(|spec| spec.generate(ctx.rng()))
+            .unwrap_or_default(),
+    )
+    .into()
+}
+
+impl Builder for Bytes {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        payload(ctx, None)
+    }
+}
+
+impl NamedBuilder for Bytes {
+    fn build_with_name<C: Context>(ctx: &mut C, name: &'static str) -> Self {
+        payload(ctx, Some(name))
+    }
+}
+
+impl Builder for BytesMut {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        payload(ctx, None)
+    }
+}
+
+impl NamedBuilder for BytesMut {
+    fn build_with_name<C: Context>(ctx: &mut C, name: &'static str) -> Self {
+        payload(ctx, Some(name))
+    }
+}
@@ -0,0 +1,84 @@
+use alloc::string::String;
+use core::fmt;
+
+use crate::ContextRead;
+
+/// Named entry of a common type, substituted for a placeholder by [`Context::format`] and JSON
+/// templates
+///
+/// Both kinds of templates look up entries with [`Placeholder::lookup`], so they support the
+/// same types.
+///
+/// [`Context::format`]: crate::Context::format
+pub(crate) enum Placeholder<'c> {
+    #[cfg(feature = "json")]
+    Json(&'c serde_json::Value),
+    Str(&'c str),
+    #[cfg(feature = "uuid")]
+    Uuid(uuid::Uuid),
+    Bool(bool),
+    Int(i64),
+    Uint(u64),
+    Float(f64),
+}
+
+impl<'c> Placeholder<'c> {
+    /// Get the named entry, trying each common type in turn
+    pub(crate) fn lookup<C: ContextRead>(ctx: &'c C, name: &'static str) -> Option<Self> {
+        #[cfg(feature = "json")]
+        if let Some(v) = ctx.get_named::<serde_json::Value>(name) {
+            return Some(Self::Json(v));
+        }
+        if let Some(v) = ctx.get_named::<String>(name) {
+            return Some(Self::Str(v));
+        }
+        if let Some(v) = ctx.get_named::<&'static str>(name) {
+            return Some(Self::Str(v));
+        }
+        #[cfg(feature = "uuid")]
+        if let Some(v) = ctx.get_named::<uuid::Uuid>(name) {
+            return Some(Self::Uuid(*v));
+        }
+        if let Some(v) = ctx.get_named::<bool>(name) {
+            return Some(Self::Bool(*v));
+        }
+        if let Some(v) = ctx.get_named::<i64>(name) {
+            return Some(Self::Int(*v));
+        }
+        if let Some(v) = ctx.get_named::<u64>(name) {
+            return Some(Self::Uint(*v));
+        }
+        if let Some(v) = ctx.get_named::<i32>(name) {
+            return Some(Self::Int((*v).into()));
+        }
+        if let Some(v) = ctx.get_named::<u32>(name) {
+            return Some(Self::Uint((*v).into()));
+        }
+        if let Some(v) = ctx.get_named::<usize>(name) {
+            return Some(Self::Uint(*v as u64));
+        }
+        if let Some(v) = ctx.get_named::<f64>(name) {
+            return Some(Self::Float(*v));
+        }
+        None
+    }
+}
+
+impl fmt::Display for Placeholder<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            // Strings are substituted without the quotes of their JSON representation
+            #[cfg(feature = "json")]
+            Self::Json(serde_json::Value::String(v)) => f.write_str(v),
+            #[cfg(feature = "json")]
+            Self::Json(v) => write!(f, "{v}"),
+            Self::Str(v) => f.write_str(v),
+            #[cfg(feature = "uuid")]
+            Self::Uuid(v) => write!(f, "{v}"),
+            Self::Bool(v) => write!(f, "{v}"),
+            Self::Int(v) => write!(f, "{v}"),
+            Self::Uint(v) => write!(f, "{v}"),
+            Self::Float(v) => write!(f, "{v}"),
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use alloc::vec::Vec;
+use core::{
+    any::{type_name, TypeId},
+    fmt,
+};
+
+use crate::{
+    bind, dynamic::DynRef, Builder, Context, ContextRead, ContextWrite, MissingEntries, Requirement,
+};
+
+/// Type that a [`BuildPlan`] can build, with its [requirements](Builder::requires)
+///
+/// This is usually obtained through the [`Planned::PLAN`] constant of a type.
+#[derive(Clone, Copy)]
+pub struct BuildStep {
+    type_id: fn() -> TypeId,
+    type_name: fn() -> &'static str,
+    requires: fn() -> &'static [Requirement],
+    build: fn(&mut DynRef<'_>),
+}
+
+impl BuildStep {
+    /// Step to build an object of type `T` and insert it in the context
+    pub const fn of<T: Builder + Send + Sync + 'static>() -> Self {
+        Self {
+            type_id: TypeId::of::<T>,
+            type_name: type_name::<T>,
+            requires: T::requires,
+            build: build_step::<T>,
+        }
+    }
+
+    /// Type name of the object built by this step
+    pub fn type_name(&self) -> &'static str {
+        (self.type_name)()
+    }
+
+    /// Whether this step builds the entry of a requirement
+    fn provides(&self, requirement: &Requirement) -> bool {
+        requirement.key() == ((self.type_id)(), None)
+    }
+}
+
+impl fmt::Debug for BuildStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BuildStep").field(&self.type_name()).finish()
+    }
+}
+
+fn build_step<T: Builder + Send + Sync + 'static>(ctx: &mut DynRef<'_>) {
+    if ctx.get::<T>().is_none() {
+        let val = ctx.build::<T>();
+        ctx.insert(val);
+    }
+}
+
+/// Trait to get the [`BuildStep`] of a type, implemented for all the types with a [`Builder`]
+///
+/// ```
+/// # use ctxbuilder::{Builder, BuildPlan, Context, Planned};
+/// # struct Person;
+/// # impl Builder for Person {
+/// #     fn build<C: Context>(_ctx: &mut C) -> Self {
+/// #         Self
+/// #     }
+/// # }
+/// let plan = BuildPlan::new(&[Person::PLAN]).unwrap();
+/// ```
+pub trait Planned {
+    /// Step to build this type and insert it in the context
+    const PLAN: BuildStep;
+}
+
+impl<T: Builder + Send + Sync + 'static> Planned for T {
+    const PLAN: BuildStep = BuildStep::of::<T>();
+}
+
+/// Construction order for a set of types, based on their [requirements](Builder::requires)
+///
+/// A type is built after the other types of the plan that it requires, and the objects are
+/// inserted in the context as they are built, so that the builders that come later can use them.
+/// This removes the need to order build calls manually for large object graphs:
+///
+/// ```
+/// # use ctxbuilder::{Builder, BuildPlan, Context, ContextRead, Planned, Requirement};
+/// struct Person {
+///     name: String,
+/// }
+///
+/// impl Builder for Person {
+///     fn build<C: Context>(_ctx: &mut C) -> Self {
+///         Self {
+///             name: "alice".to_string(),
+///         }
+///     }
+/// }
+///
+/// struct Pet {
+///     owner: String,
+/// }
+///
+/// impl Builder for Pet {
+///     fn build<C: Context>(ctx: &mut C) -> Self {
+///         Self {
+///             owner: ctx.get::<Person>().unwrap().name.clone(),
+///         }
+///     }
+///
+///     fn requires() -> &'static [Requirement] {
+///         const REQUIRES: &[Requirement] = &[Requirement::of::<Person>()];
+///         REQUIRES
+///     }
+/// }
+///
+/// let mut ctx = ctxbuilder::ctx();
+/// ctx.build_all(&[Pet::PLAN, Person::PLAN]).unwrap();
+/// assert_eq!(ctx.get::<Pet>().unwrap().owner, "alice");
+/// ```
+#[derive(Clone, Debug)]
+pub struct BuildPlan {
+    steps: Vec<BuildStep>,
+}
+
+impl BuildPlan {
+    /// Compute the construction order of these steps
+    ///
+    /// Steps keep their relative order unless one of them requires another. Duplicate steps are
+    /// only kept once. This returns [`PlanError::Cycle`] if some types require each other.
+    pub fn new(steps: &[BuildStep]) -> Result<Self, PlanError> {
+        let mut pending: Vec<BuildStep> = Vec::with_capacity(steps.len());
+        for step in steps {
+            if !pending.iter().any(|s| (s.type_id)() == (step.type_id)()) {
+                pending.push(*step);
+            }
+        }
+
+        let mut ordered = Vec::with_capacity(pending.len());
+        while !pending.is_empty() {
+            // The first step that doesn't require any of the remaining ones is ready
+            let ready = pending.iter().position(|step| {
+                (step.requires)()
+                    .iter()
+                    .all(|req| !pending.iter().any(|other| other.provides(req)))
+            });
+            match ready {
+                Some(index) => ordered.push(pending.remove(index)),
+                None => {
+                    return Err(PlanError::Cycle(
+                        pending.iter().map(BuildStep::type_name).collect(),
+                    ))
+                }
+            }
+        }
+        Ok(Self { steps: ordered })
+    }
+
+    /// Steps of this plan, in construction order
+    pub fn steps(&self) -> &[BuildStep] {
+        &self.steps
+    }
+
+    /// Build the types of this plan in order, and insert them in the context
+    ///
+    /// Types that are already in the context are not built again. Before building anything, this
+    /// checks that the requirements of the other types which are not built by the plan are in the
+    /// context, and returns [`PlanError::Missing`] otherwise.
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the context doesn't support type-erased builds, like
+    /// [`Context::build_dyn`].
+    pub fn run<C: Context>(&self, ctx: &mut C) -> Result<(), PlanError> {
+        let external = self
+            .steps
+            .iter()
+            .filter(|step| {
+                ctx.ancestors()
+                    .resolve_key(&((step.type_id)(), None))
+                    .is_none()
+            })
+            .flat_map(|step| (step.requires)())
+            .filter(|req| !self.steps.iter().any(|step| step.provides(req)));
+        MissingEntries::check(ctx, external).map_err(PlanError::Missing)?;
+
+        let ctx = bind::dyn_context(ctx);
+        for step in &self.steps {
+            (step.build)(&mut DynRef(&mut *ctx));
+        }
+        Ok(())
+    }
+}
+
+/// Error returned when a [`BuildPlan`] can't be computed or run
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PlanError {
+    /// These types require each other, directly or indirectly
+    Cycle(Vec<&'static str>),
+    /// Entries required by the plan are neither built by it nor in the context
+    Missing(MissingEntries),
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cycle(type_names) => {
+                f.write_str("dependency cycle between")?;
+                for (i, type_name) in type_names.iter().enumerate() {
+                    let sep = if i == 0 { " " } else { ", " };
+                    write!(f, "{sep}`{type_name}`")?;
+                }
+                Ok(())
+            }
+            Self::Missing(missing) => missing.fmt(f),
+        }
+    }
+}
+
+impl core::error::Error for PlanError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Cycle(_) => None,
+            Self::Missing(missing) => Some(missing),
+        }
+    }
+}
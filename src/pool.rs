@@ -0,0 +1,172 @@
+use alloc::{boxed::Box, vec::Vec};
+use core::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+
+use crate::{ContextRead, ContextWrite, MainContext, Teardown};
+
+type SetupFn = dyn Fn() -> MainContext + Send + Sync;
+type ResetFn = dyn Fn(&mut MainContext) + Send + Sync;
+
+/// Pool of pre-warmed contexts, to share expensive setups between tests
+///
+/// The pool builds a fixed number of contexts upfront with a setup function, for example one
+/// that starts a database container and loads data into it. Each test then takes a context from
+/// the pool with [`get`](Self::get), and gives it back when the returned [`PooledContext`] is
+/// dropped.
+///
+/// Tests receive a [fork](MainContext::fork) of a pooled context, so the entries they insert are
+/// discarded when they give it back. Changes to external state, such as rows written to the
+/// database, can be undone with [`with_reset`](Self::with_reset). Note that values with interior
+/// mutability, such as [`Sequence`](crate::Sequence)s, are shared between the tests using the
+/// same pooled context. Teardown hooks registered by a test run when it gives the context back,
+/// while the ones registered by the setup only run when the pool is dropped.
+///
+/// ```
+/// # use ctxbuilder::{Context, ContextPool, ContextRead, ContextWrite, MainContext};
+/// use std::sync::LazyLock;
+///
+/// static POOL: LazyLock<ContextPool> = LazyLock::new(|| {
+///     ContextPool::new(2, || MainContext::seeded(42).with_named("dataset", vec![1u32, 2, 3]))
+/// });
+///
+/// let mut ctx = POOL.get();
+/// ctx.insert_named("retries", 3u32);
+/// assert_eq!(ctx.get_named::<Vec<u32>>("dataset").unwrap().len(), 3);
+/// drop(ctx);
+///
+/// // The next test starts from the setup again
+/// assert!(POOL.get().get_named::<u32>("retries").is_none());
+/// ```
+pub struct ContextPool {
+    idle: Mutex<Vec<MainContext>>,
+    available: Condvar,
+    size: usize,
+    reset: Option<Box<ResetFn>>,
+}
+
+impl ContextPool {
+    /// Create a pool of `size` contexts, built with `setup`
+    ///
+    /// ## Panics
+    ///
+    /// This panics if `size` is zero.
+    pub fn new<F: Fn() -> MainContext + Send + Sync + 'static>(size: usize, setup: F) -> Self {
+        assert!(size > 0, "a context pool needs at least one context");
+        let setup: Box<SetupFn> = Box::new(setup);
+        Self {
+            idle: Mutex::new((0..size).map(|_| setup()).collect()),
+            available: Condvar::new(),
+            size,
+            reset: None,
+        }
+    }
+
+    /// Run `reset` on a pooled context when a test gives it back
+    ///
+    /// This is meant to undo changes to external state, such as truncating database tables.
+    /// Entries inserted by the test don't need to be reset.
+    pub fn with_reset<F: Fn(&mut MainContext) + Send + Sync + 'static>(mut self, reset: F) -> Self {
+        self.reset = Some(Box::new(reset));
+        self
+    }
+
+    /// Number of contexts in the pool
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Number of contexts that are not used by a test
+    pub fn idle(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Take a context from the pool, waiting for one to be given back if they are all in use
+    pub fn get(&self) -> PooledContext<'_> {
+        let mut idle = self.lock();
+        let mut base = loop {
+            match idle.pop() {
+                Some(base) => break base,
+                None => {
+                    idle = self
+                        .available
+                        .wait(idle)
+                        .unwrap_or_else(PoisonError::into_inner)
+                }
+            }
+        };
+        drop(idle);
+        let mut ctx = base.fork();
+        // The fork shares the teardown hooks of the setup, give it its own so that the hooks the
+        // test registers run when it gives the context back
+        if ctx.contains::<Teardown>() {
+            ctx.insert(Teardown::default());
+        }
+        PooledContext {
+            ctx: Some(ctx),
+            base: Some(base),
+            pool: self,
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<MainContext>> {
+        self.idle.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl fmt::Debug for ContextPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ContextPool")
+            .field("size", &self.size)
+            .field("idle", &self.idle())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Context taken from a [`ContextPool`], given back when dropped
+pub struct PooledContext<'p> {
+    ctx: Option<MainContext>,
+    base: Option<MainContext>,
+    pool: &'p ContextPool,
+}
+
+impl Deref for PooledContext<'_> {
+    type Target = MainContext;
+
+    fn deref(&self) -> &MainContext {
+        self.ctx
+            .as_ref()
+            .expect("pooled context is only taken on drop")
+    }
+}
+
+impl DerefMut for PooledContext<'_> {
+    fn deref_mut(&mut self) -> &mut MainContext {
+        self.ctx
+            .as_mut()
+            .expect("pooled context is only taken on drop")
+    }
+}
+
+impl Drop for PooledContext<'_> {
+    fn drop(&mut self) {
+        // Drop the entries of the test before resetting, so that its teardown hooks run first
+        drop(self.ctx.take());
+        let Some(mut base) = self.base.take() else {
+            return;
+        };
+        if let Some(reset) = &self.pool.reset {
+            reset(&mut base);
+        }
+        self.pool.lock().push(base);
+        self.pool.available.notify_one();
+    }
+}
+
+impl fmt::Debug for PooledContext<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PooledContext").finish_non_exhaustive()
+    }
+}
@@ -1,5 +1,14 @@
 //! Prelude with trait imports
 
 pub use crate::Builder as _;
+pub use crate::BuilderExt as _;
 pub use crate::Context as _;
+pub use crate::ContextRead as _;
+pub use crate::ContextWrite as _;
+pub use crate::Factory as _;
+pub use crate::FixtureSet as _;
+pub use crate::Name as _;
 pub use crate::NamedBuilder as _;
+pub use crate::ParamBuilder as _;
+pub use crate::Planned as _;
+pub use crate::Rebuild as _;
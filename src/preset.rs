@@ -0,0 +1,134 @@
+//! Reusable bundles of context values
+//!
+//! A [`Preset`] layers a set of inserts onto a context. Presets compose: a tuple of presets is
+//! itself a preset, applied from left to right, so later presets override earlier ones.
+//!
+//! This module ships presets for common personas, such as [`AdminUser`] and [`EuRegion`]:
+//!
+//! ```
+//! use ctxbuilder::{preset, Context, Email, Username};
+//!
+//! let mut ctx = ctxbuilder::ctx().with_preset((preset::AdminUser, preset::EuRegion));
+//! assert_eq!(ctx.build::<Username>().as_str(), "admin");
+//! assert_eq!(ctx.build::<Email>().as_str(), "admin@example.de");
+//! ```
+//!
+//! Tests can define their own by implementing [`Preset`]:
+//!
+//! ```
+//! use ctxbuilder::{preset::Preset, Context, ContextRead};
+//!
+//! #[derive(Debug, PartialEq, Eq)]
+//! enum Plan {
+//!     Premium,
+//! }
+//!
+//! struct PremiumCustomer;
+//!
+//! impl Preset for PremiumCustomer {
+//!     fn apply<C: Context>(self, ctx: &mut C) {
+//!         ctx.insert(Plan::Premium);
+//!         ctx.insert_named("email", "vip@example.com");
+//!     }
+//! }
+//!
+//! let ctx = ctxbuilder::ctx().with_preset(PremiumCustomer);
+//! assert_eq!(ctx.get(), Some(&Plan::Premium));
+//! ```
+
+use alloc::{string::ToString, vec::Vec};
+
+use crate::{Context, FullName, Locale, Username};
+
+/// Bundle of values applied onto a [`Context`]
+pub trait Preset {
+    /// Apply the preset onto the context
+    fn apply<C: Context>(self, ctx: &mut C);
+}
+
+/// Preset inserting a single value by type
+#[derive(Clone, Debug)]
+pub struct Insert<T>(pub T);
+
+impl<T: Send + Sync + 'static> Preset for Insert<T> {
+    fn apply<C: Context>(self, ctx: &mut C) {
+        ctx.insert(self.0);
+    }
+}
+
+/// Preset inserting a single value by name and type
+#[derive(Clone, Debug)]
+pub struct InsertNamed<T>(pub &'static str, pub T);
+
+impl<T: Send + Sync + 'static> Preset for InsertNamed<T> {
+    fn apply<C: Context>(self, ctx: &mut C) {
+        ctx.insert_named(self.0, self.1);
+    }
+}
+
+/// Preset for an administrator as the user of the context
+///
+/// This inserts the `admin` [`Username`] and the `Admin User` [`FullName`], so the
+/// [`Email`](crate::Email) built from the username is `admin@` the domain of the
+/// [`Locale`] in the context.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AdminUser;
+
+impl Preset for AdminUser {
+    fn apply<C: Context>(self, ctx: &mut C) {
+        ctx.insert(Username("admin".to_string()));
+        ctx.insert(FullName("Admin User".to_string()));
+    }
+}
+
+/// Preset for data located in the European Union
+///
+/// This inserts the [`Locale::DeDe`] locale, and with the `aws_lambda_events` feature, the
+/// `eu-central-1` AWS `Region`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EuRegion;
+
+impl Preset for EuRegion {
+    fn apply<C: Context>(self, ctx: &mut C) {
+        ctx.insert(Locale::DeDe);
+        #[cfg(feature = "aws_lambda_events")]
+        ctx.insert(crate::aws_lambda_events::Region("eu-central-1".to_string()));
+    }
+}
+
+impl<P: Preset> Preset for Option<P> {
+    fn apply<C: Context>(self, ctx: &mut C) {
+        if let Some(preset) = self {
+            preset.apply(ctx);
+        }
+    }
+}
+
+impl<P: Preset> Preset for Vec<P> {
+    fn apply<C: Context>(self, ctx: &mut C) {
+        for preset in self {
+            preset.apply(ctx);
+        }
+    }
+}
+
+macro_rules! impl_preset_tuple {
+    ($($p:ident),+) => {
+        impl<$($p: Preset),+> Preset for ($($p,)+) {
+            #[allow(non_snake_case)]
+            fn apply<C: Context>(self, ctx: &mut C) {
+                let ($($p,)+) = self;
+                $($p.apply(ctx);)+
+            }
+        }
+    };
+}
+
+impl_preset_tuple!(P1);
+impl_preset_tuple!(P1, P2);
+impl_preset_tuple!(P1, P2, P3);
+impl_preset_tuple!(P1, P2, P3, P4);
+impl_preset_tuple!(P1, P2, P3, P4, P5);
+impl_preset_tuple!(P1, P2, P3, P4, P5, P6);
+impl_preset_tuple!(P1, P2, P3, P4, P5, P6, P7);
+impl_preset_tuple!(P1, P2, P3, P4, P5, P6, P7, P8);
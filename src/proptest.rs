@@ -0,0 +1,101 @@
+//! Bridge between [`proptest`](::proptest) and [`Builder`]s
+//!
+//! [`Arb<T>`] builds any [`Arbitrary`] type from a context, drawing from the context's
+//! [`Rng`](crate::Rng), while [`strategy`] turns a [`Builder`] into a [`Strategy`] that can be
+//! used in `proptest!` blocks.
+
+use std::{fmt, marker::PhantomData, sync::Arc};
+
+use ::proptest::{
+    arbitrary::Arbitrary,
+    prelude::Rng as _,
+    strategy::{Just, NewTree, Strategy, ValueTree},
+    test_runner::{Config, RngAlgorithm, TestRng, TestRunner},
+};
+
+use crate::{Builder, Context, MainContext};
+
+/// Wrapper building an [`Arbitrary`] value from the context's random number generator
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Arb<T>(pub T);
+
+impl<T: Arbitrary> Builder for Arb<T> {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Arb(arbitrary(ctx))
+    }
+}
+
+/// Generate an [`Arbitrary`] value from the context's random number generator
+///
+/// ## Panics
+///
+/// This panics if the strategy for `T` rejects every value it generates.
+pub fn arbitrary<T: Arbitrary, C: Context>(ctx: &mut C) -> T {
+    let mut seed = [0u8; 32];
+    for chunk in seed.chunks_mut(8) {
+        chunk.copy_from_slice(&ctx.rng().next_u64().to_le_bytes());
+    }
+    let mut runner = TestRunner::new_with_rng(
+        Config::default(),
+        TestRng::from_seed(RngAlgorithm::ChaCha, &seed),
+    );
+
+    T::arbitrary()
+        .new_tree(&mut runner)
+        .expect("generate an arbitrary value")
+        .current()
+}
+
+/// Strategy building values with a [`Builder`]
+///
+/// Each value is built from a fresh [`MainContext`] seeded by the test runner. Values built this
+/// way are not shrunk.
+pub struct BuilderStrategy<T> {
+    setup: Arc<dyn Fn(&mut MainContext) + Send + Sync>,
+    _phantom_data: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for BuilderStrategy<T> {
+    fn clone(&self) -> Self {
+        Self {
+            setup: self.setup.clone(),
+            _phantom_data: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for BuilderStrategy<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BuilderStrategy")
+            .field("type", &std::any::type_name::<T>())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Builder + Clone + fmt::Debug> Strategy for BuilderStrategy<T> {
+    type Tree = Just<T>;
+    type Value = T;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let mut ctx = MainContext::seeded(runner.rng().next_u64());
+        (self.setup)(&mut ctx);
+        Ok(Just(ctx.build()))
+    }
+}
+
+/// Create a [`Strategy`] building values with a [`Builder`]
+pub fn strategy<T: Builder + Clone + fmt::Debug>() -> BuilderStrategy<T> {
+    strategy_with(|_| {})
+}
+
+/// Create a [`Strategy`] building values with a [`Builder`], from contexts prepared by `setup`
+pub fn strategy_with<T, F>(setup: F) -> BuilderStrategy<T>
+where
+    T: Builder + Clone + fmt::Debug,
+    F: Fn(&mut MainContext) + Send + Sync + 'static,
+{
+    BuilderStrategy {
+        setup: Arc::new(setup),
+        _phantom_data: PhantomData,
+    }
+}
@@ -0,0 +1,7 @@
+use crate::factory::FnBuilder;
+
+/// Lazy constructor of a named entry, see [`Context::provide_named`](crate::Context::provide_named)
+///
+/// This wraps the closure so that it doesn't collide with [`FnBuilder`]s stored under the same
+/// name.
+pub(crate) struct Provider<T>(pub(crate) FnBuilder<T>);
@@ -0,0 +1,19 @@
+use alloc::{sync::Arc, vec::Vec};
+
+/// Values appended with [`Context::push`](crate::Context::push), stored under their own key
+///
+/// Values are behind an [`Arc`] so that a sub-context can copy the list of its parent before
+/// appending to it, without requiring `T: Clone`.
+pub(crate) struct Pushed<T>(pub(crate) Vec<Arc<T>>);
+
+impl<T> Clone for Pushed<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Default for Pushed<T> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
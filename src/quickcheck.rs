@@ -0,0 +1,39 @@
+//! Bridge between [`quickcheck`](::quickcheck) and [`Builder`]s
+//!
+//! [`Arb<T>`] builds any [`Arbitrary`] type from a context, seeding quickcheck's generator from
+//! the context's [`Rng`](crate::Rng), so that existing quickcheck generators can be reused as
+//! builders.
+
+use ::quickcheck::{Arbitrary, Gen};
+
+use crate::{Builder, Context};
+
+/// Size given to quickcheck's generator by [`arbitrary()`]
+///
+/// This is the same default size as quickcheck uses for its own tests.
+pub const DEFAULT_SIZE: usize = 100;
+
+/// Wrapper building an [`Arbitrary`] value from the context's random number generator
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Arb<T>(pub T);
+
+impl<T: Arbitrary> Builder for Arb<T> {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Arb(arbitrary(ctx))
+    }
+}
+
+/// Generate an [`Arbitrary`] value from the context's random number generator
+///
+/// The generator uses the [`DEFAULT_SIZE`]. See [`arbitrary_sized`] to control the size of
+/// generated collections.
+pub fn arbitrary<T: Arbitrary, C: Context>(ctx: &mut C) -> T {
+    arbitrary_sized(ctx, DEFAULT_SIZE)
+}
+
+/// Generate an [`Arbitrary`] value from the context's random number generator, with a generator
+/// of the given `size`
+pub fn arbitrary_sized<T: Arbitrary, C: Context>(ctx: &mut C, size: usize) -> T {
+    let mut generator = Gen::from_size_and_seed(size, ctx.rng().next_u64());
+    T::arbitrary(&mut generator)
+}
@@ -0,0 +1,516 @@
+use alloc::{
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use core::{
+    any::type_name,
+    fmt,
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use std::sync::Mutex;
+
+use crate::{
+    graph::{ObjectGraph, ObjectNode},
+    hash::BuildKeyHasher,
+    intern::intern,
+    meta::Registry,
+    value::Stored,
+    Builder, ContextWrite, HashMap, Key, MainContext, NamedBuilder,
+};
+
+/// Sequence of builds and inserts recorded on a [`MainContext`]
+///
+/// Contexts created with [`MainContext::recording`] record the objects built with
+/// [`Context::build`](crate::Context::build) and
+/// [`Context::build_named`](crate::Context::build_named), and the objects inserted in them, along
+/// with their seed. Builds and inserts made by builders are not recorded, as they happen again
+/// when replaying the recipe with a [`Replayer`].
+///
+/// Recipes can be converted to and from text with [`Display`](fmt::Display) and [`FromStr`], to
+/// save the fixtures of a flaky test and reproduce them later:
+///
+/// ```
+/// # use ctxbuilder::{Context, ContextRead, ContextWrite, MainContext, Recipe, Replayer};
+/// let mut ctx = MainContext::recording(42);
+/// ctx.register_display::<u32>();
+/// ctx.insert(7u32);
+/// let id = ctx.build::<uuid::Uuid>();
+///
+/// let recipe: Recipe = ctx.recipe().unwrap().to_string().parse().unwrap();
+/// let mut replayer = Replayer::new();
+/// replayer.register_builder::<uuid::Uuid>();
+/// replayer.register_value::<u32>();
+/// let replayed = replayer.replay(&recipe).unwrap();
+/// assert_eq!(replayed.get::<uuid::Uuid>(), Some(&id));
+/// assert_eq!(replayed.get::<u32>(), Some(&7));
+/// ```
+///
+/// The text starts with a header holding the version of the format and of the crate that wrote
+/// it, so recipes checked into a repository keep working across upgrades. Recipes written
+/// without a header by earlier versions can still be parsed, and recipes written in a newer
+/// format are rejected with an error, rather than being misread. Each step is tagged with the
+/// type name of its object, which the [`Replayer`] matches against the registered types.
+///
+/// Values that are inserted are only recorded if their type was registered with
+/// [`MainContext::register_display`]. Builds with names that are not strings, and builds running
+/// in parallel, are not recorded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Recipe {
+    seed: u64,
+    steps: Vec<Step>,
+    crate_version: Option<String>,
+}
+
+/// Single step of a [`Recipe`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Step {
+    /// Object built with the context
+    Build {
+        /// Type name of the object
+        type_name: String,
+        /// Name of the object, if it was built by name
+        name: Option<String>,
+    },
+    /// Object inserted in the context
+    Insert {
+        /// Type name of the object
+        type_name: String,
+        /// Name of the object, if it was inserted by name
+        name: Option<String>,
+        /// Value of the object, if its type was registered with
+        /// [`MainContext::register_display`]
+        value: Option<String>,
+    },
+}
+
+/// Version of the crate, written in the header of recipes
+const CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+impl Recipe {
+    /// Version of the text format of recipes
+    ///
+    /// This is bumped when the format changes in a way that older versions can't read. Recipes
+    /// without a header use version 0.
+    pub const FORMAT_VERSION: u32 = 1;
+
+    /// Version of the crate that recorded or wrote this recipe, if it is known
+    ///
+    /// This is `None` for recipes parsed from text without a header.
+    pub fn crate_version(&self) -> Option<&str> {
+        self.crate_version.as_deref()
+    }
+
+    /// Seed of the random number generator of the recorded context
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Steps of the recipe, in the order they were recorded
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+}
+
+impl fmt::Display for Recipe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "ctxbuilder\t{}\t{CRATE_VERSION}", Self::FORMAT_VERSION)?;
+        writeln!(f, "seed\t{}", self.seed)?;
+        for step in &self.steps {
+            let (kind, type_name, name, value) = match step {
+                Step::Build { type_name, name } => ("build", type_name, name, None),
+                Step::Insert {
+                    type_name,
+                    name,
+                    value,
+                } => ("insert", type_name, name, value.as_ref()),
+            };
+            write!(f, "{kind}\t{}\t", Escaped(type_name))?;
+            if let Some(name) = name {
+                write!(f, "{}", Escaped(name))?;
+            }
+            if let Some(value) = value {
+                write!(f, "\t{}", Escaped(value))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Recipe {
+    type Err = ParseRecipeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().enumerate().peekable();
+        let error = |line: usize, message| ParseRecipeError {
+            line: line + 1,
+            message,
+            version: None,
+        };
+        let mut crate_version = None;
+        if let Some((i, header)) = lines.next_if(|(_, line)| line.starts_with("ctxbuilder\t")) {
+            let mut fields = header.split('\t').skip(1);
+            let version = fields
+                .next()
+                .and_then(|version| version.parse::<u32>().ok())
+                .ok_or(error(i, "expected the format version"))?;
+            if version > Self::FORMAT_VERSION {
+                return Err(ParseRecipeError {
+                    version: Some(version),
+                    ..error(i, "unsupported format version")
+                });
+            }
+            crate_version = fields.next().map(ToString::to_string);
+        }
+        let seed = match lines.next() {
+            Some((i, line)) => line
+                .strip_prefix("seed\t")
+                .and_then(|seed| seed.parse().ok())
+                .ok_or(error(i, "expected the seed"))?,
+            None => return Err(error(0, "expected the seed")),
+        };
+
+        let mut steps = Vec::new();
+        for (i, line) in lines.filter(|(_, line)| !line.is_empty()) {
+            let mut fields = line.split('\t');
+            let kind = fields.next().unwrap_or_default();
+            let type_name = fields
+                .next()
+                .map(unescape)
+                .ok_or(error(i, "expected a type name"))?;
+            let name = fields.next().filter(|name| !name.is_empty()).map(unescape);
+            let value = fields.next().map(unescape);
+            if fields.next().is_some() {
+                return Err(error(i, "unexpected field"));
+            }
+            steps.push(match kind {
+                "build" if value.is_none() => Step::Build { type_name, name },
+                "insert" => Step::Insert {
+                    type_name,
+                    name,
+                    value,
+                },
+                _ => return Err(error(i, "unknown step")),
+            });
+        }
+        Ok(Self {
+            seed,
+            steps,
+            crate_version,
+        })
+    }
+}
+
+/// Error returned when a [`Recipe`] can't be parsed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseRecipeError {
+    line: usize,
+    message: &'static str,
+    version: Option<u32>,
+}
+
+impl ParseRecipeError {
+    /// Line where the error happened, starting at 1
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Format version of the recipe, if it is newer than [`Recipe::FORMAT_VERSION`]
+    ///
+    /// Recipes in a newer format were written by a newer version of the crate.
+    pub fn unsupported_version(&self) -> Option<u32> {
+        self.version
+    }
+}
+
+impl fmt::Display for ParseRecipeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid recipe at line {}: {}", self.line, self.message)?;
+        if let Some(version) = self.version {
+            write!(
+                f,
+                " {version}, this version of ctxbuilder reads up to version {}",
+                Recipe::FORMAT_VERSION
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl core::error::Error for ParseRecipeError {}
+
+/// Field of a recipe line, with tabs, newlines and backslashes escaped
+struct Escaped<'a>(&'a str);
+
+impl fmt::Display for Escaped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '\\' => f.write_str("\\\\")?,
+                '\t' => f.write_str("\\t")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                c => fmt::Write::write_char(f, c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn unescape(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some(c) => out.push(c),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Replay [`Recipe`]s onto new contexts
+///
+/// Recipes only contain type names, so the replayer needs to know the types they refer to: types
+/// that were built are registered with [`register_builder`](Self::register_builder) or
+/// [`register_named_builder`](Self::register_named_builder), and types that were inserted with
+/// [`register_value`](Self::register_value).
+#[derive(Default)]
+pub struct Replayer {
+    builders: HashMap<&'static str, fn(&mut MainContext), BuildKeyHasher>,
+    named_builders: HashMap<&'static str, fn(&mut MainContext, &'static str), BuildKeyHasher>,
+    values: HashMap<&'static str, InsertFn, BuildKeyHasher>,
+}
+
+/// Parse and insert a value, returning `false` if it can't be parsed
+type InsertFn = fn(&mut MainContext, Option<&'static str>, &str) -> bool;
+
+impl Replayer {
+    /// Create a new [`Replayer`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a type built with [`Context::build`](crate::Context::build)
+    pub fn register_builder<T: Builder>(&mut self) {
+        self.builders.insert(type_name::<T>(), |ctx| {
+            crate::Context::build::<T>(ctx);
+        });
+    }
+
+    /// Register a type built with [`Context::build_named`](crate::Context::build_named)
+    pub fn register_named_builder<T: NamedBuilder>(&mut self) {
+        self.named_builders.insert(type_name::<T>(), |ctx, name| {
+            crate::Context::build_named::<T, _>(ctx, name);
+        });
+    }
+
+    /// Register a type inserted in the context
+    pub fn register_value<T: FromStr + Send + Sync + 'static>(&mut self) {
+        self.values.insert(type_name::<T>(), |ctx, name, value| {
+            let Ok(val) = value.parse::<T>() else {
+                return false;
+            };
+            match name {
+                Some(name) => ctx.insert_named(name, val),
+                None => ctx.insert(val),
+            };
+            true
+        });
+    }
+
+    /// Replay a recipe onto a new context
+    ///
+    /// Names of the entries are leaked to get `&'static str`s. Each distinct name is only leaked
+    /// once, so replaying the same recipes in a loop doesn't leak more memory.
+    pub fn replay(&self, recipe: &Recipe) -> Result<MainContext, ReplayError> {
+        let mut ctx = MainContext::seeded(recipe.seed);
+        for (i, step) in recipe.steps.iter().enumerate() {
+            let error = |type_name: &String, reason| ReplayError {
+                step: i,
+                type_name: type_name.clone(),
+                reason,
+            };
+            match step {
+                Step::Build { type_name, name } => match name {
+                    Some(name) => {
+                        let build = self
+                            .named_builders
+                            .get(type_name.as_str())
+                            .ok_or_else(|| error(type_name, Reason::UnknownBuilder))?;
+                        build(&mut ctx, intern(name));
+                    }
+                    None => {
+                        let build = self
+                            .builders
+                            .get(type_name.as_str())
+                            .ok_or_else(|| error(type_name, Reason::UnknownBuilder))?;
+                        build(&mut ctx);
+                    }
+                },
+                Step::Insert {
+                    type_name,
+                    name,
+                    value,
+                } => {
+                    let value = value
+                        .as_ref()
+                        .ok_or_else(|| error(type_name, Reason::MissingValue))?;
+                    let insert = self
+                        .values
+                        .get(type_name.as_str())
+                        .ok_or_else(|| error(type_name, Reason::UnknownValue))?;
+                    if !insert(&mut ctx, name.as_deref().map(intern), value) {
+                        return Err(error(type_name, Reason::InvalidValue));
+                    }
+                }
+            }
+        }
+        Ok(ctx)
+    }
+}
+
+/// Error returned when a [`Recipe`] can't be replayed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReplayError {
+    step: usize,
+    type_name: String,
+    reason: Reason,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Reason {
+    UnknownBuilder,
+    UnknownValue,
+    MissingValue,
+    InvalidValue,
+}
+
+impl ReplayError {
+    /// Index of the step that failed
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// Type name of the step that failed
+    pub fn type_name(&self) -> &str {
+        &self.type_name
+    }
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self.reason {
+            Reason::UnknownBuilder => "no builder registered for",
+            Reason::UnknownValue => "no value type registered for",
+            Reason::MissingValue => "no value recorded for",
+            Reason::InvalidValue => "cannot parse the value of",
+        };
+        write!(f, "step {}: {reason} `{}`", self.step, self.type_name)
+    }
+}
+
+impl core::error::Error for ReplayError {}
+
+/// Steps recorded by a recording [`MainContext`]
+#[derive(Default)]
+pub struct Recorder {
+    seed: u64,
+    steps: Mutex<Vec<Step>>,
+    /// Number of builds in progress, as only the outermost ones are recorded
+    depth: AtomicUsize,
+    /// Graph of all the builds, and the nodes of the builds in progress
+    graph: Mutex<(ObjectGraph, Vec<usize>)>,
+}
+
+impl Recorder {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            ..Default::default()
+        }
+    }
+
+    /// Start a build, recording it if it isn't nested in another build and `record` is set
+    pub(crate) fn enter<T>(
+        self: &Arc<Self>,
+        name: Option<&'static str>,
+        record: bool,
+    ) -> BuildGuard {
+        if self.depth.fetch_add(1, Ordering::Relaxed) == 0 && record {
+            self.push(Step::Build {
+                type_name: type_name::<T>().to_string(),
+                name: name.map(ToString::to_string),
+            });
+        }
+        let mut graph = self.lock_graph();
+        let (graph, stack) = &mut *graph;
+        let index = graph.add(
+            ObjectNode::new(type_name::<T>(), name),
+            stack.last().copied(),
+        );
+        stack.push(index);
+        BuildGuard(self.clone())
+    }
+
+    /// Record an insert, if it isn't made by a builder
+    pub(crate) fn insert(&self, key: &Key, val: &Stored, registry: &Registry) {
+        if self.depth.load(Ordering::Relaxed) > 0 {
+            return;
+        }
+        let display = registry.get(key.0).display;
+        self.push(Step::Insert {
+            type_name: val.type_name().to_string(),
+            name: key.1.map(ToString::to_string),
+            value: display.and_then(|display| display(val)),
+        });
+    }
+
+    fn push(&self, step: Step) {
+        self.steps
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push(step);
+    }
+
+    pub(crate) fn recipe(&self) -> Recipe {
+        let steps = self
+            .steps
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        Recipe {
+            seed: self.seed,
+            steps: steps.clone(),
+            crate_version: Some(CRATE_VERSION.to_string()),
+        }
+    }
+
+    pub(crate) fn object_graph(&self) -> ObjectGraph {
+        self.lock_graph().0.clone()
+    }
+
+    fn lock_graph(&self) -> std::sync::MutexGuard<'_, (ObjectGraph, Vec<usize>)> {
+        self.graph
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Marks the end of a recorded build when dropped
+pub(crate) struct BuildGuard(Arc<Recorder>);
+
+impl Drop for BuildGuard {
+    fn drop(&mut self) {
+        self.0.depth.fetch_sub(1, Ordering::Relaxed);
+        self.0.lock_graph().1.pop();
+    }
+}
@@ -0,0 +1,213 @@
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{any::type_name, cell::RefCell, fmt};
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::{Builder, Context, ContextRead, MissingEntries, MissingEntry, Requirement};
+
+/// Problems found while building an object with [`Context::try_build`]
+///
+/// Unlike [`ContextRead::validate_for`], which only checks the requirements of one builder, the
+/// report covers every nested build. Builds carry on when entries are missing, so one run finds
+/// all the missing entries of a fixture setup. A panic stops the build, and is reported with the
+/// innermost builder that was running.
+///
+/// ```
+/// # use ctxbuilder::{Builder, Context, Requirement};
+/// struct Item;
+///
+/// impl Builder for Item {
+///     fn build<C: Context>(_ctx: &mut C) -> Self {
+///         Self
+///     }
+///
+///     fn requires() -> &'static [Requirement] {
+///         const REQUIRES: &[Requirement] = &[Requirement::named::<u32>("quantity")];
+///         REQUIRES
+///     }
+/// }
+///
+/// struct Order(Item);
+///
+/// impl Builder for Order {
+///     fn build<C: Context>(ctx: &mut C) -> Self {
+///         Self(ctx.build())
+///     }
+///
+///     fn requires() -> &'static [Requirement] {
+///         const REQUIRES: &[Requirement] = &[Requirement::named::<String>("customer")];
+///         REQUIRES
+///     }
+/// }
+///
+/// let mut ctx = ctxbuilder::ctx();
+/// let report = ctx.try_build::<Order>().err().unwrap();
+/// assert_eq!(report.problems().len(), 2);
+/// ```
+///
+/// Only the builds running on the calling thread are checked.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BuildReport {
+    type_name: &'static str,
+    problems: Vec<BuildProblem>,
+}
+
+/// Problem found by a [`BuildReport`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BuildProblem {
+    /// Entry [required](Builder::requires) by a builder that is missing from the context
+    Missing {
+        /// Type name of the object that was built
+        builder: &'static str,
+        /// Missing entry
+        entry: MissingEntry,
+    },
+    /// Builder that panicked
+    Panicked {
+        /// Type name of the object that was built
+        builder: &'static str,
+        /// Panic message, if it is a string
+        message: Option<String>,
+    },
+}
+
+impl BuildReport {
+    /// Type name of the object that was built
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Problems found during the build, in the order they happened
+    pub fn problems(&self) -> &[BuildProblem] {
+        &self.problems
+    }
+
+    /// Missing entries, in the order they were found
+    pub fn missing(&self) -> impl Iterator<Item = &MissingEntry> + '_ {
+        self.problems.iter().filter_map(|problem| match problem {
+            BuildProblem::Missing { entry, .. } => Some(entry),
+            _ => None,
+        })
+    }
+}
+
+impl fmt::Display for BuildReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} problems while building `{}`:",
+            self.problems.len(),
+            self.type_name
+        )?;
+        for problem in &self.problems {
+            match problem {
+                BuildProblem::Missing { builder, entry } => match entry.name() {
+                    Some(name) => write!(
+                        f,
+                        "\n- `{builder}` requires `{name}` of type `{}`",
+                        entry.type_name()
+                    )?,
+                    None => write!(
+                        f,
+                        "\n- `{builder}` requires an entry of type `{}`",
+                        entry.type_name()
+                    )?,
+                },
+                BuildProblem::Panicked { builder, message } => match message {
+                    Some(message) => write!(f, "\n- `{builder}` panicked: {message}")?,
+                    None => write!(f, "\n- `{builder}` panicked")?,
+                },
+            }
+        }
+        Ok(())
+    }
+}
+
+impl core::error::Error for BuildReport {}
+
+/// Report of the [`Context::try_build`] running on this thread
+#[derive(Default)]
+struct State {
+    problems: Vec<BuildProblem>,
+    /// Innermost builder that was running when the build panicked
+    panicked: Option<&'static str>,
+}
+
+std::thread_local! {
+    static REPORT: RefCell<Option<State>> = const { RefCell::new(None) };
+}
+
+/// Build `T`, collecting the problems of all the nested builds
+pub(crate) fn try_build<T: Builder, C: Context>(ctx: &mut C) -> Result<T, BuildReport> {
+    let outer = REPORT.with(|report| report.replace(Some(State::default())));
+    let result = panic::catch_unwind(AssertUnwindSafe(|| ctx.build::<T>()));
+    let state = REPORT
+        .with(|report| report.replace(outer))
+        .unwrap_or_default();
+
+    let mut problems = state.problems;
+    let val = match result {
+        Ok(val) => Some(val),
+        Err(payload) => {
+            problems.push(BuildProblem::Panicked {
+                builder: state.panicked.unwrap_or(type_name::<T>()),
+                message: panic_message(payload),
+            });
+            None
+        }
+    };
+    match val {
+        Some(val) if problems.is_empty() => Ok(val),
+        _ => Err(BuildReport {
+            type_name: type_name::<T>(),
+            problems,
+        }),
+    }
+}
+
+pub(crate) fn panic_message(payload: Box<dyn core::any::Any + Send>) -> Option<String> {
+    match payload.downcast::<String>() {
+        Ok(message) => Some(*message),
+        Err(payload) => payload.downcast_ref::<&str>().map(ToString::to_string),
+    }
+}
+
+/// Check the requirements of a nested build, if a report is being collected on this thread
+pub(crate) fn enter<T, C: ContextRead + ?Sized>(
+    ctx: &C,
+    requires: &[Requirement],
+) -> Option<ReportGuard> {
+    REPORT.with(|report| {
+        let mut report = report.borrow_mut();
+        let state = report.as_mut()?;
+        if let Err(missing) = MissingEntries::check(ctx, requires) {
+            state
+                .problems
+                .extend(missing.entries().iter().map(|entry| BuildProblem::Missing {
+                    builder: type_name::<T>(),
+                    entry: entry.clone(),
+                }));
+        }
+        Some(ReportGuard(type_name::<T>()))
+    })
+}
+
+/// Records the innermost builder when a build panics
+pub(crate) struct ReportGuard(&'static str);
+
+impl Drop for ReportGuard {
+    fn drop(&mut self) {
+        if !std::thread::panicking() {
+            return;
+        }
+        REPORT.with(|report| {
+            if let Some(state) = report.borrow_mut().as_mut() {
+                state.panicked.get_or_insert(self.0);
+            }
+        });
+    }
+}
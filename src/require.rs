@@ -0,0 +1,118 @@
+use alloc::vec::Vec;
+use core::{
+    any::{type_name, TypeId},
+    fmt,
+};
+
+use crate::{ContextRead, Key, MissingEntry};
+
+/// Entry that a [`Builder`](crate::Builder) expects to find in the context
+///
+/// Builders declare their requirements with [`Builder::requires`](crate::Builder::requires), so
+/// that [`ContextRead::validate_for`] can report all the missing entries at once.
+#[derive(Clone, Copy)]
+pub struct Requirement {
+    type_id: fn() -> TypeId,
+    type_name: fn() -> &'static str,
+    name: Option<&'static str>,
+}
+
+impl Requirement {
+    /// Require an object of type `T`
+    pub const fn of<T: 'static>() -> Self {
+        Self {
+            type_id: TypeId::of::<T>,
+            type_name: type_name::<T>,
+            name: None,
+        }
+    }
+
+    /// Require an object of type `T` with this name
+    pub const fn named<T: 'static>(name: &'static str) -> Self {
+        Self {
+            name: Some(name),
+            ..Self::of::<T>()
+        }
+    }
+
+    /// Type name of the required entry
+    pub fn type_name(&self) -> &'static str {
+        (self.type_name)()
+    }
+
+    /// Name of the required entry, if it is looked up by name
+    pub fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+
+    /// Whether the context holds this entry
+    pub fn is_met<C: ContextRead + ?Sized>(&self, ctx: &C) -> bool {
+        ctx.ancestors().resolve_key(&self.key()).is_some()
+    }
+
+    pub(crate) fn key(&self) -> Key {
+        ((self.type_id)(), self.name)
+    }
+}
+
+impl fmt::Debug for Requirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Requirement")
+            .field("type_name", &self.type_name())
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl PartialEq for Requirement {
+    fn eq(&self, other: &Self) -> bool {
+        (self.type_id)() == (other.type_id)() && self.name == other.name
+    }
+}
+
+impl Eq for Requirement {}
+
+/// Error returned by [`ContextRead::validate_for`], with all the entries missing from a context
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MissingEntries {
+    entries: Vec<MissingEntry>,
+}
+
+impl MissingEntries {
+    /// Check the requirements against a context
+    pub(crate) fn check<'r, C: ContextRead + ?Sized>(
+        ctx: &C,
+        requirements: impl IntoIterator<Item = &'r Requirement>,
+    ) -> Result<(), Self> {
+        let entries: Vec<_> = requirements
+            .into_iter()
+            .filter(|req| !req.is_met(ctx))
+            .map(|req| MissingEntry::from_parts(req.type_name(), req.name))
+            .collect();
+        if entries.is_empty() {
+            Ok(())
+        } else {
+            Err(Self { entries })
+        }
+    }
+
+    /// Missing entries, in the order of the requirements
+    pub fn entries(&self) -> &[MissingEntry] {
+        &self.entries
+    }
+}
+
+impl fmt::Display for MissingEntries {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} missing context entries:", self.entries.len())?;
+        for entry in &self.entries {
+            match entry.name() {
+                Some(name) => write!(f, "\n- `{name}` of type `{}`", entry.type_name())?,
+                None => write!(f, "\n- entry of type `{}`", entry.type_name())?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl core::error::Error for MissingEntries {}
@@ -0,0 +1,235 @@
+use alloc::vec::Vec;
+use core::{
+    ops::Range,
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+};
+#[cfg(feature = "std")]
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+};
+
+const GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Seeded random number generator shared by a context
+///
+/// This is a SplitMix64 generator: it is fast and deterministic for a given seed, but it is not
+/// suitable for cryptographic purposes. It uses interior mutability, so that a
+/// [`SubContext`](crate::SubContext) draws from the same stream as its parent.
+#[derive(Debug)]
+pub struct Rng {
+    state: AtomicU64,
+    /// Whether the seed came from the system, and can't be reproduced
+    entropy: AtomicBool,
+}
+
+impl Rng {
+    /// Create a new [`Rng`] from a seed
+    pub fn seeded(seed: u64) -> Self {
+        Self {
+            state: AtomicU64::new(seed),
+            entropy: AtomicBool::new(false),
+        }
+    }
+
+    /// Create a new [`Rng`] with a random seed
+    #[cfg(feature = "std")]
+    pub fn from_entropy() -> Self {
+        let rng = Self::seeded(RandomState::new().build_hasher().finish());
+        rng.entropy.store(true, Ordering::Relaxed);
+        rng
+    }
+
+    /// Reset the generator to a seed
+    pub fn reseed(&self, seed: u64) {
+        self.state.store(seed, Ordering::Relaxed);
+        self.entropy.store(false, Ordering::Relaxed);
+    }
+
+    /// Whether the generator still uses a seed from [`from_entropy`](Self::from_entropy)
+    #[cfg(feature = "std")]
+    pub(crate) fn is_from_entropy(&self) -> bool {
+        self.entropy.load(Ordering::Relaxed)
+    }
+
+    /// Return the next random `u64`
+    pub fn next_u64(&self) -> u64 {
+        let mut z = self
+            .state
+            .fetch_add(GAMMA, Ordering::Relaxed)
+            .wrapping_add(GAMMA);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Return a new random version 4 UUID
+    ///
+    /// Unlike `Uuid::new_v4`, this doesn't need a system source of randomness, which makes it
+    /// usable on targets such as `wasm32-unknown-unknown`.
+    #[cfg(feature = "uuid")]
+    pub fn next_uuid(&self) -> uuid::Uuid {
+        let mut bytes = [0u8; 16];
+        bytes[..8].copy_from_slice(&self.next_u64().to_le_bytes());
+        bytes[8..].copy_from_slice(&self.next_u64().to_le_bytes());
+        uuid::Builder::from_random_bytes(bytes).into_uuid()
+    }
+
+    /// Return a new ULID with the given timestamp and a random part
+    #[cfg(feature = "ulid")]
+    pub fn next_ulid(&self, timestamp_ms: u64) -> ulid::Ulid {
+        let random = (u128::from(self.next_u64()) << 64) | u128::from(self.next_u64());
+        ulid::Ulid::from_parts(timestamp_ms, random)
+    }
+
+    /// Return a random `u64` in `0..n`
+    ///
+    /// ## Panics
+    ///
+    /// This panics if `n` is zero.
+    pub fn below(&self, n: u64) -> u64 {
+        assert!(n > 0, "cannot generate a value below 0");
+        ((self.next_u64() as u128 * n as u128) >> 64) as u64
+    }
+
+    /// Return a random value in the range
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the range is empty.
+    pub fn gen_range<T: SampleUniform>(&self, range: Range<T>) -> T {
+        T::sample(self, range)
+    }
+}
+
+impl Clone for Rng {
+    /// Create a new [`Rng`] with the same state
+    fn clone(&self) -> Self {
+        Self {
+            state: AtomicU64::new(self.state.load(Ordering::Relaxed)),
+            entropy: AtomicBool::new(self.entropy.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl Default for Rng {
+    /// Create a new [`Rng`] with a random seed
+    #[cfg(feature = "std")]
+    fn default() -> Self {
+        Self::from_entropy()
+    }
+
+    /// Create a new [`Rng`] with a fixed seed, as there is no source of entropy without `std`
+    #[cfg(not(feature = "std"))]
+    fn default() -> Self {
+        Self::seeded(GAMMA)
+    }
+}
+
+/// Types that can be sampled uniformly from a [`Range`]
+pub trait SampleUniform: Sized {
+    /// Sample a value uniformly in the range
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the range is empty.
+    fn sample(rng: &Rng, range: Range<Self>) -> Self;
+}
+
+macro_rules! impl_sample_uniform_int {
+    ($($t:ty),+) => {
+        $(
+            impl SampleUniform for $t {
+                fn sample(rng: &Rng, range: Range<Self>) -> Self {
+                    assert!(range.start < range.end, "cannot sample an empty range");
+                    let span = (range.end as i128 - range.start as i128) as u64;
+                    (range.start as i128 + rng.below(span) as i128) as $t
+                }
+            }
+        )+
+    };
+}
+
+impl_sample_uniform_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+macro_rules! impl_sample_uniform_float {
+    ($($t:ty),+) => {
+        $(
+            impl SampleUniform for $t {
+                fn sample(rng: &Rng, range: Range<Self>) -> Self {
+                    assert!(range.start < range.end, "cannot sample an empty range");
+                    // 53 random bits give a uniform value in `0.0..1.0`
+                    let unit = (rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+                    let value = range.start + (range.end - range.start) * unit as $t;
+                    // Rounding can land on the upper bound for `f32`
+                    if value < range.end {
+                        value
+                    } else {
+                        range.start
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_sample_uniform_float!(f32, f64);
+
+/// Weighted set of values to pick from
+///
+/// When stored in a context, builders can use it through [`Context::pick_weighted`] to let tests
+/// override the distribution of generated values.
+///
+/// [`Context::pick_weighted`]: crate::Context::pick_weighted
+#[derive(Clone, Debug)]
+pub struct Weighted<T> {
+    values: Vec<(T, u32)>,
+    total: u64,
+}
+
+impl<T> Weighted<T> {
+    /// Create a new [`Weighted`] set of values
+    ///
+    /// ## Panics
+    ///
+    /// This panics if the sum of the weights is zero.
+    pub fn new<I: IntoIterator<Item = (T, u32)>>(values: I) -> Self {
+        let values: Vec<_> = values.into_iter().collect();
+        let total = values.iter().map(|(_, w)| *w as u64).sum();
+        assert!(
+            total > 0,
+            "weighted values must have a non-zero total weight"
+        );
+        Self { values, total }
+    }
+
+    /// Pick a value at random, according to the weights
+    pub fn pick(&self, rng: &Rng) -> &T {
+        pick(&self.values, self.total, rng)
+    }
+}
+
+/// Pick a value at random in a weighted slice
+///
+/// ## Panics
+///
+/// This panics if the sum of the weights is zero.
+pub(crate) fn pick_weighted<'a, T>(values: &'a [(T, u32)], rng: &Rng) -> &'a T {
+    let total = values.iter().map(|(_, w)| *w as u64).sum();
+    assert!(
+        total > 0,
+        "weighted values must have a non-zero total weight"
+    );
+    pick(values, total, rng)
+}
+
+fn pick<'a, T>(values: &'a [(T, u32)], total: u64, rng: &Rng) -> &'a T {
+    let mut n = rng.below(total);
+    for (value, weight) in values {
+        if n < *weight as u64 {
+            return value;
+        }
+        n -= *weight as u64;
+    }
+    unreachable!("n is below the total weight")
+}
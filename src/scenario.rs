@@ -0,0 +1,114 @@
+//! Named, reusable world states
+//!
+//! A [`Scenario`] populates a context with a whole graph of related objects, such as an order with
+//! its customer and items. Unlike a [`Preset`](crate::preset::Preset), which layers a few values
+//! onto a context, a scenario usually builds objects, and [`Context::run_scenario`] records the
+//! entries it created.
+//!
+//! ```
+//! use ctxbuilder::{scenario::Scenario, Context};
+//!
+//! struct OrderWithThreeItems;
+//!
+//! impl Scenario for OrderWithThreeItems {
+//!     fn apply<C: Context>(&self, ctx: &mut C) {
+//!         ctx.insert_named("customer", "alice".to_string());
+//!         for name in ["item-1", "item-2", "item-3"] {
+//!             let id = ctx.next_seq_named("items");
+//!             ctx.insert_named(name, id);
+//!         }
+//!     }
+//! }
+//!
+//! let mut ctx = ctxbuilder::ctx();
+//! let record = ctx.run_scenario(OrderWithThreeItems);
+//! assert_eq!(record.created().len(), 5);
+//! ```
+
+use alloc::vec::Vec;
+use core::{any::type_name, fmt};
+
+use crate::Context;
+
+/// Reusable world state applied onto a [`Context`]
+pub trait Scenario {
+    /// Populate the context
+    fn apply<C: Context>(&self, ctx: &mut C);
+
+    /// Return the name of the scenario
+    ///
+    /// This defaults to the name of the type.
+    fn name(&self) -> &'static str {
+        type_name::<Self>()
+    }
+}
+
+impl<S: Scenario> Scenario for &S {
+    fn apply<C: Context>(&self, ctx: &mut C) {
+        (**self).apply(ctx);
+    }
+
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+}
+
+/// Entries created by running a [`Scenario`], returned by [`Context::run_scenario`]
+///
+/// Entries are identified by their type name and name. Entries the scenario replaced are not
+/// listed, as they existed before.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScenarioRecord {
+    name: &'static str,
+    created: Vec<(&'static str, Option<&'static str>)>,
+}
+
+impl ScenarioRecord {
+    /// Return the name of the scenario
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Return the type and name of the created entries, sorted by type name, then by name
+    pub fn created(&self) -> &[(&'static str, Option<&'static str>)] {
+        &self.created
+    }
+
+    /// Return the names of the created entries of type `T`
+    pub fn created_named<T: ?Sized>(&self) -> Vec<&'static str> {
+        self.created
+            .iter()
+            .filter(|(type_name, _)| *type_name == core::any::type_name::<T>())
+            .filter_map(|(_, name)| *name)
+            .collect()
+    }
+}
+
+impl fmt::Display for ScenarioRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "scenario `{}`", self.name)?;
+        for (type_name, name) in &self.created {
+            match name {
+                Some(name) => write!(f, "\n+ `{name}`: {type_name}")?,
+                None => write!(f, "\n+ {type_name}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Apply a scenario and record the entries it created
+pub(crate) fn run<C: Context, S: Scenario>(ctx: &mut C, scenario: S) -> ScenarioRecord {
+    let before = ctx.entry_keys();
+    scenario.apply(ctx);
+    let mut created: Vec<_> = ctx
+        .entry_keys()
+        .into_iter()
+        .filter(|key| !before.contains(key))
+        .collect();
+    created.sort_unstable();
+    ScenarioRecord {
+        name: scenario.name(),
+        created,
+    }
+}
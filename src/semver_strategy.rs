@@ -0,0 +1,95 @@
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use semver::{BuildMetadata, Prerelease, Version};
+
+use crate::ContextRead;
+
+/// Strategy to generate versions, stored in a context
+///
+/// The [`Builder`](crate::Builder) implementations for `Version` use the strategy in the
+/// context, or `1.0.0` if there is none. Each new version bumps the base version once more,
+/// which is useful to create families of related versions for registry or package manager tests:
+///
+/// ```
+/// # use ctxbuilder::{Context, VersionBump, VersionStrategy};
+/// # use semver::Version;
+/// let mut ctx = ctxbuilder::ctx()
+///     .with(VersionStrategy::new(Version::new(1, 4, 2), VersionBump::Minor));
+///
+/// let versions: Vec<Version> = ["old", "current", "next"]
+///     .into_iter()
+///     .map(|name| ctx.build_named(name))
+///     .collect();
+/// assert_eq!(versions, ["1.4.2", "1.5.0", "1.6.0"].map(|v| v.parse().unwrap()));
+/// ```
+#[derive(Debug)]
+pub struct VersionStrategy {
+    base: Version,
+    bump: VersionBump,
+    count: AtomicU64,
+}
+
+/// Part of a [`Version`] that a [`VersionStrategy`] increments
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VersionBump {
+    /// Increment the major version, and reset the minor and patch versions
+    Major,
+    /// Increment the minor version, and reset the patch version
+    Minor,
+    /// Increment the patch version
+    Patch,
+}
+
+impl VersionStrategy {
+    /// Generate versions starting from `base`, then bumping it
+    ///
+    /// The first version is `base` itself. Bumped versions don't keep the pre-release and build
+    /// metadata of the base version.
+    pub fn new(base: Version, bump: VersionBump) -> Self {
+        Self {
+            base,
+            bump,
+            count: AtomicU64::new(0),
+        }
+    }
+
+    fn next(&self) -> Version {
+        let n = self.count.fetch_add(1, Ordering::Relaxed);
+        if n == 0 {
+            return self.base.clone();
+        }
+        let Version {
+            major,
+            minor,
+            patch,
+            ..
+        } = self.base;
+        let (major, minor, patch) = match self.bump {
+            VersionBump::Major => (major + n, 0, 0),
+            VersionBump::Minor => (major, minor + n, 0),
+            VersionBump::Patch => (major, minor, patch + n),
+        };
+        Version {
+            major,
+            minor,
+            patch,
+            pre: Prerelease::EMPTY,
+            build: BuildMetadata::EMPTY,
+        }
+    }
+}
+
+impl Default for VersionStrategy {
+    /// Generate patch versions starting from `1.0.0`
+    fn default() -> Self {
+        Self::new(Version::new(1, 0, 0), VersionBump::Patch)
+    }
+}
+
+/// Generate a new version with the strategy in the context
+pub(crate) fn next_version<C: ContextRead>(ctx: &C) -> Version {
+    match ctx.get::<VersionStrategy>() {
+        Some(strategy) => strategy.next(),
+        None => Version::new(1, 0, 0),
+    }
+}
@@ -0,0 +1,27 @@
+use core::sync::atomic::{AtomicU64, Ordering};
+
+/// Monotonic counter stored in a context
+///
+/// Sequences use interior mutability, so a [`SubContext`](crate::SubContext) advances the
+/// sequence it inherits from its parent rather than restarting it.
+#[derive(Debug)]
+pub struct Sequence(AtomicU64);
+
+impl Sequence {
+    /// Create a new [`Sequence`] starting at the given value
+    pub fn starting_at(start: u64) -> Self {
+        Self(AtomicU64::new(start))
+    }
+
+    /// Return the next value in the sequence
+    pub fn next_value(&self) -> u64 {
+        self.0.fetch_add(1, Ordering::Relaxed)
+    }
+}
+
+impl Default for Sequence {
+    /// Create a new [`Sequence`] starting at 1
+    fn default() -> Self {
+        Self::starting_at(1)
+    }
+}
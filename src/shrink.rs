@@ -0,0 +1,355 @@
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
+use core::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::{report::panic_message, value::Stored, Key, MainContext};
+
+/// Maximum number of times [`MainContext::shrink`] runs the test
+const MAX_RUNS: usize = 1_000;
+
+/// Trait for values that can be replaced by simpler ones, to find a minimal failing context with
+/// [`MainContext::shrink`]
+///
+/// ```
+/// # use ctxbuilder::Shrink;
+/// #[derive(Clone, Debug)]
+/// struct Quantity(u32);
+///
+/// impl Shrink for Quantity {
+///     fn shrink(&self) -> Vec<Self> {
+///         self.0.shrink().into_iter().map(Self).collect()
+///     }
+/// }
+///
+/// assert_eq!(Quantity(10).shrink().len(), 3);
+/// ```
+pub trait Shrink: Sized {
+    /// Return simpler versions of this value, the simplest first
+    ///
+    /// This returns nothing if the value is as simple as it gets, such as an empty string or
+    /// zero.
+    fn shrink(&self) -> Vec<Self>;
+}
+
+/// Minimal failing context found by [`MainContext::shrink`]
+pub struct ShrinkReport {
+    context: Box<MainContext>,
+    message: Option<String>,
+    shrunk: Vec<ShrunkEntry>,
+    runs: usize,
+}
+
+/// Entry simplified by [`MainContext::shrink`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShrunkEntry {
+    /// Type name of the entry
+    pub type_name: &'static str,
+    /// Name of the entry, if it is named
+    pub name: Option<&'static str>,
+    /// Simplified value, formatted with [`Debug`](fmt::Debug)
+    pub value: String,
+}
+
+impl ShrinkReport {
+    /// Minimal failing context, before the test ran on it
+    pub fn context(&self) -> &MainContext {
+        &self.context
+    }
+
+    /// Take the minimal failing context, before the test ran on it
+    pub fn into_context(self) -> MainContext {
+        *self.context
+    }
+
+    /// Panic message of the test on the minimal failing context, if it is a string
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    /// Entries that were simplified, sorted by type name and then by name
+    pub fn shrunk(&self) -> &[ShrunkEntry] {
+        &self.shrunk
+    }
+
+    /// Number of times the test ran, including the first failure
+    pub fn runs(&self) -> usize {
+        self.runs
+    }
+}
+
+impl fmt::Debug for ShrinkReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShrinkReport")
+            .field("message", &self.message)
+            .field("shrunk", &self.shrunk)
+            .field("runs", &self.runs)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for ShrinkReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.message {
+            Some(message) => write!(f, "test failed after {} runs: {message}", self.runs)?,
+            None => write!(f, "test failed after {} runs", self.runs)?,
+        }
+        for entry in &self.shrunk {
+            match entry.name {
+                Some(name) => write!(
+                    f,
+                    "\n- `{name}` of type `{}`: {}",
+                    entry.type_name, entry.value
+                )?,
+                None => write!(f, "\n- `{}`: {}", entry.type_name, entry.value)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl core::error::Error for ShrinkReport {}
+
+impl MainContext {
+    /// Register `T` as shrinkable, so that [`shrink`](Self::shrink) tries simpler values for its
+    /// entries
+    ///
+    /// This also registers `T` as [cloneable](Self::register_clone) and
+    /// [printable](Self::register_debug).
+    pub fn register_shrink<T>(&mut self)
+    where
+        T: Shrink + Clone + fmt::Debug + Send + Sync + 'static,
+    {
+        self.registry.register_shrink::<T>();
+        self.registry.register_clone::<T>();
+        self.registry.register_debug::<T>();
+    }
+
+    /// Run `test` on a fork of this context, and shrink the context if it fails
+    ///
+    /// The test fails by panicking, such as with a failed assertion. When it does, the entries
+    /// whose type was registered with [`register_shrink`](Self::register_shrink) are replaced by
+    /// simpler values one at a time, keeping each replacement for which the test still fails,
+    /// until no entry can be simplified. This returns the minimal failing context, with the
+    /// entries that were simplified.
+    ///
+    /// Each run starts from a [fork](Self::fork) of this context, with the same random number
+    /// generator state. Values with interior mutability, such as [`Sequence`](crate::Sequence)s,
+    /// are shared between runs. Panic messages are printed by the panic hook as usual.
+    ///
+    /// ```
+    /// # use ctxbuilder::{ContextRead, ContextWrite, MainContext};
+    /// let mut ctx = MainContext::new();
+    /// ctx.register_shrink::<String>();
+    /// ctx.register_shrink::<u32>();
+    /// ctx.insert_named("name", "Alice Smith".to_string());
+    /// ctx.insert_named("quantity", 42u32);
+    ///
+    /// let report = ctx
+    ///     .shrink(|ctx| {
+    ///         let quantity = *ctx.get_named::<u32>("quantity").unwrap();
+    ///         assert!(quantity < 10, "too many items");
+    ///     })
+    ///     .unwrap_err();
+    ///
+    /// assert_eq!(report.message(), Some("too many items"));
+    /// assert_eq!(report.context().get_named::<String>("name").unwrap(), "");
+    /// assert_eq!(report.context().get_named::<u32>("quantity"), Some(&10));
+    /// ```
+    pub fn shrink<F: FnMut(&mut MainContext)>(&mut self, mut test: F) -> Result<(), ShrinkReport> {
+        let mut message = match self.run_shrunk(&[], &mut test) {
+            Ok(()) => return Ok(()),
+            Err(message) => message,
+        };
+        let mut runs = 1;
+        let mut overrides: Vec<(Key, Stored)> = Vec::new();
+
+        let mut keys: Vec<_> = self
+            .entries()
+            .into_iter()
+            .filter(|(key, _)| self.registry.get(key.0).shrink.is_some())
+            .map(|(key, val)| (val.type_name(), *key))
+            .collect();
+        // Shrink entries in a stable order, so that shrinking is reproducible
+        keys.sort_unstable_by_key(|(type_name, key)| (*type_name, key.1));
+
+        'shrinking: loop {
+            for (_, key) in &keys {
+                let meta = self.registry.get(key.0);
+                let Some(shrink) = meta.shrink else {
+                    continue;
+                };
+                let index = overrides.iter().position(|(k, _)| k == key);
+                let candidates = match index {
+                    Some(index) => shrink(&overrides[index].1),
+                    None => match self.entries().into_iter().find(|(k, _)| *k == key) {
+                        Some((_, val)) => shrink(val),
+                        None => continue,
+                    },
+                };
+                for candidate in candidates {
+                    if runs >= MAX_RUNS {
+                        break 'shrinking;
+                    }
+                    let mut attempt = self.clone_overrides(&overrides);
+                    match index {
+                        Some(index) => attempt[index].1 = candidate,
+                        None => attempt.push((*key, candidate)),
+                    }
+                    runs += 1;
+                    if let Err(failure) = self.run_shrunk(&attempt, &mut test) {
+                        message = failure;
+                        overrides = attempt;
+                        continue 'shrinking;
+                    }
+                }
+            }
+            break;
+        }
+
+        let mut shrunk: Vec<_> = overrides
+            .iter()
+            .map(|(key, val)| ShrunkEntry {
+                type_name: val.type_name(),
+                name: key.1,
+                value: self
+                    .registry
+                    .get(key.0)
+                    .debug
+                    .and_then(|debug| debug(val))
+                    .unwrap_or_default(),
+            })
+            .collect();
+        shrunk.sort_unstable_by(|a, b| (a.type_name, a.name).cmp(&(b.type_name, b.name)));
+        let mut context = self.fork();
+        for (key, val) in self.clone_overrides(&overrides) {
+            context.insert_key(key, val);
+        }
+        Err(ShrinkReport {
+            context: Box::new(context),
+            message,
+            shrunk,
+            runs,
+        })
+    }
+
+    /// Copy the values replaced while shrinking
+    fn clone_overrides(&self, overrides: &[(Key, Stored)]) -> Vec<(Key, Stored)> {
+        overrides
+            .iter()
+            .filter_map(|(key, val)| Some((*key, (self.registry.get(key.0).clone?)(val)?)))
+            .collect()
+    }
+
+    /// Run the test on a fork with the replaced values, returning the panic message if it fails
+    fn run_shrunk<F: FnMut(&mut MainContext)>(
+        &mut self,
+        overrides: &[(Key, Stored)],
+        test: &mut F,
+    ) -> Result<(), Option<String>> {
+        let mut ctx = self.fork();
+        for (key, val) in self.clone_overrides(overrides) {
+            ctx.insert_key(key, val);
+        }
+        panic::catch_unwind(AssertUnwindSafe(|| test(&mut ctx))).map_err(panic_message)
+    }
+}
+
+macro_rules! impl_shrink_unsigned {
+    ($($ty:ty),*) => {
+        $(
+            impl Shrink for $ty {
+                fn shrink(&self) -> Vec<Self> {
+                    let mut candidates = Vec::new();
+                    for candidate in [0, *self / 2, self.saturating_sub(1)] {
+                        if candidate != *self && !candidates.contains(&candidate) {
+                            candidates.push(candidate);
+                        }
+                    }
+                    candidates
+                }
+            }
+        )*
+    };
+}
+
+impl_shrink_unsigned!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_shrink_signed {
+    ($($ty:ty),*) => {
+        $(
+            impl Shrink for $ty {
+                fn shrink(&self) -> Vec<Self> {
+                    let mut candidates = Vec::new();
+                    for candidate in [0, *self / 2, *self - self.signum()] {
+                        if candidate != *self && !candidates.contains(&candidate) {
+                            candidates.push(candidate);
+                        }
+                    }
+                    candidates
+                }
+            }
+        )*
+    };
+}
+
+impl_shrink_signed!(i8, i16, i32, i64, i128, isize);
+
+impl Shrink for bool {
+    fn shrink(&self) -> Vec<Self> {
+        if *self {
+            vec![false]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+impl Shrink for String {
+    fn shrink(&self) -> Vec<Self> {
+        let chars: Vec<char> = self.chars().collect();
+        let mut candidates: Vec<String> = Vec::new();
+        for len in [0, chars.len() / 2, chars.len().saturating_sub(1)] {
+            let candidate: String = chars[..len].iter().collect();
+            if len < chars.len() && !candidates.contains(&candidate) {
+                candidates.push(candidate);
+            }
+        }
+        candidates
+    }
+}
+
+impl<T: Shrink> Shrink for Option<T> {
+    fn shrink(&self) -> Vec<Self> {
+        match self {
+            Some(val) => core::iter::once(None)
+                .chain(val.shrink().into_iter().map(Some))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl<T: Clone> Shrink for Vec<T> {
+    fn shrink(&self) -> Vec<Self> {
+        let mut candidates: Vec<Vec<T>> = Vec::new();
+        let mut lens = Vec::new();
+        for len in [0, self.len() / 2, self.len().saturating_sub(1)] {
+            if len < self.len() && !lens.contains(&len) {
+                lens.push(len);
+                candidates.push(self[..len].to_vec());
+            }
+        }
+        candidates
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl Shrink for uuid::Uuid {
+    fn shrink(&self) -> Vec<Self> {
+        if self.is_nil() {
+            Vec::new()
+        } else {
+            vec![uuid::Uuid::nil()]
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+use crate::MainContext;
+
+/// Printable view of the entries of a [`MainContext`], for snapshot tests
+///
+/// Only the entries whose type was registered with [`MainContext::register_debug`] are included,
+/// with their value, sorted by type name and then by name. This makes the snapshot stable across
+/// runs, regardless of the order of the entries in the context. See
+/// [`assert_snapshot!`](crate::assert_snapshot).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContextSnapshot {
+    entries: Vec<(&'static str, Option<&'static str>, String)>,
+}
+
+impl MainContext {
+    /// Take a [`ContextSnapshot`] of the entries of this context
+    pub fn snapshot(&self) -> ContextSnapshot {
+        let mut entries: Vec<_> = self
+            .entries()
+            .into_iter()
+            .filter_map(|(key, val)| {
+                let debug = self.registry.get(key.0).debug?;
+                Some((val.type_name(), key.1, debug(val)?))
+            })
+            .collect();
+        entries.sort_unstable();
+        ContextSnapshot { entries }
+    }
+}
+
+impl fmt::Display for ContextSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (type_name, name, value) in &self.entries {
+            match name {
+                Some(name) => writeln!(f, "`{name}`: {type_name} = {value}")?,
+                None => writeln!(f, "{type_name} = {value}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compare the entries of a [`MainContext`] with a named [`insta`](::insta) snapshot
+///
+/// This catches unintended changes to the fixtures when refactoring builders. Only the entries
+/// whose type was registered with [`MainContext::register_debug`] are compared, see
+/// [`ContextSnapshot`].
+///
+/// ```no_run
+/// # use ctxbuilder::{ContextWrite, MainContext};
+/// let mut ctx = MainContext::seeded(42);
+/// ctx.register_debug::<u32>();
+/// ctx.insert_named("retries", 3u32);
+///
+/// ctxbuilder::assert_snapshot!(ctx, "after_setup");
+/// ```
+///
+/// This is a macro rather than a method so that snapshots are stored next to the test that calls
+/// it, as with [`insta::assert_snapshot!`](::insta::assert_snapshot).
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($ctx:expr, $name:expr $(,)?) => {
+        $crate::__insta::assert_snapshot!(
+            $name,
+            $crate::MainContext::snapshot(&$ctx).to_string(),
+            stringify!($ctx)
+        )
+    };
+}
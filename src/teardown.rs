@@ -0,0 +1,149 @@
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{ready, Poll},
+};
+use std::sync::Mutex;
+
+/// Cleanup hooks stored in a context
+///
+/// Hooks registered with [`Context::on_teardown`](crate::Context::on_teardown) and guards added
+/// with [`Context::add_guard`](crate::Context::add_guard) run when this value is dropped, in
+/// reverse registration order, so that resources are released before the ones they depend on.
+/// Since it is stored in the context, this happens when the [`MainContext`](crate::MainContext)
+/// holding it is dropped.
+///
+/// Like [`Sequence`](crate::Sequence)s, hooks use interior mutability: a
+/// [`SubContext`](crate::SubContext) adds its hooks to the ones of its parent if it has any, and
+/// runs them when it is dropped otherwise.
+///
+/// Hooks also run if the test panics, but a hook that panics while the thread is already
+/// panicking aborts the process.
+///
+/// Async hooks registered with
+/// [`Context::on_async_teardown`](crate::Context::on_async_teardown) only run through
+/// [`Context::shutdown`](crate::Context::shutdown), as dropping a context can't wait for them.
+/// They are dropped without running otherwise.
+#[derive(Default)]
+pub struct Teardown {
+    hooks: Mutex<Vec<Hook>>,
+}
+
+enum Hook {
+    Sync(Box<dyn FnOnce() + Send>),
+    Async(Pin<Box<dyn Future<Output = ()> + Send>>),
+}
+
+impl Teardown {
+    /// Register a hook to run on teardown
+    pub fn push<F: FnOnce() + Send + 'static>(&self, hook: F) {
+        self.lock().push(Hook::Sync(Box::new(hook)));
+    }
+
+    /// Register an async hook to run on [shutdown](Self::shutdown)
+    pub fn push_async<F: Future<Output = ()> + Send + 'static>(&self, hook: F) {
+        self.lock().push(Hook::Async(Box::pin(hook)));
+    }
+
+    /// Number of hooks that haven't run yet
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Whether all hooks already ran
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Run the registered hooks now, in reverse registration order
+    ///
+    /// Hooks registered while running are run as well. Async hooks are dropped without running.
+    pub fn run(&self) {
+        loop {
+            // The lock is released before running the hook, so that hooks can register new hooks
+            let Some(hook) = self.lock().pop() else {
+                break;
+            };
+            if let Hook::Sync(hook) = hook {
+                hook();
+            }
+        }
+    }
+
+    /// Take the registered hooks, to run them in reverse registration order with the returned
+    /// future
+    pub fn shutdown(&self) -> Shutdown {
+        Shutdown {
+            hooks: core::mem::take(&mut *self.lock()),
+            current: None,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Hook>> {
+        self.hooks
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl Drop for Teardown {
+    fn drop(&mut self) {
+        self.run();
+    }
+}
+
+impl core::fmt::Debug for Teardown {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Teardown")
+            .field("hooks", &self.len())
+            .finish()
+    }
+}
+
+/// Future running teardown hooks, returned by [`Context::shutdown`](crate::Context::shutdown)
+///
+/// Hooks run one after the other, in reverse registration order: each async hook completes before
+/// the next hook starts. If this future is dropped before completing, the remaining sync hooks
+/// still run.
+#[must_use = "futures do nothing unless awaited"]
+#[derive(Default)]
+pub struct Shutdown {
+    hooks: Vec<Hook>,
+    current: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
+}
+
+impl Future for Shutdown {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut core::task::Context<'_>) -> Poll<()> {
+        loop {
+            if let Some(current) = &mut self.current {
+                ready!(current.as_mut().poll(cx));
+                self.current = None;
+            }
+            match self.hooks.pop() {
+                Some(Hook::Sync(hook)) => hook(),
+                Some(Hook::Async(hook)) => self.current = Some(hook),
+                None => return Poll::Ready(()),
+            }
+        }
+    }
+}
+
+impl Drop for Shutdown {
+    fn drop(&mut self) {
+        while let Some(hook) = self.hooks.pop() {
+            if let Hook::Sync(hook) = hook {
+                hook();
+            }
+        }
+    }
+}
+
+impl core::fmt::Debug for Shutdown {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Shutdown")
+            .field("hooks", &self.hooks.len())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,39 @@
+//! Temporary files and directories cleaned up on teardown
+
+use std::path::Path;
+
+use tempfile::{NamedTempFile, TempDir};
+
+use crate::Context;
+
+pub(crate) fn temp_dir<'c, C: Context>(ctx: &'c mut C, name: &'static str) -> &'c Path {
+    if ctx.get_named::<TempDir>(name).is_none() {
+        let mut dir = TempDir::with_prefix(name).expect("failed to create a temporary directory");
+        // Remove the directory with the other teardown hooks rather than when the entry is dropped,
+        // so that hooks registered later, such as stopping a server using it, run first
+        dir.disable_cleanup(true);
+        let path = dir.path().to_path_buf();
+        ctx.on_teardown(move || {
+            let _ = std::fs::remove_dir_all(path);
+        });
+        ctx.insert_named(name, dir);
+    }
+    ctx.get_named::<TempDir>(name)
+        .expect("temporary directory inserted in the context")
+        .path()
+}
+
+pub(crate) fn temp_file<'c, C: Context>(ctx: &'c mut C, name: &'static str) -> &'c Path {
+    if ctx.get_named::<NamedTempFile>(name).is_none() {
+        let mut file = NamedTempFile::with_prefix(name).expect("failed to create a temporary file");
+        file.disable_cleanup(true);
+        let path = file.path().to_path_buf();
+        ctx.on_teardown(move || {
+            let _ = std::fs::remove_file(path);
+        });
+        ctx.insert_named(name, file);
+    }
+    ctx.get_named::<NamedTempFile>(name)
+        .expect("temporary file inserted in the context")
+        .path()
+}
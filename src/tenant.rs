@@ -0,0 +1,206 @@
+use alloc::vec::Vec;
+use core::any::TypeId;
+
+#[cfg(feature = "metrics")]
+use crate::metrics::Metrics;
+use crate::{
+    ancestors::{Layer, LayerKind},
+    dynamic::{DynContext, RawEntry},
+    trace,
+    value::Stored,
+    Ancestors, AnyMap, ContextRead, ContextWrite, Entry, Key, KeySet, MainContext, Rng,
+};
+
+impl MainContext {
+    /// Get the partition of a tenant in this context
+    ///
+    /// Each tenant has its own set of entries, for every type and name. Lookups in a tenant fall
+    /// back to the global entries of this context, but values inserted in a tenant are never
+    /// visible from the global entries or from the other tenants. This is useful to test
+    /// multi-tenant services, where leaking data from one tenant to another is the bug to catch.
+    ///
+    /// The entries of a tenant are kept in this context, so getting the same tenant again sees
+    /// the values inserted in it before.
+    ///
+    /// ```
+    /// # use ctxbuilder::{ContextRead, ContextWrite};
+    /// let mut ctx = ctxbuilder::ctx();
+    /// ctx.insert_named("region", "eu-west-1");
+    /// ctx.tenant("acme").insert_named("plan", "enterprise");
+    ///
+    /// let globex = ctx.tenant("globex");
+    /// assert_eq!(globex.get_named("region"), Some(&"eu-west-1"));
+    /// assert_eq!(globex.get_named::<&str>("plan"), None);
+    /// # drop(globex);
+    /// assert_eq!(ctx.tenant("acme").get_named("plan"), Some(&"enterprise"));
+    /// ```
+    pub fn tenant(&mut self, tenant: &'static str) -> TenantContext<'_> {
+        let map = self.tenants.remove(tenant).unwrap_or_default();
+        TenantContext {
+            main: self,
+            tenant,
+            map,
+        }
+    }
+
+    /// Return the names of the tenants with entries in this context, sorted
+    pub fn tenants(&self) -> Vec<&'static str> {
+        self.tenants
+            .iter()
+            .filter(|(_, map)| !map.is_empty())
+            .map(|(tenant, _)| *tenant)
+            .collect()
+    }
+
+    /// Remove all the entries of a tenant, and return how many were removed
+    pub fn remove_tenant(&mut self, tenant: &str) -> usize {
+        self.tenants.remove(tenant).map_or(0, |map| map.len())
+    }
+}
+
+/// Partition of a [`MainContext`] for a single tenant
+///
+/// Returned by [`MainContext::tenant`]. Lookups are resolved from the entries of the tenant
+/// first, then from the global entries of the main context. Inserted values are only stored for
+/// the tenant, and are kept in the main context when this is dropped.
+pub struct TenantContext<'c> {
+    main: &'c mut MainContext,
+    tenant: &'static str,
+    map: AnyMap,
+}
+
+impl TenantContext<'_> {
+    /// Return the name of this tenant
+    pub fn name(&self) -> &'static str {
+        self.tenant
+    }
+
+    /// Count an insert in the metrics of the main context
+    #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+    fn count_insert(&self, type_name: &'static str) {
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = self.metrics() {
+            metrics.insert(type_name);
+        }
+    }
+}
+
+impl Drop for TenantContext<'_> {
+    fn drop(&mut self) {
+        let map = core::mem::take(&mut self.map);
+        self.main.tenants.insert(self.tenant, map);
+    }
+}
+
+impl ContextRead for TenantContext<'_> {
+    fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.map
+            .get(&(TypeId::of::<T>(), None))
+            .and_then(Stored::downcast_ref)
+            .or_else(|| self.main.get())
+    }
+
+    fn get_named<T: Send + Sync + 'static>(&self, name: &'static str) -> Option<&T> {
+        self.map
+            .get(&(TypeId::of::<T>(), Some(name)))
+            .and_then(Stored::downcast_ref)
+            .or_else(|| self.main.get_named(name))
+    }
+
+    fn rng(&self) -> &Rng {
+        &self.main.rng
+    }
+
+    fn entry_keys(&self) -> Vec<(&'static str, Option<&'static str>)> {
+        let mut seen = KeySet::default();
+        self.map
+            .iter()
+            .chain(self.main.entries())
+            .filter(|(key, _)| seen.insert(**key))
+            .map(|(key, val)| (val.type_name(), key.1))
+            .collect()
+    }
+
+    fn ancestors(&self) -> Ancestors<'_> {
+        let main = self.main.layers(LayerKind::Parent);
+        Ancestors::new(
+            core::iter::once(Layer::new(LayerKind::Local, &self.map))
+                .chain(main)
+                .collect(),
+        )
+    }
+
+    #[cfg(feature = "metrics")]
+    fn metrics(&self) -> Option<&Metrics> {
+        self.main.metrics()
+    }
+}
+
+impl ContextWrite for TenantContext<'_> {
+    fn entry<T: Send + Sync + 'static>(&mut self) -> Entry<'_, T> {
+        let main = &*self.main;
+        Entry::inherit(
+            || main.get(),
+            self.map.entry((TypeId::of::<T>(), None)),
+            &main.rng,
+        )
+    }
+
+    fn entry_named<T: Send + Sync + 'static>(&mut self, name: &'static str) -> Entry<'_, T> {
+        let main = &*self.main;
+        Entry::inherit(
+            || main.get_named(name),
+            self.map.entry((TypeId::of::<T>(), Some(name))),
+            &main.rng,
+        )
+    }
+
+    fn insert<T: Send + Sync + 'static>(&mut self, val: T) -> Option<T> {
+        self.count_insert(core::any::type_name::<T>());
+        let old = self.map.insert((TypeId::of::<T>(), None), Stored::new(val));
+        trace::insert::<T>(None, old.is_some());
+        old.and_then(Stored::into_inner)
+    }
+
+    fn insert_named<T: Send + Sync + 'static>(&mut self, name: &'static str, val: T) -> Option<T> {
+        self.count_insert(core::any::type_name::<T>());
+        let old = self
+            .map
+            .insert((TypeId::of::<T>(), Some(name)), Stored::new(val));
+        trace::insert::<T>(Some(name), old.is_some());
+        old.and_then(Stored::into_inner)
+    }
+
+    fn as_dyn(&mut self) -> Option<&mut dyn DynContext> {
+        Some(self)
+    }
+}
+
+impl DynContext for TenantContext<'_> {
+    fn get_stored(&self, key: &Key) -> Option<&Stored> {
+        self.map.get(key).or_else(|| self.main.get_stored(key))
+    }
+
+    fn entry_stored(&mut self, key: Key) -> RawEntry<'_> {
+        let main = &*self.main;
+        RawEntry::inherit(|| main.get_stored(&key), self.map.entry(key), &main.rng)
+    }
+
+    fn insert_stored(&mut self, key: Key, val: Stored) -> Option<Stored> {
+        self.count_insert(val.type_name());
+        self.map.insert(key, val)
+    }
+
+    fn dyn_rng(&self) -> &Rng {
+        &self.main.rng
+    }
+
+    fn dyn_ancestors(&self) -> Ancestors<'_> {
+        self.ancestors()
+    }
+
+    #[cfg(feature = "metrics")]
+    fn dyn_metrics(&self) -> Option<&Metrics> {
+        self.metrics()
+    }
+}
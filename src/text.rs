@@ -0,0 +1,327 @@
+use alloc::{format, string::String};
+use core::fmt;
+
+use crate::{Builder, Context, ContextRead, NamedBuilder, Rng};
+
+const ADJECTIVES: &[&str] = &[
+    "amber", "brave", "calm", "clever", "eager", "gentle", "happy", "jolly", "kind", "lucky",
+    "merry", "quiet", "rapid", "shiny", "swift", "witty",
+];
+
+const NOUNS: &[&str] = &[
+    "badger", "falcon", "fox", "heron", "koala", "lynx", "marten", "newt", "otter", "panda",
+    "puffin", "raven", "robin", "seal", "tiger", "walrus",
+];
+
+/// Region of the data generated by the string builders, stored in a context
+///
+/// The builders for [`FullName`], [`PhoneNumber`] and [`Email`] use the locale in the context, or
+/// [`Locale::EnUs`] if there is none, so the same builders can produce region-appropriate
+/// fixtures for code that formats or validates them:
+///
+/// ```
+/// # use ctxbuilder::{Context, Locale, PhoneNumber};
+/// let mut ctx = ctxbuilder::ctx().with(Locale::FrFr);
+/// assert!(ctx.build::<PhoneNumber>().as_str().starts_with("+33 6 "));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Locale {
+    /// English, United States
+    #[default]
+    EnUs,
+    /// English, United Kingdom
+    EnGb,
+    /// French, France
+    FrFr,
+    /// German, Germany
+    DeDe,
+}
+
+impl Locale {
+    /// Return the locale in the context, or the default one
+    fn of<C: ContextRead>(ctx: &C) -> Self {
+        ctx.get::<Self>().copied().unwrap_or_default()
+    }
+
+    fn first_names(self) -> &'static [&'static str] {
+        match self {
+            Self::EnUs => &[
+                "Ava", "Ethan", "Harper", "Liam", "Mia", "Noah", "Olivia", "Mason",
+            ],
+            Self::EnGb => &[
+                "Amelia", "Arthur", "Freya", "George", "Isla", "Oliver", "Poppy", "Harry",
+            ],
+            Self::FrFr => &[
+                "Camille", "Hugo", "Chloé", "Louis", "Inès", "Gabriel", "Léa", "Jules",
+            ],
+            Self::DeDe => &[
+                "Emma", "Paul", "Hannah", "Leon", "Lina", "Felix", "Mia", "Jonas",
+            ],
+        }
+    }
+
+    fn last_names(self) -> &'static [&'static str] {
+        match self {
+            Self::EnUs => &[
+                "Johnson", "Miller", "Davis", "Garcia", "Wilson", "Moore", "Taylor", "Clark",
+            ],
+            Self::EnGb => &[
+                "Smith", "Jones", "Evans", "Hughes", "Wright", "Walker", "Hall", "Wood",
+            ],
+            Self::FrFr => &[
+                "Martin", "Bernard", "Dubois", "Moreau", "Laurent", "Girard", "Roux", "Fournier",
+            ],
+            Self::DeDe => &[
+                "Müller",
+                "Schmidt",
+                "Schneider",
+                "Fischer",
+                "Weber",
+                "Wagner",
+                "Becker",
+                "Hoffmann",
+            ],
+        }
+    }
+
+    /// Domain of the email addresses, reserved for documentation where possible
+    fn email_domain(self) -> &'static str {
+        match self {
+            Self::EnUs => "example.com",
+            Self::EnGb => "example.co.uk",
+            Self::FrFr => "example.fr",
+            Self::DeDe => "example.de",
+        }
+    }
+
+    /// Format a phone number from random digits, in ranges reserved for fiction where possible
+    fn phone_number(self, rng: &Rng) -> String {
+        let digits = |n: u32| rng.below(10u64.pow(n));
+        match self {
+            Self::EnUs => format!("+1 202-555-01{:02}", digits(2)),
+            Self::EnGb => format!("+44 7700 900{:03}", digits(3)),
+            Self::FrFr => format!(
+                "+33 6 {:02} {:02} {:02} {:02}",
+                digits(2),
+                digits(2),
+                digits(2),
+                digits(2)
+            ),
+            Self::DeDe => format!("+49 30 {:08}", digits(8)),
+        }
+    }
+}
+
+fn word<'a>(rng: &Rng, words: &[&'a str]) -> &'a str {
+    words[rng.below(words.len() as u64) as usize]
+}
+
+/// Lowercase the name, replacing anything but ASCII letters and digits with `sep`
+fn normalize(name: &str, sep: char) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                sep
+            }
+        })
+        .collect()
+}
+
+macro_rules! impl_text {
+    ($($ty:ident),*) => {
+        $(
+            impl $ty {
+                /// Return this value as a string slice
+                pub fn as_str(&self) -> &str {
+                    &self.0
+                }
+            }
+
+            impl fmt::Display for $ty {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.write_str(&self.0)
+                }
+            }
+
+            impl AsRef<str> for $ty {
+                fn as_ref(&self) -> &str {
+                    &self.0
+                }
+            }
+
+            impl From<$ty> for String {
+                fn from(val: $ty) -> Self {
+                    val.0
+                }
+            }
+        )*
+    };
+}
+
+/// Username, such as `swift_otter_3`
+///
+/// The [`Builder`] implementation combines random words from the context's [`Rng`] with the next
+/// value of its [`Sequence`](crate::Sequence), so usernames are reproducible with a seeded
+/// context and unique within it. The [`NamedBuilder`] implementation uses the name instead of the
+/// random words, such as `admin_4`. Built usernames are stored in the context, so building the
+/// same one again returns the same value.
+///
+/// ```
+/// # use ctxbuilder::{Context, Email, Username};
+/// let mut ctx = ctxbuilder::ctx();
+/// let admin = ctx.build_named::<Username, _>("admin");
+/// assert!(admin.as_str().starts_with("admin_"));
+/// assert_eq!(ctx.build_named::<Email, _>("admin").to_string(), format!("{admin}@example.com"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Username(pub String);
+
+/// Email address, such as `swift_otter_3@example.com`
+///
+/// The [`Builder`] and [`NamedBuilder`] implementations use the [`Username`] with the same name
+/// in the context, building it first if needed, at an example domain of the [`Locale`] in the
+/// context. Built addresses are stored in the context.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Email(pub String);
+
+/// URL-friendly identifier, such as `calm-heron-5`
+///
+/// Like [`Username`], the [`Builder`] implementation combines random words with the next value of
+/// the context's [`Sequence`](crate::Sequence), and the [`NamedBuilder`] implementation uses the
+/// name instead, such as `release-notes-6` for `"release notes"`. Built slugs are stored in the
+/// context.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Slug(pub String);
+
+/// Full name of a person, such as `Olivia Garcia`
+///
+/// The [`Builder`] and [`NamedBuilder`] implementations pick a first and last name common in the
+/// [`Locale`] in the context, with its [`Rng`]. Built names are stored in the context.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FullName(pub String);
+
+/// Phone number in international format, such as `+44 7700 900123`
+///
+/// The [`Builder`] and [`NamedBuilder`] implementations follow the format of the [`Locale`] in
+/// the context, with random digits from its [`Rng`]. Numbers are picked from ranges reserved for
+/// fiction where the region has one, such as `+1 202-555-01XX` in the United States. Built numbers
+/// are stored in the context.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PhoneNumber(pub String);
+
+impl_text!(Username, Email, Slug, FullName, PhoneNumber);
+
+impl Builder for Username {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        if let Some(username) = ctx.get::<Self>() {
+            return username.clone();
+        }
+        let (adjective, noun) = (word(ctx.rng(), ADJECTIVES), word(ctx.rng(), NOUNS));
+        let username = Self(format!("{adjective}_{noun}_{}", ctx.next_seq()));
+        ctx.entry().or_insert(username).clone()
+    }
+}
+
+impl NamedBuilder for Username {
+    fn build_with_name<C: Context>(ctx: &mut C, name: &'static str) -> Self {
+        if let Some(username) = ctx.get_named::<Self>(name) {
+            return username.clone();
+        }
+        let username = Self(format!("{}_{}", normalize(name, '_'), ctx.next_seq()));
+        ctx.entry_named(name).or_insert(username).clone()
+    }
+}
+
+impl Builder for Email {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        if let Some(email) = ctx.get::<Self>() {
+            return email.clone();
+        }
+        let domain = Locale::of(ctx).email_domain();
+        let email = Self(format!("{}@{domain}", ctx.build::<Username>()));
+        ctx.entry().or_insert(email).clone()
+    }
+}
+
+impl NamedBuilder for Email {
+    fn build_with_name<C: Context>(ctx: &mut C, name: &'static str) -> Self {
+        if let Some(email) = ctx.get_named::<Self>(name) {
+            return email.clone();
+        }
+        let username = ctx.build_named::<Username, _>(name);
+        let domain = Locale::of(ctx).email_domain();
+        let email = Self(format!("{username}@{domain}"));
+        ctx.entry_named(name).or_insert(email).clone()
+    }
+}
+
+impl Builder for Slug {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        if let Some(slug) = ctx.get::<Self>() {
+            return slug.clone();
+        }
+        let (adjective, noun) = (word(ctx.rng(), ADJECTIVES), word(ctx.rng(), NOUNS));
+        let slug = Self(format!("{adjective}-{noun}-{}", ctx.next_seq()));
+        ctx.entry().or_insert(slug).clone()
+    }
+}
+
+impl NamedBuilder for Slug {
+    fn build_with_name<C: Context>(ctx: &mut C, name: &'static str) -> Self {
+        if let Some(slug) = ctx.get_named::<Self>(name) {
+            return slug.clone();
+        }
+        let slug = Self(format!("{}-{}", normalize(name, '-'), ctx.next_seq()));
+        ctx.entry_named(name).or_insert(slug).clone()
+    }
+}
+
+fn full_name<C: Context>(ctx: &C) -> FullName {
+    let locale = Locale::of(ctx);
+    let first = word(ctx.rng(), locale.first_names());
+    let last = word(ctx.rng(), locale.last_names());
+    FullName(format!("{first} {last}"))
+}
+
+impl Builder for FullName {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        if let Some(name) = ctx.get::<Self>() {
+            return name.clone();
+        }
+        let name = full_name(ctx);
+        ctx.entry().or_insert(name).clone()
+    }
+}
+
+impl NamedBuilder for FullName {
+    fn build_with_name<C: Context>(ctx: &mut C, name: &'static str) -> Self {
+        if let Some(full_name) = ctx.get_named::<Self>(name) {
+            return full_name.clone();
+        }
+        let full_name = full_name(ctx);
+        ctx.entry_named(name).or_insert(full_name).clone()
+    }
+}
+
+impl Builder for PhoneNumber {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        if let Some(number) = ctx.get::<Self>() {
+            return number.clone();
+        }
+        let number = Self(Locale::of(ctx).phone_number(ctx.rng()));
+        ctx.entry().or_insert(number).clone()
+    }
+}
+
+impl NamedBuilder for PhoneNumber {
+    fn build_with_name<C: Context>(ctx: &mut C, name: &'static str) -> Self {
+        if let Some(number) = ctx.get_named::<Self>(name) {
+            return number.clone();
+        }
+        let number = Self(Locale::of(ctx).phone_number(ctx.rng()));
+        ctx.entry_named(name).or_insert(number).clone()
+    }
+}
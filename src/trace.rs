@@ -0,0 +1,44 @@
+//! Instrumentation with [`tracing`](https://docs.rs/tracing), enabled by the `tracing` feature
+//!
+//! Without that feature, these functions do nothing and are optimised away.
+
+#![cfg_attr(
+    not(feature = "tracing"),
+    allow(unused_variables, clippy::extra_unused_type_parameters)
+)]
+
+#[cfg(feature = "tracing")]
+use core::any::type_name;
+
+/// Guard for the span of a build, exited when dropped
+#[cfg(feature = "tracing")]
+pub(crate) type BuildSpan = tracing::span::EnteredSpan;
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct BuildSpan;
+
+/// Enter a span for building an object of type `T`
+#[inline]
+pub(crate) fn build<T>(name: Option<&'static str>) -> BuildSpan {
+    #[cfg(feature = "tracing")]
+    {
+        tracing::debug_span!("build", r#type = type_name::<T>(), name).entered()
+    }
+    #[cfg(not(feature = "tracing"))]
+    {
+        BuildSpan
+    }
+}
+
+/// Record an insert of an object of type `T`
+#[inline]
+pub(crate) fn insert<T>(name: Option<&'static str>, replaced: bool) {
+    #[cfg(feature = "tracing")]
+    tracing::trace!(r#type = type_name::<T>(), name, replaced, "insert");
+}
+
+/// Record a lookup of an entry of type `T`
+#[inline]
+pub(crate) fn entry<T>(name: Option<&'static str>, hit: bool) {
+    #[cfg(feature = "tracing")]
+    tracing::trace!(r#type = type_name::<T>(), name, hit, "entry");
+}
@@ -0,0 +1,89 @@
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use uuid::Uuid;
+
+use crate::{clock, ContextRead};
+
+/// Strategy to generate UUIDs, stored in a context
+///
+/// The [`Builder`](crate::Builder) implementations for `Uuid` and the `{uuid}` placeholder of
+/// [`Context::format`](crate::Context::format) use the strategy in the context, or random
+/// version 4 UUIDs if there is none. Sequential UUIDs are much easier to tell apart in test
+/// failure output:
+///
+/// ```
+/// # use ctxbuilder::{Context, UuidStrategy};
+/// # use uuid::Uuid;
+/// let mut ctx = ctxbuilder::ctx().with(UuidStrategy::prefixed(0xabcd));
+/// assert_eq!(
+///     ctx.build_named::<Uuid, _>("alice").to_string(),
+///     "00000000-0000-abcd-0000-000000000001"
+/// );
+/// ```
+#[derive(Debug)]
+pub struct UuidStrategy(Kind);
+
+#[derive(Debug)]
+enum Kind {
+    Random,
+    TimeOrdered,
+    Sequential(AtomicU64),
+    Prefixed(u64, AtomicU64),
+}
+
+impl UuidStrategy {
+    /// Generate random version 4 UUIDs with the context's [`Rng`](crate::Rng)
+    pub fn random() -> Self {
+        Self(Kind::Random)
+    }
+
+    /// Generate time-ordered version 7 UUIDs
+    ///
+    /// The timestamp comes from the context's [`Clock`](crate::Clock), and the random part
+    /// from its [`Rng`](crate::Rng).
+    pub fn time_ordered() -> Self {
+        Self(Kind::TimeOrdered)
+    }
+
+    /// Generate UUIDs counting up from `start`, such as
+    /// `00000000-0000-0000-0000-000000000001`
+    pub fn sequential(start: u64) -> Self {
+        Self(Kind::Sequential(AtomicU64::new(start)))
+    }
+
+    /// Generate UUIDs with a fixed prefix in the first half, and counting up from 1 in the
+    /// second half
+    pub fn prefixed(prefix: u64) -> Self {
+        Self(Kind::Prefixed(prefix, AtomicU64::new(1)))
+    }
+}
+
+impl Default for UuidStrategy {
+    /// Generate random version 4 UUIDs
+    fn default() -> Self {
+        Self::random()
+    }
+}
+
+/// Generate a new UUID with the strategy in the context
+pub(crate) fn next_uuid<C: ContextRead>(ctx: &C) -> Uuid {
+    let Some(UuidStrategy(kind)) = ctx.get::<UuidStrategy>() else {
+        return ctx.rng().next_uuid();
+    };
+    match kind {
+        Kind::Random => ctx.rng().next_uuid(),
+        Kind::TimeOrdered => {
+            let millis = clock::unix_time(ctx).as_millis() as u64;
+            let mut bytes = [0u8; 10];
+            bytes[..8].copy_from_slice(&ctx.rng().next_u64().to_le_bytes());
+            bytes[8..].copy_from_slice(&ctx.rng().next_u64().to_le_bytes()[..2]);
+            uuid::Builder::from_unix_timestamp_millis(millis, &bytes).into_uuid()
+        }
+        Kind::Sequential(counter) => {
+            Uuid::from_u128(u128::from(counter.fetch_add(1, Ordering::Relaxed)))
+        }
+        Kind::Prefixed(prefix, counter) => {
+            Uuid::from_u64_pair(*prefix, counter.fetch_add(1, Ordering::Relaxed))
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use core::{
+    any::{type_name, Any, TypeId},
+    fmt,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use smallbox::{smallbox, space::S4, SmallBox};
+
+/// Type-erased value stored in a context
+///
+/// Values that fit in four words (such as `Uuid`, most enums and integers) are stored inline
+/// rather than on the heap, which avoids an allocation per entry in builder-heavy code.
+///
+/// Values are stored as `Option<T>` so that they can be moved back out of the type-erased storage
+/// with [`Option::take`] when they are replaced.
+///
+/// Each value keeps track of whether it was ever read, to find entries that are never used.
+/// One-shot values, inserted with [`Context::insert_once`](crate::Context::insert_once), panic
+/// when they are read a second time.
+///
+/// Removing an entry that is shared with forked contexts stores a *tombstone* instead, which hides
+/// the shared value without modifying it.
+pub struct Stored {
+    value: SmallBox<dyn Any + Send + Sync, S4>,
+    type_name: &'static str,
+    read: AtomicBool,
+    once: bool,
+}
+
+/// Type-erased value to insert in a [`MainContext`](crate::MainContext)
+///
+/// This lets fixture libraries export bundles of entries of different types, which consumers
+/// pour into their own contexts with [`Extend`]:
+///
+/// ```
+/// # use ctxbuilder::{BoxedValue, ContextRead, MainContext};
+/// fn billing_fixtures() -> Vec<(Option<&'static str>, BoxedValue)> {
+///     vec![
+///         (Some("currency"), BoxedValue::new("EUR")),
+///         (None, BoxedValue::new(30u32)),
+///     ]
+/// }
+///
+/// let mut ctx = MainContext::new();
+/// ctx.extend(billing_fixtures());
+/// assert_eq!(ctx.get_named::<&str>("currency"), Some(&"EUR"));
+/// assert_eq!(ctx.get::<u32>(), Some(&30));
+/// ```
+pub struct BoxedValue {
+    pub(crate) type_id: TypeId,
+    pub(crate) stored: Stored,
+}
+
+impl BoxedValue {
+    /// Box a value to insert in a context
+    pub fn new<T: Send + Sync + 'static>(val: T) -> Self {
+        Self {
+            type_id: TypeId::of::<T>(),
+            stored: Stored::new(val),
+        }
+    }
+
+    /// Type name of the boxed value
+    pub fn type_name(&self) -> &'static str {
+        self.stored.type_name()
+    }
+}
+
+impl fmt::Debug for BoxedValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoxedValue")
+            .field("type_name", &self.type_name())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Value of a tombstone
+struct Removed;
+
+impl Stored {
+    /// Create a tombstone for a removed entry
+    pub(crate) fn removed() -> Self {
+        Self {
+            value: smallbox!(Removed),
+            type_name: type_name::<Removed>(),
+            read: AtomicBool::new(true),
+            once: false,
+        }
+    }
+
+    /// Whether this is a tombstone for a removed entry
+    pub(crate) fn is_removed(&self) -> bool {
+        self.value.is::<Removed>()
+    }
+
+    pub(crate) fn new<T: Send + Sync + 'static>(val: T) -> Self {
+        Self {
+            value: smallbox!(Some(val)),
+            type_name: type_name::<T>(),
+            read: AtomicBool::new(false),
+            once: false,
+        }
+    }
+
+    /// Create a value that can only be read once
+    pub(crate) fn once<T: Send + Sync + 'static>(val: T) -> Self {
+        Self {
+            once: true,
+            ..Self::new(val)
+        }
+    }
+
+    /// Downcast the stored value to `T`
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        if self.read.swap(true, Ordering::Relaxed) && self.once {
+            self.consumed();
+        }
+        self.value
+            .downcast_ref::<Option<T>>()
+            .and_then(Option::as_ref)
+    }
+
+    pub(crate) fn downcast_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        if core::mem::replace(self.read.get_mut(), true) && self.once {
+            self.consumed();
+        }
+        self.value
+            .downcast_mut::<Option<T>>()
+            .and_then(Option::as_mut)
+    }
+
+    /// Downcast the stored value without marking it as read
+    pub(crate) fn peek<T: 'static>(&self) -> Option<&T> {
+        self.value
+            .downcast_ref::<Option<T>>()
+            .and_then(Option::as_ref)
+    }
+
+    /// Downcast the stored value, panicking with the expected and actual types if it fails
+    pub(crate) fn expect_ref<T: 'static>(&self, name: Option<&'static str>) -> &T {
+        match self.downcast_ref() {
+            Some(val) => val,
+            None => self.mismatch::<T>(name),
+        }
+    }
+
+    /// Downcast the stored value, panicking with the expected and actual types if it fails
+    pub(crate) fn expect_mut<T: 'static>(&mut self, name: Option<&'static str>) -> &mut T {
+        if self.peek::<T>().is_none() {
+            self.mismatch::<T>(name);
+        }
+        self.downcast_mut().expect("value checked above")
+    }
+
+    #[cold]
+    fn consumed(&self) -> ! {
+        panic!(
+            "one-shot context entry of type `{}` was already read",
+            self.type_name
+        )
+    }
+
+    #[cold]
+    fn mismatch<T: 'static>(&self, name: Option<&'static str>) -> ! {
+        match name {
+            Some(name) => panic!(
+                "context entry `{name}` holds a `{}`, expected a `{}`",
+                self.type_name,
+                type_name::<T>()
+            ),
+            None => panic!(
+                "context entry holds a `{}`, expected a `{}`",
+                self.type_name,
+                type_name::<T>()
+            ),
+        }
+    }
+
+    pub(crate) fn into_inner<T: 'static>(mut self) -> Option<T> {
+        self.value
+            .downcast_mut::<Option<T>>()
+            .and_then(Option::take)
+    }
+
+    /// Move the stored value out, leaving the entry empty until it is
+    /// [restored](Self::restore)
+    pub(crate) fn take<T: 'static>(&mut self) -> Option<T> {
+        self.value
+            .downcast_mut::<Option<T>>()
+            .and_then(Option::take)
+    }
+
+    /// Put back a value that was [taken](Self::take), unless another one was stored since
+    pub(crate) fn restore<T: 'static>(&mut self, val: T) {
+        if let Some(slot @ None) = self.value.downcast_mut::<Option<T>>() {
+            *slot = Some(val);
+        }
+    }
+
+    /// Name of the type of the stored value
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Whether the stored value was ever read
+    pub(crate) fn is_read(&self) -> bool {
+        self.read.load(Ordering::Relaxed)
+    }
+}
+
+impl fmt::Debug for Stored {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Stored")
+            .field("type_name", &self.type_name)
+            .finish_non_exhaustive()
+    }
+}
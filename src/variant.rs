@@ -0,0 +1,39 @@
+use core::{any::type_name, fmt, marker::PhantomData};
+
+/// Variant to build for the enum `T`, stored in a context
+///
+/// This is used by the `Builder` derive for enums: the variant with this name is built, instead of
+/// the default one.
+pub struct Variant<T> {
+    name: &'static str,
+    _phantom_data: PhantomData<fn() -> T>,
+}
+
+impl<T> Variant<T> {
+    /// Select the variant with this name
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            _phantom_data: PhantomData,
+        }
+    }
+
+    /// Return the name of the selected variant
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<T> Clone for Variant<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Variant<T> {}
+
+impl<T> fmt::Debug for Variant<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Variant<{}>({:?})", type_name::<T>(), self.name)
+    }
+}
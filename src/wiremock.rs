@@ -0,0 +1,94 @@
+//! Mock HTTP servers with [`wiremock`](::wiremock)
+//!
+//! [`start_mock_server`] stores a [`MockServer`] in the context, and the [`Url`] builder then
+//! points at it. With the `axum` feature, the default [`Uri`](::axum::http::Uri) of requests
+//! points at it too, so HTTP client tests get a fake backend from the same context as their
+//! other fixtures:
+//!
+//! ```
+//! # use ctxbuilder::Context;
+//! # use url::Url;
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! let mut ctx = ctxbuilder::ctx();
+//! let server_uri = ctxbuilder::wiremock::start_mock_server(&mut ctx).await.uri();
+//!
+//! let url = ctx.build::<Url>();
+//! assert_eq!(url.as_str().trim_end_matches('/'), server_uri);
+//! # }
+//! ```
+//!
+//! The server is stopped when the context is dropped, which also verifies the expectations of
+//! its mocks.
+
+use ::wiremock::MockServer;
+use url::Url;
+
+use crate::{Builder, Context, NamedBuilder};
+
+/// Start a [`MockServer`] and store it in the context, unless it already has one
+pub async fn start_mock_server<C: Context>(ctx: &mut C) -> &MockServer {
+    if ctx.get::<MockServer>().is_none() {
+        let server = MockServer::start().await;
+        ctx.insert(server);
+    }
+    ctx.get::<MockServer>()
+        .expect("mock server inserted in the context")
+}
+
+/// Start a named [`MockServer`] and store it in the context, unless it already has one
+///
+/// This is useful for tests that talk to multiple backends. The named [`Url`] builder points at
+/// the server with the same name.
+pub async fn start_mock_server_named<'c, C: Context>(
+    ctx: &'c mut C,
+    name: &'static str,
+) -> &'c MockServer {
+    if ctx.get_named::<MockServer>(name).is_none() {
+        let server = MockServer::start().await;
+        ctx.insert_named(name, server);
+    }
+    ctx.get_named::<MockServer>(name)
+        .expect("mock server inserted in the context")
+}
+
+/// URI of the mock server in the context, if any
+pub(crate) fn mock_server_uri<C: Context>(ctx: &C, name: Option<&'static str>) -> Option<String> {
+    let server = match name {
+        Some(name) => ctx.get_named::<MockServer>(name),
+        None => ctx.get::<MockServer>(),
+    };
+    server.map(MockServer::uri)
+}
+
+fn default_url<C: Context>(ctx: &C, name: Option<&'static str>) -> Url {
+    let uri = mock_server_uri(ctx, name);
+    Url::parse(uri.as_deref().unwrap_or("http://localhost/"))
+        .expect("mock server URIs are valid URLs")
+}
+
+/// Build the base URL of the [`MockServer`] in the context
+///
+/// Without a mock server, this defaults to `http://localhost/`.
+impl Builder for Url {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        if let Some(url) = ctx.get::<Self>() {
+            return url.clone();
+        }
+        let url = default_url(ctx, None);
+        ctx.entry::<Self>().or_insert(url).clone()
+    }
+}
+
+/// Build the base URL of the [`MockServer`] with the same name in the context
+///
+/// Without a mock server, this defaults to `http://localhost/`.
+impl NamedBuilder for Url {
+    fn build_with_name<C: Context>(ctx: &mut C, name: &'static str) -> Self {
+        if let Some(url) = ctx.get_named::<Self>(name) {
+            return url.clone();
+        }
+        let url = default_url(ctx, Some(name));
+        ctx.entry_named::<Self>(name).or_insert(url).clone()
+    }
+}
@@ -0,0 +1,80 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use ctxbuilder::{ContextRead, ContextWrite, MainContext};
+
+/// Allocator counting the allocations made by the current thread
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn allocations<F: FnOnce()>(f: F) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+fn setup() -> MainContext {
+    let mut ctx = MainContext::seeded(42);
+    ctx.insert(1u32);
+    ctx.insert_named("name", "alice".to_string());
+    ctx
+}
+
+#[test]
+fn test_lookups_do_not_allocate() {
+    // GIVEN a context with entries
+    let mut ctx = setup();
+
+    // WHEN looking up existing entries
+    let count = allocations(|| {
+        assert_eq!(ctx.get::<u32>(), Some(&1));
+        assert!(ctx.get_named::<String>("name").is_some());
+        assert!(ctx.get_named::<String>("missing").is_none());
+        assert_eq!(*ctx.entry::<u32>().or_insert(2), 1);
+        ctx.entry_named::<String>("name").or_default();
+    });
+
+    // THEN nothing is allocated
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn test_layered_lookups_do_not_allocate() {
+    // GIVEN a fork and a sub-context over it, holding some entries themselves
+    let mut ctx = setup();
+    let mut fork = ctx.fork();
+    fork.insert(2u8);
+    let mut sub = fork.sub();
+    sub.insert(true);
+
+    // WHEN looking up existing entries through the layers
+    let count = allocations(|| {
+        assert_eq!(sub.get::<u32>(), Some(&1));
+        assert_eq!(sub.get::<u8>(), Some(&2));
+        assert!(sub.get_named::<String>("name").is_some());
+        assert!(*sub.entry::<bool>().or_insert(false));
+        assert_eq!(*sub.entry::<u32>().or_insert(3), 1);
+    });
+
+    // THEN nothing is allocated
+    assert_eq!(count, 0);
+}
@@ -0,0 +1,94 @@
+use ctxbuilder::{Context, ContextRead, ContextWrite, LayerKind, LayeredContext, MainContext};
+
+#[test]
+fn test_ancestors_fork() {
+    // GIVEN a forked context that shadows a shared entry
+    let mut ctx = ctxbuilder::ctx().with(1u32).with_named("name", "alice");
+    let mut fork = ctx.fork();
+    fork.insert(2u32);
+
+    // WHEN iterating over its layers
+    let layers: Vec<_> = fork.ancestors().collect();
+
+    // THEN the local entries come first, then the shared ones
+    assert_eq!(layers.len(), 2);
+    assert_eq!(layers[0].kind(), LayerKind::Local);
+    assert_eq!(layers[0].get::<u32>(), Some(&2));
+    assert_eq!(layers[1].kind(), LayerKind::Shared);
+    assert_eq!(layers[1].get::<u32>(), Some(&1));
+    assert_eq!(
+        layers[1].entry_keys(),
+        vec![("&str", Some("name")), ("u32", None)]
+    );
+    assert_eq!(fork.ancestors().resolve_named::<&str>("name").unwrap().0, 1);
+}
+
+#[test]
+fn test_ancestors_removed() {
+    // GIVEN a fork that removed a shared entry
+    let mut ctx = ctxbuilder::ctx().with(1u32);
+    let mut fork = ctx.fork();
+    fork.retain(|_, _| false);
+
+    // WHEN resolving the removed entry
+    let resolved = fork.ancestors().resolve::<u32>();
+
+    // THEN it is not resolved from the shared layer
+    assert!(resolved.is_none());
+    assert!(fork.ancestors().next().unwrap().is_empty());
+    assert!(ctx.ancestors().resolve::<u32>().is_some());
+}
+
+#[test]
+fn test_ancestors_sub() {
+    // GIVEN a sub-context of a forked context
+    let mut ctx = ctxbuilder::ctx().with(1u32);
+    ctx.fork();
+    ctx.insert(true);
+    let mut sub = ctx.sub();
+    sub.insert_named("name", "bob");
+
+    // WHEN iterating over its layers
+    let kinds: Vec<_> = sub.ancestors().map(|layer| layer.kind()).collect();
+
+    // THEN the layers of the parent follow the sub-context
+    assert_eq!(
+        kinds,
+        vec![LayerKind::Local, LayerKind::Parent, LayerKind::Shared]
+    );
+    assert_eq!(sub.ancestors().resolve::<bool>().unwrap().0, 1);
+    assert_eq!(sub.ancestors().resolve::<u32>().unwrap().0, 2);
+}
+
+#[test]
+fn test_ancestors_layered() {
+    // GIVEN a layered context
+    let base = MainContext::new().with(1u32).with(1u8);
+    let team = MainContext::new().with(2u32);
+    let mut ctx = LayeredContext::new(&base).with_layer(&team);
+    ctx.insert(3u8);
+
+    // WHEN resolving entries
+    let u8_layer = ctx.ancestors().resolve::<u8>().unwrap();
+    let u32_layer = ctx.ancestors().resolve::<u32>().unwrap();
+
+    // THEN the last layer added is checked first, after the local entries
+    assert_eq!(ctx.ancestors().len(), 3);
+    assert_eq!((u8_layer.0, u8_layer.1.kind()), (0, LayerKind::Local));
+    assert_eq!((u32_layer.0, u32_layer.1.kind()), (1, LayerKind::Parent));
+    assert_eq!(u32_layer.1.get::<u32>(), Some(&2));
+}
+
+#[test]
+fn test_ancestors_frozen() {
+    // GIVEN a frozen context
+    let frozen = ctxbuilder::ctx().with(1u32).freeze();
+
+    // WHEN iterating over its layers
+    let layers: Vec<_> = frozen.ancestors().collect();
+
+    // THEN its entries are all shared
+    assert_eq!(layers.len(), 1);
+    assert_eq!(layers[0].kind(), LayerKind::Shared);
+    assert!(layers[0].contains::<u32>());
+}
@@ -0,0 +1,19 @@
+#![cfg(feature = "arbitrary")]
+
+use arbitrary::Unstructured;
+use ctxbuilder::{Context, MainContext};
+
+#[test]
+fn test_from_unstructured() {
+    // GIVEN two contexts created from the same bytes
+    let data = [1, 2, 3, 4, 5, 6, 7, 8];
+    let mut a = MainContext::from_unstructured(&mut Unstructured::new(&data)).unwrap();
+    let mut b = MainContext::from_unstructured(&mut Unstructured::new(&data)).unwrap();
+
+    // WHEN generating values
+    let a: u64 = a.pick_in_range(0..1_000_000);
+    let b: u64 = b.pick_in_range(0..1_000_000);
+
+    // THEN they are the same
+    assert_eq!(a, b);
+}
@@ -0,0 +1,58 @@
+use ctxbuilder::{assertions::*, Context};
+
+#[test]
+fn test_assertions() {
+    // GIVEN a context with entries
+    let ctx = ctxbuilder::ctx().with(42u32).with_named("owner", "alice");
+
+    // WHEN asserting on existing entries
+    // THEN the assertions pass
+    assert_contains::<u32>(&ctx);
+    assert_contains_named::<&str>(&ctx, "owner");
+    assert_value_eq(&ctx, &42u32);
+    assert_entry_eq(&ctx, "owner", &"alice");
+}
+
+#[test]
+#[should_panic(expected = "missing context entry `owner` of type `u32`
+  entries of type `u32`: (unnamed), `admin`
+  entries named `owner`: `&str`")]
+fn test_assert_contains_named_missing() {
+    // GIVEN a context without the entry
+    let ctx = ctxbuilder::ctx()
+        .with(42u32)
+        .with_named("admin", 1u32)
+        .with_named("owner", "alice");
+
+    // WHEN asserting on the missing entry
+    // THEN it panics with nearby entries
+    assert_contains_named::<u32>(&ctx, "owner");
+}
+
+#[test]
+#[should_panic(expected = "context entry `owner` of type `&str` differs
+  expected: \"bob\"
+     found: \"alice\"")]
+fn test_assert_entry_eq_differs() {
+    // GIVEN a context with an entry
+    let ctx = ctxbuilder::ctx().with_named("owner", "alice");
+
+    // WHEN asserting on a different value
+    // THEN it panics with both values
+    assert_entry_eq(&ctx, "owner", &"bob");
+}
+
+#[test]
+#[should_panic(expected = "missing context entry of type `u64`")]
+fn test_assert_contains_sub() {
+    // GIVEN a sub-context
+    let ctx = ctxbuilder::ctx().with(42u32);
+    let sub = ctx.sub().with_named("owner", "alice");
+
+    // WHEN asserting on entries
+    assert_contains::<u32>(&sub);
+    assert_contains_named::<&str>(&sub, "owner");
+
+    // THEN missing entries panic
+    assert_contains::<u64>(&sub);
+}
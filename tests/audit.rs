@@ -0,0 +1,66 @@
+use std::time::SystemTime;
+
+use ctxbuilder::{Clock, Context, ContextWrite, FrozenInstant, MainContext};
+
+#[test]
+fn test_audit_system_time() {
+    // GIVEN an audited context without a clock
+    let mut ctx = MainContext::seeded(1).audit_determinism();
+
+    // WHEN building time-based values
+    ctx.build::<SystemTime>();
+    ctx.build::<SystemTime>();
+    ctx.build::<FrozenInstant>();
+
+    // THEN the system sources are reported once each
+    assert_eq!(
+        ctx.nondeterministic_sources(),
+        [
+            "`SystemTime::now`, without a `Clock` in the context",
+            "`Instant::now`, for the first `FrozenInstant`",
+        ]
+    );
+}
+
+#[test]
+fn test_audit_deterministic() {
+    // GIVEN an audited context with a seed and a clock
+    let mut ctx = MainContext::seeded(1).audit_determinism();
+    ctx.insert(Clock::from_unix_millis(1_700_000_000_000));
+
+    // WHEN building values in a sub-context
+    let mut sub = ctx.sub();
+    sub.build::<SystemTime>();
+    sub.build::<uuid::Uuid>();
+    drop(sub);
+
+    // THEN nothing is reported
+    assert!(ctx.nondeterministic_sources().is_empty());
+}
+
+#[test]
+fn test_audit_random_seed() {
+    // GIVEN an audited context with a random seed
+    let ctx = MainContext::new().audit_determinism();
+
+    // WHEN reseeding it
+    let before = ctx.nondeterministic_sources();
+    ctxbuilder::ContextRead::rng(&ctx).reseed(3);
+
+    // THEN the random seed is only reported before
+    assert_eq!(before.len(), 1);
+    assert!(before[0].starts_with("random seed"));
+    assert!(ctx.nondeterministic_sources().is_empty());
+}
+
+#[test]
+fn test_audit_disabled() {
+    // GIVEN a context that is not audited
+    let mut ctx = MainContext::new();
+
+    // WHEN building values from the system time
+    ctx.build::<SystemTime>();
+
+    // THEN nothing is tracked
+    assert!(ctx.nondeterministic_sources().is_empty());
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "aws_lambda_events")]
+
+use aws_lambda_events::{apigw::ApiGatewayProxyRequest, dynamodb, sqs::SqsEvent};
+use ctxbuilder::{
+    aws_lambda_events::{AccountId, QueueName},
+    Context,
+};
+
+#[test]
+fn test_shared_account() {
+    // GIVEN a context with an account ID and queue name
+    let mut ctx = ctxbuilder::ctx()
+        .with(AccountId("111122223333".to_string()))
+        .with(QueueName("orders".to_string()));
+
+    // WHEN building events from multiple sources
+    let req: ApiGatewayProxyRequest = ctx.build();
+    let sqs: SqsEvent = ctx.build();
+    let ddb: dynamodb::Event = ctx.build();
+
+    // THEN they share the same account
+    assert_eq!(
+        req.request_context.account_id.as_deref(),
+        Some("111122223333")
+    );
+    assert_eq!(
+        sqs.records[0].event_source_arn.as_deref(),
+        Some("arn:aws:sqs:us-east-1:111122223333:orders")
+    );
+    assert!(ddb.records[0]
+        .event_source_arn
+        .as_deref()
+        .unwrap()
+        .starts_with("arn:aws:dynamodb:us-east-1:111122223333:table/table/"));
+}
+
+#[test]
+fn test_shared_request_id() {
+    // GIVEN a single context
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN building two API Gateway requests
+    let first: ApiGatewayProxyRequest = ctx.build();
+    let second: ApiGatewayProxyRequest = ctx.build();
+
+    // THEN they share the same request ID
+    assert!(first.request_context.request_id.is_some());
+    assert_eq!(
+        first.request_context.request_id,
+        second.request_context.request_id
+    );
+}
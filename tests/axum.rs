@@ -0,0 +1,65 @@
+#![cfg(feature = "axum")]
+
+use axum::{
+    body::{to_bytes, Bytes},
+    extract::State,
+    http::{Method, StatusCode, Uri},
+    routing::post,
+    Router,
+};
+use ctxbuilder::{Builder, Context};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct AppState {
+    name: String,
+}
+
+impl Builder for AppState {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            name: ctx
+                .entry_named("app")
+                .or_insert_with(|| "app".to_string())
+                .clone(),
+        }
+    }
+}
+
+#[test]
+fn test_request_with_state() {
+    // GIVEN a context with request parts
+    let mut ctx = ctxbuilder::ctx()
+        .with(Method::POST)
+        .with(Uri::from_static("/pets"))
+        .with_named("app", "pets".to_string());
+
+    // WHEN building a request and state pair
+    let (req, state): (_, State<AppState>) = ctxbuilder::axum::request_with_state(&mut ctx);
+
+    // THEN they use the values from the context
+    assert_eq!(req.method(), Method::POST);
+    assert_eq!(req.uri(), "/pets");
+    assert_eq!(state.0.name, "pets");
+
+    // THEN the state is shared with later builds
+    let State(other): State<AppState> = ctx.build();
+    assert_eq!(other, state.0);
+}
+
+#[tokio::test]
+async fn test_call_router() {
+    // GIVEN a router and a context describing the request
+    let router = Router::new().route("/echo", post(|body: Bytes| async move { body }));
+    let mut ctx = ctxbuilder::ctx()
+        .with(Method::POST)
+        .with(Uri::from_static("/echo"))
+        .with(Bytes::from_static(b"hello"));
+
+    // WHEN calling the router
+    let res = ctxbuilder::axum::call(&mut ctx, router).await.unwrap();
+
+    // THEN the request reaches the handler with the body from the context
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(body, b"hello".as_slice());
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "postcard")]
+
+use ctxbuilder::{BinaryError, ContextRead, ContextWrite, MainContext};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Row {
+    id: u64,
+    label: String,
+}
+
+fn rows(count: u64) -> Vec<Row> {
+    (0..count)
+        .map(|id| Row {
+            id,
+            label: format!("row-{id}"),
+        })
+        .collect()
+}
+
+#[test]
+fn test_binary_round_trip() {
+    // GIVEN a context with registered and unregistered entries
+    let mut ctx = MainContext::new();
+    ctx.register_binary::<Vec<Row>>();
+    ctx.register_binary::<u32>();
+    ctx.insert_named("rows", rows(1_000));
+    ctx.insert(7u32);
+    ctx.insert(true);
+
+    // WHEN loading its binary snapshot in another context
+    let bytes = ctx.to_binary().unwrap();
+    let mut loaded = MainContext::new();
+    loaded.register_binary::<Vec<Row>>();
+    loaded.register_binary::<u32>();
+    let count = loaded.load_binary(&bytes).unwrap();
+
+    // THEN only the registered entries are loaded
+    assert_eq!(count, 2);
+    assert_eq!(loaded.get_named::<Vec<Row>>("rows"), Some(&rows(1_000)));
+    assert_eq!(loaded.get::<u32>(), Some(&7));
+    assert!(loaded.get::<bool>().is_none());
+}
+
+#[test]
+fn test_binary_unknown_type() {
+    // GIVEN a binary snapshot
+    let mut ctx = MainContext::new();
+    ctx.register_binary::<u32>();
+    ctx.insert(7u32);
+    let bytes = ctx.to_binary().unwrap();
+
+    // WHEN loading it in a context without the type registered
+    let err = MainContext::new().load_binary(&bytes).unwrap_err();
+
+    // THEN it reports the unknown type
+    assert!(matches!(&err, BinaryError::UnknownType(type_name) if type_name == "u32"));
+    assert_eq!(err.to_string(), "no binary encoding registered for `u32`");
+}
+
+#[test]
+fn test_binary_invalid() {
+    // GIVEN snapshots with another version and truncated data
+    let mut ctx = MainContext::new();
+    ctx.register_binary::<Vec<Row>>();
+    ctx.insert(rows(10));
+    let bytes = ctx.to_binary().unwrap();
+    let mut newer = bytes.clone();
+    newer[0] = 2;
+
+    // WHEN loading them
+    let mut loaded = MainContext::new();
+    loaded.register_binary::<Vec<Row>>();
+    let newer = loaded.load_binary(&newer).unwrap_err();
+    let truncated = loaded.load_binary(&bytes[..bytes.len() / 2]).unwrap_err();
+
+    // THEN they fail without inserting anything
+    assert!(matches!(newer, BinaryError::UnsupportedVersion(2)));
+    assert!(matches!(truncated, BinaryError::Encoding(_)));
+    assert!(loaded.get::<Vec<Row>>().is_none());
+}
@@ -0,0 +1,163 @@
+use std::sync::Arc;
+
+use ctxbuilder::{Builder, Context, MainContext};
+
+trait Repository: Send + Sync {
+    fn kind(&self) -> &'static str;
+    fn prefix(&self) -> &str;
+}
+
+struct InMemoryRepo {
+    prefix: String,
+}
+
+impl Repository for InMemoryRepo {
+    fn kind(&self) -> &'static str {
+        "in-memory"
+    }
+
+    fn prefix(&self) -> &str {
+        &self.prefix
+    }
+}
+
+impl Builder for InMemoryRepo {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            prefix: ctx.get::<String>().cloned().unwrap_or_default(),
+        }
+    }
+}
+
+struct FailingRepo;
+
+impl Repository for FailingRepo {
+    fn kind(&self) -> &'static str {
+        "failing"
+    }
+
+    fn prefix(&self) -> &str {
+        ""
+    }
+}
+
+impl Builder for FailingRepo {
+    fn build<C: Context>(_ctx: &mut C) -> Self {
+        Self
+    }
+}
+
+struct Service {
+    repo: Arc<dyn Repository>,
+    id: u64,
+}
+
+impl Builder for Service {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            repo: ctx.build_dyn_arc(),
+            id: ctx.next_seq(),
+        }
+    }
+}
+
+#[test]
+fn test_build_dyn() {
+    // GIVEN a context with a bound implementation
+    let mut ctx = ctxbuilder::ctx().with("users/".to_string());
+    ctx.bind::<dyn Repository, InMemoryRepo>(|val| val);
+
+    // WHEN building the trait object
+    let repo = ctx.build_dyn::<dyn Repository>();
+
+    // THEN the implementation is built with the context
+    assert_eq!(repo.kind(), "in-memory");
+    assert_eq!(repo.prefix(), "users/");
+}
+
+#[test]
+fn test_build_dyn_swap() {
+    // GIVEN a builder that uses a trait object
+    let mut ctx = ctxbuilder::ctx();
+    ctx.bind::<dyn Repository, InMemoryRepo>(|val| val);
+
+    // WHEN a sub-context binds another implementation
+    let mut sub = ctx.sub();
+    sub.bind::<dyn Repository, FailingRepo>(|val| val);
+    let service = sub.build::<Service>();
+
+    // THEN the builder uses the new implementation, without changing the parent
+    assert_eq!(service.repo.kind(), "failing");
+    assert_eq!(ctx.build::<Service>().repo.kind(), "in-memory");
+}
+
+#[test]
+fn test_build_dyn_shares_entries() {
+    // GIVEN a context with a bound implementation that inserts values
+    struct Counted;
+    impl Repository for Counted {
+        fn kind(&self) -> &'static str {
+            "counted"
+        }
+
+        fn prefix(&self) -> &str {
+            ""
+        }
+    }
+    impl Builder for Counted {
+        fn build<C: Context>(ctx: &mut C) -> Self {
+            ctx.next_seq_named("repos");
+            Self
+        }
+    }
+    let mut ctx = MainContext::new();
+    ctx.bind::<dyn Repository, Counted>(|val| val);
+
+    // WHEN building the trait object several times
+    ctx.build_dyn::<dyn Repository>();
+    ctx.build_dyn::<dyn Repository>();
+
+    // THEN the values inserted by the implementation are stored in the context
+    assert_eq!(ctx.next_seq_named("repos"), 3);
+}
+
+#[test]
+#[should_panic(expected = "no implementation bound for")]
+fn test_build_dyn_unbound() {
+    // GIVEN a context without any bound implementation
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN building the trait object
+    // THEN it panics
+    ctx.build_dyn::<dyn Repository>();
+}
+
+#[test]
+fn test_build_dyn_frozen() {
+    // GIVEN a frozen context with a bound implementation
+    let mut ctx = ctxbuilder::ctx().with("orders/".to_string());
+    ctx.bind::<dyn Repository, InMemoryRepo>(|val| val);
+    let mut frozen = ctx.freeze();
+
+    // WHEN building the trait object
+    let repo = frozen.build_dyn_arc::<dyn Repository>();
+
+    // THEN the implementation reads from the frozen context
+    assert_eq!(repo.prefix(), "orders/");
+}
+
+#[test]
+fn test_build_dyn_write_through() {
+    // GIVEN a write-through sub-context with a bound implementation that inserts values
+    let mut ctx = ctxbuilder::ctx();
+    ctx.bind::<dyn Repository, InMemoryRepo>(|val| val);
+    let mut sub = ctx.sub_mut();
+    sub.set_write_through(true);
+
+    // WHEN building a service in the sub-context
+    sub.build::<Service>();
+    drop(sub);
+
+    // THEN the sequence is stored in the parent
+    assert_eq!(ctx.build::<Service>().id, 2);
+}
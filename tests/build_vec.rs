@@ -0,0 +1,117 @@
+use ctxbuilder::{Builder, Context, MainContext};
+
+#[derive(Debug, Clone, PartialEq)]
+struct Row {
+    tenant: u64,
+    value: u64,
+}
+
+impl Builder for Row {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        let tenant = *ctx.entry_named("tenant").or_insert_in_range(0..u64::MAX);
+        Self {
+            tenant,
+            value: ctx.rng().next_u64(),
+        }
+    }
+}
+
+#[test]
+fn test_build_vec() {
+    // GIVEN a context
+    let mut ctx = MainContext::seeded(1);
+
+    // WHEN building multiple rows
+    let rows: Vec<Row> = ctx.build_vec(10);
+
+    // THEN they share the same tenant
+    assert_eq!(rows.len(), 10);
+    assert!(rows.iter().all(|row| row.tenant == rows[0].tenant));
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_build_par_vec() {
+    // GIVEN two contexts with the same seed
+    let mut a = MainContext::seeded(2);
+    let mut b = MainContext::seeded(2);
+
+    // WHEN building rows in parallel
+    let a: Vec<Row> = a.build_par_vec(1_000);
+    let b: Vec<Row> = b.build_par_vec(1_000);
+
+    // THEN they share the same tenant and are deterministic
+    assert_eq!(a.len(), 1_000);
+    assert!(a.iter().all(|row| row.tenant == a[0].tenant));
+    assert_eq!(a, b);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_build_par_vec_merge() {
+    use ctxbuilder::ContextRead;
+
+    // GIVEN a context
+    let mut ctx = MainContext::seeded(3);
+
+    // WHEN building values with shared entries in parallel
+    let _: Vec<Row> = ctx.build_par_vec(10);
+
+    // THEN the shared values are stored in the context
+    assert!(ctx.get_named::<u64>("tenant").is_some());
+}
+
+#[cfg(feature = "rayon")]
+#[derive(Debug)]
+struct Tagged;
+
+#[cfg(feature = "rayon")]
+impl Builder for Tagged {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        // Only the objects built after the first one create the `rest` entry
+        if ctx.contains_named::<u64>("first") {
+            ctx.entry_named("rest").or_insert(2u64);
+        } else {
+            ctx.insert_named("first", 1u64);
+        }
+        Self
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_build_par_vec_merge_removed() {
+    use ctxbuilder::{ContextRead, ContextWrite};
+
+    // GIVEN a fork, where an entry of the shared layer was removed
+    let mut base = MainContext::seeded(4);
+    base.insert_named("rest", 0u64);
+    let mut ctx = base.fork();
+    ctx.remove_prefix("rest");
+
+    // WHEN building values creating that entry in parallel
+    let _: Vec<Tagged> = ctx.build_par_vec(3);
+
+    // THEN the entry is inserted, as with sequential builds
+    assert_eq!(ctx.get_named::<u64>("rest"), Some(&2));
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_build_par_vec_merge_recorded() {
+    use ctxbuilder::Step;
+
+    // GIVEN a recording context
+    let mut ctx = MainContext::recording(5);
+    ctx.register_display::<u64>();
+
+    // WHEN building values creating an entry in parallel
+    let _: Vec<Tagged> = ctx.build_par_vec(3);
+
+    // THEN the merged entry is recorded, as with sequential builds
+    assert!(ctx.recipe().unwrap().steps().contains(&Step::Insert {
+        type_name: "u64".to_string(),
+        name: Some("rest".to_string()),
+        value: Some("2".to_string()),
+    }));
+}
@@ -0,0 +1,97 @@
+#![cfg(feature = "bytes")]
+
+use bytes::{Bytes, BytesMut};
+use ctxbuilder::{Context, MainContext, PayloadSpec};
+
+#[test]
+fn test_bytes_spec() {
+    // GIVEN a context with a payload spec
+    let mut ctx = ctxbuilder::ctx().with(PayloadSpec::sized(64));
+
+    // WHEN building payloads
+    let a = ctx.build::<Bytes>();
+    let b = ctx.build::<BytesMut>();
+
+    // THEN they have the size of the spec, and differ from each other
+    assert_eq!(a.len(), 64);
+    assert_eq!(b.len(), 64);
+    assert_ne!(a, b.freeze());
+}
+
+#[test]
+fn test_bytes_stored() {
+    // GIVEN a context with a payload and a spec
+    let mut ctx = ctxbuilder::ctx()
+        .with(PayloadSpec::sized(64))
+        .with(Bytes::from_static(b"hello"));
+
+    // WHEN building payloads
+    let body = ctx.build::<Bytes>();
+    let named = ctx.build_named::<Bytes, _>("chunk");
+
+    // THEN the stored payload is returned for its name only
+    assert_eq!(body, "hello");
+    assert_eq!(named.len(), 64);
+}
+
+#[test]
+fn test_bytes_default() {
+    // GIVEN a context without payload spec
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN building a payload
+    // THEN it is empty
+    assert!(ctx.build::<Bytes>().is_empty());
+    assert!(ctx.build_named::<BytesMut, _>("chunk").is_empty());
+}
+
+#[test]
+fn test_bytes_deterministic() {
+    // GIVEN two contexts with the same seed
+    let mut a = MainContext::seeded(7).with(PayloadSpec::between(10..100));
+    let mut b = MainContext::seeded(7).with(PayloadSpec::between(10..100));
+
+    // WHEN building payloads
+    let a: Vec<Bytes> = (0..3).map(|_| a.build()).collect();
+    let b: Vec<Bytes> = (0..3).map(|_| b.build()).collect();
+
+    // THEN they are the same
+    assert_eq!(a, b);
+    assert!(a.iter().all(|payload| (10..100).contains(&payload.len())));
+}
+
+#[test]
+fn test_bytes_named() {
+    // GIVEN a context with a global and a named payload spec
+    let mut ctx = ctxbuilder::ctx()
+        .with(PayloadSpec::sized(8))
+        .with_named("chunk", PayloadSpec::sized(1024).printable());
+
+    // WHEN building named payloads
+    let chunk = ctx.build_named::<BytesMut, _>("chunk");
+    let header = ctx.build_named::<Bytes, _>("header");
+
+    // THEN the named spec is used if there is one
+    assert_eq!(chunk.len(), 1024);
+    assert!(chunk.iter().all(u8::is_ascii_graphic));
+    assert_eq!(header.len(), 8);
+}
+
+#[test]
+fn test_bytes_empty() {
+    // GIVEN a context with an empty payload spec
+    let mut ctx = ctxbuilder::ctx().with(PayloadSpec::sized(0));
+
+    // WHEN building a payload
+    // THEN it is empty
+    assert!(ctx.build::<Bytes>().is_empty());
+}
+
+#[test]
+#[should_panic(expected = "payload length range is empty")]
+fn test_bytes_empty_range() {
+    // GIVEN an empty length range
+    // WHEN creating a payload spec
+    // THEN it panics
+    PayloadSpec::between(10..10);
+}
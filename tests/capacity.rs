@@ -0,0 +1,31 @@
+use ctxbuilder::{ContextWrite, MainContext};
+
+const NAMES: &[&str] = &["a", "b", "c", "d", "e", "f", "g", "h"];
+
+#[test]
+fn test_with_capacity() {
+    // GIVEN a context created with a capacity
+    let mut ctx = MainContext::with_capacity(NAMES.len());
+    let capacity = ctx.capacity();
+    assert!(capacity >= NAMES.len());
+
+    // WHEN inserting up to that many entries
+    for (i, name) in NAMES.iter().enumerate() {
+        ctx.insert_named(name, i);
+    }
+
+    // THEN the context doesn't reallocate
+    assert_eq!(ctx.capacity(), capacity);
+}
+
+#[test]
+fn test_reserve() {
+    // GIVEN an empty context
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN reserving space
+    ctx.reserve(50);
+
+    // THEN the capacity is increased
+    assert!(ctx.capacity() >= 50);
+}
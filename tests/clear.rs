@@ -0,0 +1,49 @@
+use std::{any::TypeId, rc::Rc};
+
+use ctxbuilder::{Context, ContextRead, LocalContext};
+
+#[test]
+fn test_clear() {
+    // GIVEN a forked context
+    let mut base = ctxbuilder::ctx().with(1u32).with_named("name", "alice");
+    let mut fork = base.fork().with(2u64);
+
+    // WHEN clearing the fork
+    fork.clear();
+
+    // THEN the fork is empty, but the base context is untouched
+    assert_eq!(fork.get::<u32>(), None);
+    assert_eq!(fork.get::<u64>(), None);
+    assert_eq!(base.get(), Some(&1u32));
+    assert_eq!(base.get_named("name"), Some(&"alice"));
+}
+
+#[test]
+fn test_retain() {
+    // GIVEN a forked context
+    let mut base = ctxbuilder::ctx().with(1u32).with_named("name", "alice");
+    let mut fork = base.fork().with(2u64).with_named("other", 3u64);
+
+    // WHEN only keeping unnamed entries
+    fork.retain(|_, name| name.is_none());
+
+    // THEN named entries are removed from the fork only
+    assert_eq!(fork.get(), Some(&1u32));
+    assert_eq!(fork.get(), Some(&2u64));
+    assert_eq!(fork.get_named::<&str>("name"), None);
+    assert_eq!(fork.get_named::<u64>("other"), None);
+    assert_eq!(base.get_named("name"), Some(&"alice"));
+}
+
+#[test]
+fn test_retain_local() {
+    // GIVEN a local context
+    let mut ctx = LocalContext::new().with(1u32).with_local(Rc::new(2u64));
+
+    // WHEN removing entries by type
+    ctx.retain(|type_id, _| type_id != TypeId::of::<Rc<u64>>());
+
+    // THEN only matching entries are removed
+    assert_eq!(ctx.get(), Some(&1u32));
+    assert_eq!(ctx.get_local::<Rc<u64>>(), None);
+}
@@ -0,0 +1,45 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use ctxbuilder::{Clock, Context, ContextRead, FrozenInstant};
+
+#[test]
+fn test_builder_system_time() {
+    // GIVEN a context with a clock
+    let mut ctx = ctxbuilder::ctx().with(Clock::from_unix_millis(1_700_000_000_000));
+
+    // WHEN building system times while advancing the clock
+    let before = ctx.build::<SystemTime>();
+    ctx.get::<Clock>().unwrap().advance(Duration::from_secs(60));
+    let after = ctx.build::<SystemTime>();
+
+    // THEN they follow the clock
+    assert_eq!(
+        before,
+        UNIX_EPOCH + Duration::from_millis(1_700_000_000_000)
+    );
+    assert_eq!(
+        after.duration_since(before).unwrap(),
+        Duration::from_secs(60)
+    );
+}
+
+#[test]
+fn test_builder_frozen_instant() {
+    // GIVEN a context with a clock
+    let mut ctx = ctxbuilder::ctx().with(Clock::from_unix_millis(0));
+
+    // WHEN building instants while advancing the clock
+    let first = ctx.build::<FrozenInstant>();
+    let same = ctx.build::<FrozenInstant>();
+    ctx.get::<Clock>()
+        .unwrap()
+        .advance(Duration::from_millis(250));
+    let later = ctx.build::<FrozenInstant>();
+
+    // THEN they only move with the clock
+    assert_eq!(first, same);
+    assert_eq!(
+        later.instant().duration_since(first.instant()),
+        Duration::from_millis(250)
+    );
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "csv")]
+
+use ctxbuilder::{Builder, Context, MainContext};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct Customer {
+    id: u64,
+    name: String,
+    vip: bool,
+    zip: Option<u32>,
+}
+
+impl Builder for Customer {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        let id = ctx.next_seq();
+        Self {
+            id,
+            name: format!("Customer, {id}"),
+            vip: id.is_multiple_of(2),
+            zip: (id == 1).then_some(75001),
+        }
+    }
+}
+
+#[test]
+fn test_csv_to_string() {
+    // GIVEN built objects
+    let mut ctx = MainContext::new();
+    let customers = ctx.build_vec::<Customer>(2);
+
+    // WHEN exporting them to CSV
+    let csv = ctxbuilder::csv::to_string(&customers).unwrap();
+
+    // THEN the rows follow a header, with fields quoted as needed
+    assert_eq!(
+        csv,
+        "id,name,vip,zip\n\
+         1,\"Customer, 1\",false,75001\n\
+         2,\"Customer, 2\",true,\n"
+    );
+}
+
+#[test]
+fn test_csv_to_writer_empty() {
+    // GIVEN no objects
+    let customers: Vec<Customer> = Vec::new();
+
+    // WHEN exporting them to a writer
+    let mut out = Vec::new();
+    ctxbuilder::csv::to_writer(&customers, &mut out).unwrap();
+
+    // THEN nothing is written
+    assert!(out.is_empty());
+}
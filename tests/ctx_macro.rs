@@ -0,0 +1,84 @@
+use ctxbuilder::{Builder, Context, ContextRead, MainContext};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PetType {
+    Cat,
+    Dog,
+}
+
+#[derive(Debug, PartialEq)]
+struct Pet {
+    pet_type: PetType,
+    owner: &'static str,
+}
+
+impl Builder for Pet {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            pet_type: *ctx.entry().or_insert(PetType::Dog),
+            owner: ctx.entry_named("owner").or_insert("nobody"),
+        }
+    }
+}
+
+#[test]
+fn test_ctx_macro() {
+    // GIVEN a context created with the macro
+    let owner = "Alice";
+    let mut ctx = ctxbuilder::ctx! {
+        PetType::Cat,
+        "owner" => owner,
+        "retries" => 3u32,
+    };
+
+    // WHEN building an object
+    let pet = ctx.build::<Pet>();
+
+    // THEN it uses the entries from the macro
+    assert_eq!(
+        pet,
+        Pet {
+            pet_type: PetType::Cat,
+            owner: "Alice"
+        }
+    );
+    assert_eq!(ctx.get_named("retries"), Some(&3u32));
+}
+
+#[test]
+fn test_ctx_macro_seed() {
+    // GIVEN contexts created with the macro and a seed
+    let ctx = ctxbuilder::ctx! { seed: 42, "owner" => "Alice" };
+    let other = ctxbuilder::ctx! { "owner" => "Bob", seed: 42 };
+
+    // WHEN generating random numbers
+    // THEN they match a seeded context
+    let expected = MainContext::seeded(42).rng().next_u64();
+    assert_eq!(ctx.rng().next_u64(), expected);
+    assert_eq!(other.rng().next_u64(), expected);
+}
+
+#[test]
+fn test_ctx_macro_empty() {
+    // GIVEN an empty context created with the macro
+    let ctx = ctxbuilder::ctx! {};
+
+    // THEN it has no entries
+    assert!(ctx.entry_keys().is_empty());
+}
+
+#[test]
+fn test_ctx_macro_matches_chain() {
+    // GIVEN the same entries with the macro and with a chain of calls
+    let ctx = ctxbuilder::ctx! { PetType::Dog, "owner" => "Alice" };
+    let chain = ctxbuilder::ctx()
+        .with(PetType::Dog)
+        .with_named("owner", "Alice");
+
+    // THEN they have the same entries
+    let mut keys = ctx.entry_keys();
+    let mut chain_keys = chain.entry_keys();
+    keys.sort_unstable();
+    chain_keys.sort_unstable();
+    assert_eq!(keys, chain_keys);
+}
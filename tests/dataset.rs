@@ -0,0 +1,91 @@
+use ctxbuilder::{Builder, Context, MainContext, ParamBuilder};
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+struct Person {
+    id: u64,
+}
+
+impl Builder for Person {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            id: ctx.next_seq_named("person"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+struct Pet {
+    id: u64,
+    owner_id: u64,
+}
+
+impl ParamBuilder<&Person> for Pet {
+    fn build_with<C: Context>(ctx: &mut C, owner: &Person) -> Self {
+        Self {
+            id: ctx.next_seq_named("pet"),
+            owner_id: owner.id,
+        }
+    }
+}
+
+#[test]
+fn test_build_dataset() {
+    // GIVEN a seeded context
+    let mut ctx = MainContext::seeded(42);
+
+    // WHEN building a dataset
+    let dataset = ctx.build_dataset::<Person, Pet>(500, 0..4);
+
+    // THEN every child references its parent
+    assert_eq!(dataset.parents().len(), 500);
+    let mut count = 0;
+    for (i, person) in dataset.parents().iter().enumerate() {
+        let pets = dataset.children_of(i);
+        assert!(pets.len() < 4);
+        assert!(pets.iter().all(|pet| pet.owner_id == person.id));
+        count += pets.len();
+    }
+    assert_eq!(count, dataset.children().len());
+    assert!(count > 500);
+}
+
+#[test]
+fn test_build_dataset_seeded() {
+    // GIVEN two contexts with the same seed
+    let mut first = MainContext::seeded(7);
+    let mut second = MainContext::seeded(7);
+
+    // WHEN building datasets with both
+    let first = first.build_dataset::<Person, Pet>(50, 1..3);
+    let second = second.build_dataset::<Person, Pet>(50, 1..3);
+
+    // THEN they are the same
+    assert_eq!(first, second);
+    let (persons, pets) = first.into_parts();
+    assert_eq!(persons.len(), 50);
+    assert_eq!(pets.last().unwrap().owner_id, 50);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_dataset_json() {
+    // GIVEN a dataset with named tables
+    let mut ctx = MainContext::seeded(42);
+    let dataset = ctx
+        .build_dataset::<Person, Pet>(2, 1..2)
+        .with_names("persons", "pets");
+
+    // WHEN serializing it to JSON
+    let json = serde_json::to_value(&dataset).unwrap();
+
+    // THEN each table is under its name
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "persons": [{"id": 1}, {"id": 2}],
+            "pets": [{"id": 1, "owner_id": 1}, {"id": 2, "owner_id": 2}],
+        })
+    );
+}
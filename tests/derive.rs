@@ -0,0 +1,406 @@
+#![cfg(feature = "derive")]
+
+use ctxbuilder::{
+    Builder, Context, ContextRead, ContextWrite, DefaultBuilder, FromContext, IntoContext, Variant,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Region {
+    Eu,
+}
+
+#[derive(IntoContext)]
+struct Config {
+    region: Region,
+    #[ctx(name = "admin")]
+    admin_email: &'static str,
+    #[ctx(named)]
+    max_pets: u32,
+    #[ctx(skip)]
+    #[allow(dead_code)]
+    comment: String,
+}
+
+#[derive(IntoContext)]
+#[ctx(named)]
+struct Names {
+    alice: u64,
+    bob: u64,
+}
+
+#[derive(IntoContext)]
+struct Seed(u64, #[ctx(name = "label")] &'static str);
+
+#[derive(IntoContext)]
+struct Labelled<C: Send + Sync + 'static> {
+    #[ctx(name = "label")]
+    label: C,
+}
+
+#[test]
+fn test_into_context() {
+    // GIVEN a configuration struct
+    let config = Config {
+        region: Region::Eu,
+        admin_email: "admin@example.com",
+        max_pets: 3,
+        comment: "ignored".to_string(),
+    };
+
+    // WHEN applying it onto a context
+    let mut ctx = ctxbuilder::ctx();
+    ctx.apply(config);
+
+    // THEN its fields are inserted
+    assert_eq!(ctx.get(), Some(&Region::Eu));
+    assert_eq!(ctx.get_named("admin"), Some(&"admin@example.com"));
+    assert_eq!(ctx.get_named("max_pets"), Some(&3u32));
+    assert_eq!(ctx.get::<String>(), None);
+}
+
+#[test]
+fn test_into_context_named() {
+    // GIVEN structs with named and unnamed fields
+    // WHEN applying them onto a context
+    let ctx = ctxbuilder::ctx()
+        .with_preset(Names { alice: 1, bob: 2 })
+        .with_preset(Seed(42, "seed"));
+
+    // THEN fields are inserted with the right names
+    assert_eq!(ctx.get_named("alice"), Some(&1u64));
+    assert_eq!(ctx.get_named("bob"), Some(&2u64));
+    assert_eq!(ctx.get(), Some(&42u64));
+    assert_eq!(ctx.get_named("label"), Some(&"seed"));
+}
+
+#[test]
+fn test_into_context_generic() {
+    // GIVEN a struct with a type parameter named like the context one
+    let labelled = Labelled { label: "fixture" };
+
+    // WHEN applying it to a context
+    let ctx = ctxbuilder::ctx().with_preset(labelled);
+
+    // THEN its field is inserted
+    assert_eq!(ctx.get_named::<&str>("label"), Some(&"fixture"));
+}
+
+#[derive(FromContext, Debug, PartialEq)]
+struct Snapshot {
+    region: Region,
+    #[ctx(name = "admin")]
+    admin_email: &'static str,
+    #[ctx(named)]
+    max_pets: u32,
+    #[ctx(skip)]
+    comment: String,
+}
+
+#[test]
+fn test_from_context() {
+    // GIVEN a context with entries
+    let ctx = ctxbuilder::ctx()
+        .with(Region::Eu)
+        .with_named("admin", "admin@example.com")
+        .with_named("max_pets", 3u32);
+
+    // WHEN extracting a snapshot
+    let snapshot: Snapshot = ctx.extract().unwrap();
+
+    // THEN the fields are taken from the context
+    assert_eq!(
+        snapshot,
+        Snapshot {
+            region: Region::Eu,
+            admin_email: "admin@example.com",
+            max_pets: 3,
+            comment: String::new(),
+        }
+    );
+}
+
+#[test]
+fn test_from_context_missing() {
+    // GIVEN a context without the named entry
+    let ctx = ctxbuilder::ctx()
+        .with(Region::Eu)
+        .with_named("admin", "admin@example.com");
+
+    // WHEN extracting a snapshot
+    let err = ctx.extract::<Snapshot>().unwrap_err();
+
+    // THEN the missing entry is reported
+    assert_eq!(err.name(), Some("max_pets"));
+    assert_eq!(err.type_name(), "u32");
+    assert_eq!(
+        err.to_string(),
+        "missing context entry `max_pets` of type `u32`"
+    );
+}
+
+#[derive(FromContext, Debug, PartialEq)]
+struct Extracted<C: Clone + Send + Sync + 'static> {
+    #[ctx(name = "label")]
+    label: C,
+}
+
+#[test]
+fn test_from_context_generic() {
+    // GIVEN a context with a named entry
+    let ctx = ctxbuilder::ctx().with_named("label", "fixture");
+
+    // WHEN extracting a type with a type parameter named like the context one
+    let extracted: Extracted<&str> = ctx.extract().unwrap();
+
+    // THEN its field is taken from the context
+    assert_eq!(extracted, Extracted { label: "fixture" });
+}
+
+fn next_pet_count<C: Context>(ctx: &mut C) -> u32 {
+    ctx.next_seq_named("pets") as u32 * 10
+}
+
+#[derive(Builder, Debug, PartialEq)]
+struct Person {
+    id: uuid::Uuid,
+    #[ctx(name = "manager")]
+    manager_id: uuid::Uuid,
+    #[ctx(default = "\"Alice\".to_string()")]
+    name: String,
+    #[ctx(with = "next_pet_count")]
+    pets: u32,
+}
+
+#[derive(Builder)]
+struct Pair(uuid::Uuid, #[ctx(default = "42")] u8);
+
+#[test]
+fn test_builder() {
+    // GIVEN a context with entries
+    let manager = uuid::Uuid::from_u128(1);
+    let mut ctx = ctxbuilder::ctx().with_named("manager", manager);
+
+    // WHEN building objects with the derived builder
+    let person = ctx.build::<Person>();
+    let other = ctx.build::<Person>();
+    let pair = ctx.build::<Pair>();
+
+    // THEN fields are built with the context, defaults and functions
+    assert_eq!(person.manager_id, manager);
+    assert_eq!(person.name, "Alice");
+    assert_eq!((person.pets, other.pets), (10, 20));
+    assert_eq!(person.id, other.id);
+    assert_eq!(pair.0, person.id);
+    assert_eq!(pair.1, 42);
+}
+
+#[derive(Builder, Debug)]
+#[ctx(skip)]
+struct Request {
+    #[ctx(builder)]
+    request_id: uuid::Uuid,
+    #[ctx(named)]
+    trace_id: uuid::Uuid,
+    retries: u32,
+    body: Vec<u8>,
+}
+
+#[derive(Builder, Debug)]
+struct Response {
+    request_id: uuid::Uuid,
+    #[ctx(skip)]
+    cache: Option<String>,
+}
+
+#[test]
+fn test_builder_skip() {
+    // GIVEN a context with a request ID
+    let request_id = uuid::Uuid::from_u128(7);
+    let mut ctx = ctxbuilder::ctx().with(request_id);
+
+    // WHEN building structs with skipped fields
+    let request = ctx.build::<Request>();
+    let response = ctx.build::<Response>();
+
+    // THEN skipped fields use their default value
+    assert_eq!(request.request_id, request_id);
+    assert_eq!(ctx.get_named("trace_id"), Some(&request.trace_id));
+    assert_eq!((request.retries, request.body.len()), (0, 0));
+    assert_eq!(response.request_id, request_id);
+    assert_eq!(response.cache, None);
+}
+
+#[derive(Builder, Debug, PartialEq)]
+enum Payment {
+    Card {
+        #[ctx(name = "card")]
+        token: uuid::Uuid,
+        #[ctx(default = "3")]
+        installments: u8,
+    },
+    #[ctx(default_variant)]
+    Cash,
+    Voucher(uuid::Uuid, #[ctx(skip)] Option<String>),
+}
+
+#[test]
+fn test_builder_enum() {
+    // GIVEN contexts selecting different variants
+    let card = uuid::Uuid::from_u128(3);
+    let mut default_ctx = ctxbuilder::ctx();
+    let mut card_ctx = ctxbuilder::ctx()
+        .with(Variant::<Payment>::new("Card"))
+        .with_named("card", card);
+    let mut voucher_ctx = ctxbuilder::ctx().with(Variant::<Payment>::new("Voucher"));
+
+    // WHEN building the enum
+    let default = default_ctx.build::<Payment>();
+    let card_payment = card_ctx.build::<Payment>();
+    let voucher = voucher_ctx.build::<Payment>();
+
+    // THEN the selected variant is built with its fields
+    assert_eq!(default, Payment::Cash);
+    assert_eq!(
+        card_payment,
+        Payment::Card {
+            token: card,
+            installments: 3
+        }
+    );
+    assert!(matches!(voucher, Payment::Voucher(_, None)));
+}
+
+#[test]
+#[should_panic(expected = "unknown variant `Cheque`")]
+fn test_builder_enum_unknown() {
+    // GIVEN a context selecting a variant that doesn't exist
+    let mut ctx = ctxbuilder::ctx().with(Variant::<Payment>::new("Cheque"));
+
+    // WHEN building the enum
+    // THEN it panics
+    ctx.build::<Payment>();
+}
+
+#[derive(Builder, Debug, PartialEq)]
+struct Page<T> {
+    item: T,
+    #[ctx(name = "next")]
+    next: T,
+    #[ctx(skip)]
+    items: Vec<T>,
+    #[ctx(default = "\"cursor\".to_string()")]
+    cursor: String,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+enum Lookup<T> {
+    Found(T),
+    #[ctx(default_variant)]
+    Missing,
+}
+
+#[test]
+fn test_builder_generic() {
+    // GIVEN a context with entries
+    let next = uuid::Uuid::from_u128(9);
+    let mut ctx = ctxbuilder::ctx()
+        .with_named("next", next)
+        .with(Variant::<Lookup<uuid::Uuid>>::new("Found"));
+
+    // WHEN building generic types
+    let page = ctx.build::<Page<uuid::Uuid>>();
+    let found = ctx.build::<Lookup<uuid::Uuid>>();
+    let missing = ctx.build::<Lookup<Page<uuid::Uuid>>>();
+
+    // THEN fields are built for the type arguments
+    assert_eq!(page.next, next);
+    assert_eq!(page.cursor, "cursor");
+    assert!(page.items.is_empty());
+    assert_eq!(found, Lookup::Found(page.item));
+    assert_eq!(missing, Lookup::Missing);
+}
+
+#[derive(Builder, Debug, PartialEq)]
+struct Wrapper<C> {
+    inner: C,
+}
+
+#[test]
+fn test_builder_generic_context_name() {
+    // GIVEN a context with an entry
+    let inner = uuid::Uuid::from_u128(3);
+    let mut ctx = ctxbuilder::ctx().with(inner);
+
+    // WHEN building a type with a type parameter named like the context one
+    let wrapper = ctx.build::<Wrapper<uuid::Uuid>>();
+
+    // THEN its field is built for the type argument
+    assert_eq!(wrapper, Wrapper { inner });
+}
+
+#[derive(DefaultBuilder, Debug, PartialEq)]
+struct OrderId(uuid::Uuid);
+
+#[derive(DefaultBuilder, Debug, PartialEq)]
+struct CustomerId {
+    id: uuid::Uuid,
+}
+
+#[derive(DefaultBuilder, Debug, PartialEq)]
+struct Id<C>(C);
+
+mod legacy {
+    #[derive(ctxbuilder::DefaultBuilder, Debug, PartialEq)]
+    pub struct OrderId(pub uuid::Uuid);
+}
+
+#[test]
+fn test_default_builder_namespaced() {
+    // GIVEN a context with an entry for one of the newtypes
+    let order_id = uuid::Uuid::from_u128(1);
+    let mut ctx = ctxbuilder::ctx().with_named("derive::OrderId", order_id);
+
+    // WHEN building newtypes wrapping the same type
+    let order = ctx.build::<OrderId>();
+    let legacy = ctx.build::<legacy::OrderId>();
+    let customer = ctx.build::<CustomerId>();
+
+    // THEN each newtype uses the entry named after its path
+    assert_eq!(order, OrderId(order_id));
+    assert_ne!(legacy.0, order_id);
+    assert_eq!(ctx.get_named("derive::legacy::OrderId"), Some(&legacy.0));
+    assert_ne!(customer.id, order_id);
+    assert_eq!(ctx.get_named("derive::CustomerId"), Some(&customer.id));
+    assert_eq!(ctx.build::<CustomerId>(), customer);
+}
+
+#[test]
+fn test_default_builder_generic() {
+    // GIVEN a context with an entry for a generic newtype
+    let id = uuid::Uuid::from_u128(4);
+    let mut ctx = ctxbuilder::ctx().with_named("derive::Id", id);
+
+    // WHEN building the newtype, with a type parameter named like the context one
+    let built = ctx.build::<Id<uuid::Uuid>>();
+
+    // THEN it uses the entry named after its path
+    assert_eq!(built, Id(id));
+}
+
+#[test]
+fn test_default_builder_named() {
+    // GIVEN a context with a named entry for one of the newtypes
+    let buyer = uuid::Uuid::from_u128(2);
+    let mut ctx = ctxbuilder::ctx();
+    ctx.insert_named("derive::CustomerId.buyer", buyer);
+
+    // WHEN building newtypes with the same name
+    let customer = ctx.build_named::<CustomerId, _>("buyer");
+    let order = ctx.build_named::<OrderId, _>("buyer");
+
+    // THEN each newtype uses the entry namespaced by its path
+    assert_eq!(customer, CustomerId { id: buyer });
+    assert_ne!(order.0, buyer);
+    assert_eq!(ctx.get_named("derive::OrderId.buyer"), Some(&order.0));
+    assert_eq!(ctx.build_named::<OrderId, _>("buyer"), order);
+}
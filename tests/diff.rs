@@ -0,0 +1,63 @@
+use std::any::TypeId;
+
+use ctxbuilder::{Builder, Context, ContextDiff, ContextWrite};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PetType {
+    Dog,
+    Cat,
+}
+
+struct Pet;
+
+impl Builder for Pet {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        ctx.entry_named("owner").or_insert("alice");
+        Pet
+    }
+}
+
+#[test]
+fn test_diff() {
+    // GIVEN a context and a snapshot of it
+    let mut ctx = ctxbuilder::ctx().with(PetType::Dog).with(1u32).with(2u64);
+    ctx.register_debug::<PetType>();
+    ctx.register_eq::<PetType>();
+    ctx.register_debug::<&str>();
+    let before = ctx.fork();
+
+    // WHEN modifying the context
+    ctx.build::<Pet>();
+    ctx.insert(PetType::Cat);
+    ctx.insert(1u32);
+    ctx.retain(|type_id, _| type_id != TypeId::of::<u64>());
+
+    // THEN the differences are reported
+    let diff = ContextDiff::between(&before, &ctx);
+    assert_eq!(diff.added().len(), 1);
+    assert_eq!(diff.added()[0].to_string(), "`owner`: &str (\"alice\")");
+    assert_eq!(diff.removed().len(), 1);
+    assert_eq!(diff.removed()[0].type_name(), "u64");
+
+    // THEN changes are reported with their values when registered
+    assert_eq!(diff.changed().len(), 2);
+    let pet_type = &diff.changed()[0];
+    assert!(pet_type.type_name().ends_with("PetType"));
+    assert_eq!(pet_type.before(), Some("Dog"));
+    assert_eq!(pet_type.after(), Some("Cat"));
+    assert_eq!(diff.changed()[1].to_string(), "u32");
+}
+
+#[test]
+fn test_diff_equal() {
+    // GIVEN a context and a snapshot of it
+    let mut ctx = ctxbuilder::ctx().with(PetType::Dog);
+    ctx.register_eq::<PetType>();
+    let before = ctx.fork();
+
+    // WHEN replacing a value with an equal one
+    ctx.insert(PetType::Dog);
+
+    // THEN there are no differences
+    assert!(ContextDiff::between(&before, &ctx).is_empty());
+}
@@ -0,0 +1,74 @@
+use core::any::TypeId;
+
+use ctxbuilder::{
+    BoxedValue, Builder, Context, ContextRead, ContextWrite, DynContext, DynRef, MainContext,
+};
+
+struct Plugin {
+    name: &'static str,
+}
+
+impl Builder for Plugin {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            name: ctx
+                .get_named::<&str>("plugin")
+                .copied()
+                .unwrap_or("default"),
+        }
+    }
+}
+
+/// Function that could live in another crate, behind a trait object
+fn run_plugin(ctx: &mut dyn DynContext) -> &'static str {
+    let mut ctx = DynRef::new(ctx);
+    ctx.build::<Plugin>().name
+}
+
+#[test]
+fn test_dyn_context_build() {
+    // GIVEN a context with an entry
+    let mut ctx = MainContext::new().with_named("plugin", "metrics");
+
+    // WHEN passing it as a trait object
+    let name = run_plugin(ctx.as_dyn().unwrap());
+
+    // THEN builders can read its entries
+    assert_eq!(name, "metrics");
+}
+
+#[test]
+fn test_dyn_context_erased() {
+    // GIVEN a type-erased context
+    let mut ctx = MainContext::new();
+    let dyn_ctx = ctx.as_dyn().unwrap();
+
+    // WHEN inserting and reading values by type ID
+    assert!(dyn_ctx
+        .insert_boxed(Some("port"), BoxedValue::new(8080u16))
+        .is_none());
+    let old = dyn_ctx.insert_boxed(Some("port"), BoxedValue::new(9090u16));
+
+    // THEN the values are stored in the context
+    assert_eq!(old.unwrap().type_name(), "u16");
+    let stored = dyn_ctx
+        .get_erased(TypeId::of::<u16>(), Some("port"))
+        .unwrap();
+    assert_eq!(stored.downcast_ref::<u16>(), Some(&9090));
+    assert!(dyn_ctx.get_erased(TypeId::of::<u16>(), None).is_none());
+    assert_eq!(ctx.get_named::<u16>("port"), Some(&9090));
+}
+
+#[test]
+fn test_dyn_context_sub_context() {
+    // GIVEN a sub-context of a context with an entry
+    let ctx = MainContext::new().with(3u32);
+    let mut sub = ctx.sub();
+
+    // WHEN reading the entry through its type-erased view
+    let dyn_ctx = sub.as_dyn().unwrap();
+
+    // THEN it finds the entry of the parent
+    let stored = dyn_ctx.get_erased(TypeId::of::<u32>(), None).unwrap();
+    assert_eq!(stored.downcast_ref::<u32>(), Some(&3));
+}
@@ -0,0 +1,48 @@
+use ctxbuilder::{Context, ContextRead, ContextWrite, EntryName, NamedBuilder};
+
+ctxbuilder::entry_name! {
+    Person = "person";
+    /// Organization in the fixtures
+    Org = "org";
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Account {
+    owner: &'static str,
+}
+
+impl NamedBuilder<Person> for Account {
+    fn build_with_name<C: Context>(_ctx: &mut C, _name: Person) -> Self {
+        Self {
+            owner: Person::NAME,
+        }
+    }
+}
+
+#[test]
+fn test_entry_const() {
+    // GIVEN a context with an entry inserted by a string name
+    let mut ctx = ctxbuilder::ctx().with_named("person", 7u32);
+
+    // WHEN reading and modifying it through entry names
+    let org = *ctx.entry_const::<Org, u32>().or_insert(3);
+    let old = ctx.insert_const::<Org, _>(5u32);
+
+    // THEN the entry names match the string names
+    assert_eq!((org, old), (3, Some(3)));
+    assert_eq!(ctx.get_const::<Person, u32>(), Some(&7));
+    assert_eq!(ctx.get_named::<u32>("org"), Some(&5));
+    assert_eq!(*ctx.entry_const::<Person, u32>().or_default(), 7);
+}
+
+#[test]
+fn test_entry_name_as_name() {
+    // GIVEN a context
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN building with an entry name
+    let account: Account = ctx.build_named(Person);
+
+    // THEN the builder receives the entry name
+    assert_eq!(account, Account { owner: "person" });
+}
@@ -0,0 +1,75 @@
+use ctxbuilder::{BoxedValue, Context, ContextRead, ContextWrite, MainContext};
+
+#[derive(Debug, Clone, PartialEq)]
+struct Currency(&'static str);
+
+/// Not registered as cloneable, so it can't be copied
+struct Connection;
+
+fn billing() -> MainContext {
+    let mut ctx = MainContext::new();
+    ctx.register_clone::<Currency>();
+    ctx.register_clone::<u32>();
+    ctx.insert(Currency("EUR"));
+    ctx.insert_named("net_days", 30u32);
+    ctx.insert(Connection);
+    ctx
+}
+
+#[test]
+fn test_extend_from() {
+    // GIVEN a fixture context and a test context with overlapping entries
+    let fixtures = billing();
+    let mut ctx = ctxbuilder::ctx()
+        .with_named("net_days", 10u32)
+        .with_named("retries", 3u32);
+
+    // WHEN copying the fixtures into the test context
+    let count = ctx.extend_from(&fixtures);
+
+    // THEN the cloneable entries are copied, replacing existing ones
+    assert_eq!(count, 2);
+    assert_eq!(ctx.get(), Some(&Currency("EUR")));
+    assert_eq!(ctx.get_named::<u32>("net_days"), Some(&30));
+    assert_eq!(ctx.get_named::<u32>("retries"), Some(&3));
+    assert!(ctx.get::<Connection>().is_none());
+    assert!(fixtures.get::<Connection>().is_some());
+}
+
+#[test]
+fn test_extend_from_chained() {
+    // GIVEN a context that copied entries from fixtures
+    let mut ctx = MainContext::new();
+    ctx.extend_from(&billing());
+
+    // WHEN copying it again into another context
+    let mut other = MainContext::new();
+    let count = other.extend_from(&ctx);
+
+    // THEN the copied types can be copied further
+    assert_eq!(count, 2);
+    assert_eq!(other.get(), Some(&Currency("EUR")));
+}
+
+#[test]
+fn test_extend_boxed_values() {
+    // GIVEN a bundle of boxed values of different types
+    let bundle = vec![
+        (None, BoxedValue::new(Currency("USD"))),
+        (Some("net_days"), BoxedValue::new(45u32)),
+        (Some("owner"), BoxedValue::new("alice".to_string())),
+    ];
+    assert_eq!(bundle[0].1.type_name(), "extend::Currency");
+
+    // WHEN pouring it into a context
+    let mut ctx = ctxbuilder::ctx().with_named("net_days", 10u32);
+    ctx.extend(bundle);
+
+    // THEN the values are inserted with their types and names
+    assert_eq!(ctx.get(), Some(&Currency("USD")));
+    assert_eq!(ctx.get_named::<u32>("net_days"), Some(&45));
+    assert_eq!(
+        ctx.get_named::<String>("owner").map(String::as_str),
+        Some("alice")
+    );
+}
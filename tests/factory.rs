@@ -0,0 +1,114 @@
+use ctxbuilder::{factory::FnBuilder, prelude::*, Builder, Context, ContextRead, NamedBuilder};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct PersonId(u64);
+
+impl Builder for PersonId {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        *ctx.entry().or_insert_with(|| PersonId(1))
+    }
+}
+
+impl NamedBuilder for PersonId {
+    fn build_with_name<C: Context>(ctx: &mut C, name: &'static str) -> Self {
+        let id = ctx.next_seq() + 100;
+        *ctx.entry_named(name).or_insert(PersonId(id))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Pet {
+    owner: PersonId,
+}
+
+impl Builder for Pet {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self { owner: ctx.build() }
+    }
+}
+
+#[test]
+fn test_factory_map() {
+    // GIVEN a context
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN building with a mapped factory
+    let id = ctx.make(PersonId::builder().map(|PersonId(id)| id));
+
+    // THEN the function is applied
+    assert_eq!(id, 1);
+}
+
+#[test]
+fn test_factory_zip() {
+    // GIVEN a context
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN building a pair
+    let (person, pet) = ctx.make(PersonId::builder().zip(Pet::builder()));
+
+    // THEN they share the context
+    assert_eq!(pet.owner, person);
+}
+
+#[test]
+fn test_factory_and_then() {
+    // GIVEN a context
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN chaining factories
+    let factory = PersonId::named_builder("alice").and_then(|alice| {
+        assert_eq!(alice, PersonId(101));
+        PersonId::named_builder("bob")
+    });
+    let bob = ctx.make(&factory);
+
+    // THEN the second factory is built with the same context
+    assert_eq!(bob, PersonId(102));
+    assert_eq!(ctx.make(&factory), bob);
+}
+
+#[test]
+fn test_build_fn() {
+    // GIVEN a context
+    let mut ctx = ctxbuilder::ctx().with(PersonId(7));
+
+    // WHEN building with a closure
+    let pets = ctx.build_fn(|ctx| [ctx.build::<Pet>(), ctx.build::<Pet>()]);
+
+    // THEN the closure uses the context
+    assert_eq!(pets, [Pet { owner: PersonId(7) }; 2]);
+}
+
+#[test]
+fn test_fn_builder() {
+    // GIVEN a closure-backed factory
+    let factory = FnBuilder::new(|ctx| Pet {
+        owner: ctx.build_named("owner"),
+    });
+
+    // WHEN building with it in a sub-context and through another factory
+    let ctx = ctxbuilder::ctx();
+    let mut sub = ctx.sub();
+    let pet = sub.make(&factory);
+    let owner = sub.make(factory.map(|pet| pet.owner));
+
+    // THEN both use the same context
+    assert_eq!(pet.owner, PersonId(101));
+    assert_eq!(owner, PersonId(101));
+}
+
+#[test]
+fn test_fn_builder_stored() {
+    // GIVEN a closure-backed factory stored in a context
+    let mut ctx =
+        ctxbuilder::ctx().with_named("pet", FnBuilder::new(|ctx| Pet { owner: ctx.build() }));
+
+    // WHEN building with it from a fork
+    let mut fork = ctx.fork();
+    let factory = fork.get_named::<FnBuilder<Pet>>("pet").unwrap().clone();
+    let pet = fork.make(&factory);
+
+    // THEN it builds the object
+    assert_eq!(pet.owner, PersonId(1));
+}
@@ -0,0 +1,91 @@
+use ctxbuilder::{Builder, Context, ContextRead, Finalize, MainContext};
+
+#[derive(Debug, PartialEq)]
+struct Node {
+    id: u64,
+    next: Option<u64>,
+}
+
+impl Finalize for Node {
+    fn finalize<C: Context>(&mut self, ctx: &mut C) {
+        let next = if self.id == 1 { "second" } else { "first" };
+        self.next = ctx.get_named::<Node>(next).map(|node| node.id);
+    }
+}
+
+struct Cycle;
+
+impl Builder for Cycle {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        ctx.insert_finalized_named("first", Node { id: 1, next: None });
+        ctx.insert_finalized_named("second", Node { id: 2, next: None });
+        ctx.finalize_all();
+        Self
+    }
+}
+
+#[test]
+fn test_finalize_all() {
+    // GIVEN a builder that creates objects referencing each other
+    let mut ctx = MainContext::new();
+
+    // WHEN building it
+    ctx.build::<Cycle>();
+
+    // THEN the references are patched
+    assert_eq!(
+        ctx.get_named::<Node>("first"),
+        Some(&Node {
+            id: 1,
+            next: Some(2)
+        })
+    );
+    assert_eq!(
+        ctx.get_named::<Node>("second"),
+        Some(&Node {
+            id: 2,
+            next: Some(1)
+        })
+    );
+}
+
+#[derive(Debug, PartialEq)]
+struct Counter(u32);
+
+impl Finalize for Counter {
+    fn finalize<C: Context>(&mut self, ctx: &mut C) {
+        self.0 += 1;
+        if self.0 < 3 {
+            ctx.insert_finalized_named("again", Counter(self.0));
+        }
+    }
+}
+
+#[test]
+fn test_finalize_all_nested() {
+    // GIVEN an object that inserts more objects to finalize
+    let mut ctx = MainContext::new();
+    ctx.insert_finalized(Counter(0));
+
+    // WHEN finalizing the context twice
+    ctx.finalize_all();
+    ctx.finalize_all();
+
+    // THEN every object is finalized once
+    assert_eq!(ctx.get::<Counter>(), Some(&Counter(1)));
+    assert_eq!(ctx.get_named::<Counter>("again"), Some(&Counter(3)));
+}
+
+#[test]
+fn test_finalize_all_removed() {
+    // GIVEN an object to finalize that was removed
+    let mut ctx = MainContext::new();
+    ctx.insert_finalized(Counter(0));
+    ctx.clear();
+
+    // WHEN finalizing the context
+    ctx.finalize_all();
+
+    // THEN nothing is finalized
+    assert!(ctx.get::<Counter>().is_none());
+}
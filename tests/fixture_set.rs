@@ -0,0 +1,89 @@
+use ctxbuilder::{Builder, Context, FixtureSet, ParamBuilder};
+
+#[derive(Debug, PartialEq)]
+struct Person {
+    id: u64,
+}
+
+impl Builder for Person {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self { id: ctx.next_seq() }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Pet {
+    owner_id: u64,
+}
+
+impl ParamBuilder<&Person> for Pet {
+    fn build_with<C: Context>(_ctx: &mut C, owner: &Person) -> Self {
+        Self { owner_id: owner.id }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Account {
+    owner_id: u64,
+    balance: u32,
+}
+
+struct Household;
+
+impl FixtureSet for Household {
+    type Output = (Person, Vec<Pet>, Account);
+
+    fn build_set<C: Context>(ctx: &mut C) -> Self::Output {
+        let person = ctx.build::<Person>();
+        let pets = (0..2).map(|_| ctx.build_with(&person)).collect();
+        let account = Account {
+            owner_id: person.id,
+            balance: *ctx.entry_named("balance").or_insert(100),
+        };
+        (person, pets, account)
+    }
+}
+
+#[test]
+fn test_build_set() {
+    // GIVEN a context
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN building a fixture set
+    let (person, pets, account) = ctx.build_set::<Household>();
+
+    // THEN the objects are related to each other
+    assert_eq!(
+        pets,
+        [
+            Pet {
+                owner_id: person.id
+            },
+            Pet {
+                owner_id: person.id
+            }
+        ]
+    );
+    assert_eq!(
+        account,
+        Account {
+            owner_id: person.id,
+            balance: 100
+        }
+    );
+}
+
+#[test]
+fn test_build_set_twice() {
+    // GIVEN a context with a value used by the fixture set
+    let mut ctx = ctxbuilder::ctx().with_named("balance", 5u32);
+
+    // WHEN building two fixture sets
+    let (first, _, first_account) = ctx.build_set::<Household>();
+    let (second, second_pets, second_account) = ctx.build_set::<Household>();
+
+    // THEN each set is coherent on its own, and uses the shared context
+    assert_ne!(first.id, second.id);
+    assert!(second_pets.iter().all(|pet| pet.owner_id == second.id));
+    assert_eq!((first_account.balance, second_account.balance), (5, 5));
+}
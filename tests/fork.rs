@@ -0,0 +1,62 @@
+use std::sync::Mutex;
+
+use ctxbuilder::{Context, ContextRead, ContextWrite, MainContext};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum PetType {
+    Dog,
+    Cat,
+}
+
+/// Expensive value that can't be cloned
+struct Database(Mutex<Vec<&'static str>>);
+
+#[test]
+fn test_fork_shares_entries() {
+    // GIVEN a base context with an expensive value
+    let mut base = ctxbuilder::ctx()
+        .with(Database(Mutex::new(Vec::new())))
+        .with(PetType::Dog);
+
+    // WHEN forking the context
+    let fork = base.fork();
+
+    // THEN both contexts share the same value
+    fork.get::<Database>()
+        .unwrap()
+        .0
+        .lock()
+        .unwrap()
+        .push("fork");
+    assert_eq!(*base.get::<Database>().unwrap().0.lock().unwrap(), ["fork"]);
+    assert_eq!(fork.get(), Some(&PetType::Dog));
+}
+
+#[test]
+fn test_fork_copy_on_write() {
+    // GIVEN a base context and a fork
+    let mut base = ctxbuilder::ctx().with(PetType::Dog);
+    let mut fork = base.fork();
+
+    // WHEN inserting in each context
+    fork.insert(PetType::Cat);
+    base.insert_named("name", "base");
+
+    // THEN the contexts are independent
+    assert_eq!(base.get(), Some(&PetType::Dog));
+    assert_eq!(fork.get(), Some(&PetType::Cat));
+    assert_eq!(fork.get_named::<&str>("name"), None);
+    assert_eq!(*fork.entry().or_insert(PetType::Dog), PetType::Cat);
+}
+
+#[test]
+fn test_fork_rng() {
+    // GIVEN a seeded context
+    let mut base = MainContext::seeded(9);
+
+    // WHEN forking the context
+    let fork = base.fork();
+
+    // THEN the fork continues from the same state
+    assert_eq!(base.rng().next_u64(), fork.rng().next_u64());
+}
@@ -0,0 +1,97 @@
+use ctxbuilder::{Context, Generator, Sequence};
+
+#[test]
+fn test_format_seq() {
+    // GIVEN a context
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN formatting templates with the sequence
+    let first = ctx.format("user-{seq}");
+    let second = ctx.format("user-{seq}");
+
+    // THEN the sequence is incremented
+    assert_eq!(first, "user-1");
+    assert_eq!(second, "user-2");
+}
+
+#[test]
+fn test_format_entries() {
+    // GIVEN a context with named entries, sequences and generators
+    let mut ctx = ctxbuilder::ctx()
+        .with_named("name", "alice".to_string())
+        .with_named("order", Sequence::starting_at(100))
+        .with_named("bucket", Generator::new(|| "uploads".to_string()));
+
+    // WHEN formatting a template
+    let key = ctx.format("{bucket}/{name}/{order}.json");
+
+    // THEN the placeholders are expanded
+    assert_eq!(key, "uploads/alice/100.json");
+}
+
+#[test]
+fn test_format_entry_types() {
+    // GIVEN a context with named entries of common types
+    let mut ctx = ctxbuilder::ctx()
+        .with_named("count", 3usize)
+        .with_named("ratio", 0.5f64)
+        .with_named("enabled", true);
+
+    // WHEN formatting a template
+    let line = ctx.format("{count} at {ratio}: {enabled}");
+
+    // THEN the entries are formatted
+    assert_eq!(line, "3 at 0.5: true");
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_format_json_entry() {
+    // GIVEN a context with named JSON values
+    let mut ctx = ctxbuilder::ctx()
+        .with_named("name", serde_json::json!("alice"))
+        .with_named("tags", serde_json::json!(["admin"]));
+
+    // WHEN formatting a template
+    let line = ctx.format("{name}: {tags}");
+
+    // THEN strings are formatted without quotes, and other values as JSON
+    assert_eq!(line, r#"alice: ["admin"]"#);
+}
+
+#[test]
+fn test_format_escape() {
+    // GIVEN a context
+    let mut ctx = ctxbuilder::ctx().with_named("id", 7u32);
+
+    // WHEN formatting a template with escaped braces
+    let s = ctx.format("{{{id}}}");
+
+    // THEN the braces are kept
+    assert_eq!(s, "{7}");
+}
+
+#[test]
+fn test_format_subcontext_seq() {
+    // GIVEN a context with a sequence
+    let mut ctx = ctxbuilder::ctx();
+    assert_eq!(ctx.next_seq(), 1);
+
+    // WHEN using the sequence from a subcontext
+    let mut subctx = ctx.sub();
+
+    // THEN it continues the parent sequence
+    assert_eq!(subctx.next_seq(), 2);
+    assert_eq!(ctx.format("{seq}"), "3");
+}
+
+#[test]
+#[should_panic(expected = "missing context entry for placeholder `{missing}`")]
+fn test_format_missing() {
+    // GIVEN an empty context
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN formatting a template with an unknown placeholder
+    // THEN it panics
+    ctx.format("{missing}");
+}
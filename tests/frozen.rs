@@ -0,0 +1,100 @@
+use ctxbuilder::{Builder, Context, ContextRead, ContextWrite, DynRef, FrozenContext};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum PetType {
+    Dog,
+    Cat,
+}
+
+#[derive(Debug)]
+struct Pet {
+    pet_type: PetType,
+}
+
+impl Builder for Pet {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            pet_type: *ctx.entry().or_insert(PetType::Dog),
+        }
+    }
+}
+
+fn golden() -> FrozenContext {
+    ctxbuilder::ctx().with(PetType::Cat).freeze()
+}
+
+#[test]
+fn test_frozen_read() {
+    // GIVEN a frozen context
+    let mut frozen = golden();
+
+    // WHEN building an object that only reads existing entries
+    let pet = frozen.build::<Pet>();
+
+    // THEN the entries are used
+    assert_eq!(pet.pet_type, PetType::Cat);
+    assert_eq!(frozen.get(), Some(&PetType::Cat));
+}
+
+#[test]
+#[should_panic(expected = "cannot insert `u32` into a frozen context")]
+fn test_frozen_insert() {
+    // GIVEN a frozen context
+    let mut frozen = golden();
+
+    // WHEN inserting a value
+    // THEN it panics
+    frozen.insert(42u32);
+}
+
+#[test]
+#[should_panic(expected = "into a frozen context")]
+fn test_frozen_entry_missing() {
+    // GIVEN a frozen context without the entry
+    let mut frozen = ctxbuilder::ctx().freeze();
+
+    // WHEN building an object that inserts a default value
+    // THEN it panics
+    frozen.build::<Pet>();
+}
+
+#[test]
+#[should_panic(expected = "cannot modify `frozen::PetType` in a frozen context")]
+fn test_frozen_entry_modify() {
+    // GIVEN a frozen context
+    let mut frozen = golden();
+
+    // WHEN modifying an existing entry
+    // THEN it panics
+    frozen
+        .entry::<PetType>()
+        .and_modify(|pet_type| *pet_type = PetType::Dog)
+        .or_insert(PetType::Dog);
+}
+
+#[test]
+#[should_panic(expected = "cannot modify `frozen::PetType` in a frozen context")]
+fn test_frozen_dyn_entry_modify() {
+    // GIVEN a type-erased view of a frozen context
+    let mut frozen = golden();
+    let mut ctx = DynRef::new(&mut frozen);
+
+    // WHEN modifying an existing entry
+    // THEN it panics
+    ctx.entry::<PetType>()
+        .and_modify(|pet_type| *pet_type = PetType::Dog);
+}
+
+#[test]
+fn test_frozen_fork() {
+    // GIVEN a frozen context
+    let frozen = golden();
+
+    // WHEN forking and modifying it
+    let mut fork = frozen.fork().with(PetType::Dog);
+
+    // THEN only the fork is modified
+    assert_eq!(fork.build::<Pet>().pet_type, PetType::Dog);
+    assert_eq!(frozen.get(), Some(&PetType::Cat));
+    assert_eq!(frozen.clone().build::<Pet>().pet_type, PetType::Cat);
+}
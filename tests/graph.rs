@@ -0,0 +1,83 @@
+use ctxbuilder::{Builder, Context, MainContext};
+
+struct Account {
+    _owner: User,
+    _admin: User,
+}
+
+impl Builder for Account {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            _owner: ctx.build_named("owner"),
+            _admin: ctx.build_named("admin"),
+        }
+    }
+}
+
+struct User {
+    _id: uuid::Uuid,
+}
+
+impl ctxbuilder::NamedBuilder for User {
+    fn build_with_name<C: Context>(ctx: &mut C, _name: &'static str) -> Self {
+        Self { _id: ctx.build() }
+    }
+}
+
+#[test]
+fn test_object_graph() {
+    // GIVEN a recording context
+    let mut ctx = MainContext::recording(42);
+
+    // WHEN building an object with nested builds
+    ctx.build::<Account>();
+    ctx.build::<uuid::Uuid>();
+
+    // THEN the graph links each object to the ones built by its builder
+    let graph = ctx.object_graph().unwrap();
+    let nodes: Vec<_> = graph
+        .nodes()
+        .iter()
+        .map(|node| (node.type_name(), node.name()))
+        .collect();
+    assert_eq!(
+        nodes,
+        [
+            ("graph::Account", None),
+            ("graph::User", Some("owner")),
+            ("uuid::Uuid", None),
+            ("graph::User", Some("admin")),
+        ]
+    );
+    assert_eq!(graph.edges(), [(0, 1), (1, 2), (0, 3), (3, 2)]);
+    assert_eq!(graph.roots().count(), 1);
+    assert_eq!(graph.children(0).count(), 2);
+}
+
+#[test]
+fn test_object_graph_dot() {
+    // GIVEN a context with a recorded build
+    let mut ctx = MainContext::recording(42);
+    ctx.build_named::<User, _>("owner");
+
+    // WHEN rendering the graph to DOT
+    let dot = ctx.object_graph().unwrap().to_dot();
+
+    // THEN it contains the nodes and edges
+    assert_eq!(
+        dot,
+        "digraph objects {\n    n0 [label=\"owner\\ngraph::User\"];\n    n1 [label=\"uuid::Uuid\"];\n    n0 -> n1;\n}\n"
+    );
+}
+
+#[test]
+fn test_object_graph_not_recording() {
+    // GIVEN a context that isn't recording
+    let mut ctx = MainContext::new();
+
+    // WHEN building an object
+    ctx.build::<Account>();
+
+    // THEN there is no graph
+    assert!(ctx.object_graph().is_none());
+}
@@ -0,0 +1,113 @@
+use ctxbuilder::{Context, ContextRead, ContextWrite};
+
+#[derive(Clone, Debug, PartialEq)]
+struct Person {
+    name: &'static str,
+    age: u32,
+}
+
+#[test]
+fn test_history() {
+    // GIVEN a context that keeps the history of an entry
+    let mut ctx = ctxbuilder::ctx();
+    ctx.keep_history::<Person>("person");
+
+    // WHEN replacing its value
+    ctx.insert_named(
+        "person",
+        Person {
+            name: "Alice",
+            age: 30,
+        },
+    );
+    ctx.insert_named(
+        "person",
+        Person {
+            name: "Alice",
+            age: 31,
+        },
+    );
+    ctx.insert_named(
+        "person",
+        Person {
+            name: "Bob",
+            age: 31,
+        },
+    );
+
+    // THEN the previous values are kept, from the oldest to the newest
+    assert_eq!(
+        ctx.history::<Person>("person"),
+        vec![
+            &Person {
+                name: "Alice",
+                age: 30
+            },
+            &Person {
+                name: "Alice",
+                age: 31
+            },
+        ]
+    );
+    assert_eq!(ctx.get_named::<Person>("person").unwrap().name, "Bob");
+}
+
+#[test]
+fn test_history_disabled() {
+    // GIVEN a context that doesn't keep the history of an entry
+    let mut ctx = ctxbuilder::ctx().with_named(
+        "person",
+        Person {
+            name: "Alice",
+            age: 30,
+        },
+    );
+    ctx.keep_history::<Person>("other");
+
+    // WHEN replacing its value
+    ctx.insert_named(
+        "person",
+        Person {
+            name: "Bob",
+            age: 31,
+        },
+    );
+
+    // THEN nothing is kept
+    assert!(ctx.history::<Person>("person").is_empty());
+    assert!(ctx.history::<Person>("other").is_empty());
+}
+
+#[test]
+fn test_history_fork() {
+    // GIVEN a fork of a context that keeps the history of an entry
+    let mut ctx = ctxbuilder::ctx();
+    ctx.keep_history::<u32>("count");
+    ctx.insert_named("count", 1u32);
+    let mut fork = ctx.fork();
+
+    // WHEN replacing the shared value in both contexts
+    ctx.insert_named("count", 2u32);
+    fork.insert_named("count", 3u32);
+
+    // THEN only the original context keeps its history, including the shared value
+    assert_eq!(ctx.history::<u32>("count"), vec![&1]);
+    assert!(fork.history::<u32>("count").is_empty());
+}
+
+#[test]
+fn test_history_clear() {
+    // GIVEN a context with the history of an entry
+    let mut ctx = ctxbuilder::ctx();
+    ctx.keep_history::<u32>("count");
+    ctx.insert_named("count", 1u32);
+    ctx.insert_named("count", 2u32);
+
+    // WHEN clearing it and replacing the value again
+    ctx.clear();
+    ctx.insert_named("count", 3u32);
+    ctx.insert_named("count", 4u32);
+
+    // THEN the history restarts
+    assert_eq!(ctx.history::<u32>("count"), vec![&3]);
+}
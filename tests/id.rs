@@ -0,0 +1,101 @@
+use std::time::Duration;
+
+use ctxbuilder::{Builder, Clock, Context, ContextRead, IdStrategy, Identified};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct PersonId(u64);
+
+impl From<u64> for PersonId {
+    fn from(id: u64) -> Self {
+        Self(id)
+    }
+}
+
+#[derive(Debug)]
+struct Person {
+    id: PersonId,
+}
+
+impl Identified for Person {
+    type Id = PersonId;
+
+    fn default_id_strategy() -> IdStrategy<Self> {
+        IdStrategy::sequence()
+    }
+}
+
+impl Builder for Person {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            id: ctx.next_id::<Person>(),
+        }
+    }
+}
+
+struct Order;
+
+impl Identified for Order {
+    type Id = u64;
+
+    fn default_id_strategy() -> IdStrategy<Self> {
+        IdStrategy::new(|_, _| 1000)
+    }
+}
+
+#[test]
+fn test_next_id_default() {
+    // GIVEN a context without any strategy
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN building objects
+    let people: Vec<Person> = ctx.build_vec(2);
+
+    // THEN the default strategy of each type is used
+    assert_eq!(people[0].id, PersonId(1));
+    assert_eq!(people[1].id, PersonId(2));
+    assert_eq!(ctx.next_id::<Order>(), 1000);
+}
+
+#[test]
+fn test_next_id_snowflake() {
+    // GIVEN a context with a snowflake strategy and a clock
+    let mut ctx = ctxbuilder::ctx()
+        .with(IdStrategy::<Person>::snowflake(7))
+        .with(Clock::from_unix_millis(1_000));
+
+    // WHEN building objects while advancing the clock
+    let first = ctx.build::<Person>();
+    let second = ctx.build::<Person>();
+    ctx.get::<Clock>()
+        .unwrap()
+        .advance(Duration::from_millis(1));
+    let third = ctx.build::<Person>();
+
+    // THEN IDs are sorted and made of the time, node and counter
+    assert_eq!(first.id, PersonId((1_000 << 22) | (7 << 12)));
+    assert_eq!(second.id, PersonId((1_000 << 22) | (7 << 12) | 1));
+    assert_eq!(third.id, PersonId((1_001 << 22) | (7 << 12) | 2));
+}
+
+#[cfg(feature = "uuid")]
+#[test]
+fn test_next_id_uuid() {
+    // GIVEN seeded contexts with a UUID strategy
+    struct Account;
+    impl Identified for Account {
+        type Id = uuid::Uuid;
+
+        fn default_id_strategy() -> IdStrategy<Self> {
+            IdStrategy::uuid()
+        }
+    }
+    let mut a = ctxbuilder::MainContext::seeded(1);
+    let mut b = ctxbuilder::MainContext::seeded(1);
+
+    // WHEN generating IDs
+    let id = a.next_id::<Account>();
+
+    // THEN they are deterministic and different every time
+    assert_eq!(id, b.next_id::<Account>());
+    assert_ne!(id, a.next_id::<Account>());
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "json")]
+
+use ctxbuilder::{json::JsonTemplate, Context};
+use serde_json::{json, Value};
+
+#[test]
+fn test_json_template() {
+    // GIVEN a context with a JSON template and named entries
+    let template = JsonTemplate::new(
+        r#"{
+            "id": "{{person}}",
+            "email": "{{person}}@example.com",
+            "age": "{{age}}",
+            "tags": ["{{tag}}"]
+        }"#,
+    )
+    .unwrap();
+    let mut ctx = ctxbuilder::ctx()
+        .with(template)
+        .with_named("person", "alice".to_string())
+        .with_named("age", 42u32)
+        .with_named("tag", "admin");
+
+    // WHEN building a JSON value
+    let value: Value = ctx.build();
+
+    // THEN the placeholders are replaced by the context entries
+    assert_eq!(
+        value,
+        json!({
+            "id": "alice",
+            "email": "alice@example.com",
+            "age": 42,
+            "tags": ["admin"],
+        })
+    );
+}
+
+#[test]
+fn test_json_template_entry_types() {
+    // GIVEN a context with a JSON template and named entries of common types
+    let template =
+        JsonTemplate::new(r#"{"count": "{{count}}", "label": "{{count}} items"}"#).unwrap();
+    let mut ctx = ctxbuilder::ctx().with(template).with_named("count", 3usize);
+
+    // WHEN building a JSON value
+    let value: Value = ctx.build();
+
+    // THEN the entries are used like in `Context::format`
+    assert_eq!(value, json!({"count": 3, "label": "3 items"}));
+    assert_eq!(ctx.format("{count} items"), "3 items");
+}
+
+#[test]
+fn test_json_template_named() {
+    // GIVEN a context with a named JSON template
+    let template = JsonTemplate::new(r#"{"event": "{{event}}"}"#).unwrap();
+    let mut ctx = ctxbuilder::ctx()
+        .with_named("webhook", template)
+        .with_named("event", "created");
+
+    // WHEN building the named JSON value
+    let value: Value = ctx.build_named("webhook");
+
+    // THEN it uses the named template
+    assert_eq!(value, json!({"event": "created"}));
+}
+
+#[test]
+#[should_panic(expected = "missing context entry for placeholder `{{person}}`")]
+fn test_json_template_missing() {
+    // GIVEN a context with a template, but no matching entry
+    let template = JsonTemplate::new(r#"{"id": "{{person}}"}"#).unwrap();
+    let mut ctx = ctxbuilder::ctx().with(template);
+
+    // WHEN building the JSON value
+    // THEN it panics
+    let _: Value = ctx.build();
+}
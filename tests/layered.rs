@@ -0,0 +1,68 @@
+use ctxbuilder::{Builder, Context, ContextRead, LayeredContext};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Region {
+    Us,
+    Eu,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Team {
+    region: Region,
+    name: &'static str,
+    size: u32,
+}
+
+impl Builder for Team {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            region: *ctx.entry().or_insert(Region::Us),
+            name: ctx.entry_named("team").or_insert("default"),
+            size: *ctx.entry_named("size").or_insert(1),
+        }
+    }
+}
+
+#[test]
+fn test_layered() {
+    // GIVEN global and team defaults
+    let global = ctxbuilder::ctx()
+        .with(Region::Us)
+        .with_named("team", "global")
+        .with_named("size", 10u32);
+    let team = ctxbuilder::ctx().with_named("team", "platform");
+
+    // WHEN building with test overrides on top
+    let mut ctx = LayeredContext::new(&global)
+        .with_layer(&team)
+        .with(Region::Eu);
+    let built = ctx.build::<Team>();
+
+    // THEN lookups are resolved top-down
+    assert_eq!(
+        built,
+        Team {
+            region: Region::Eu,
+            name: "platform",
+            size: 10,
+        }
+    );
+
+    // THEN the parents are untouched
+    assert_eq!(global.get(), Some(&Region::Us));
+    assert_eq!(team.get::<Region>(), None);
+}
+
+#[test]
+fn test_layered_rng() {
+    // GIVEN a seeded base context
+    let base = ctxbuilder::MainContext::seeded(1);
+    let top = ctxbuilder::MainContext::seeded(2);
+    let expected = ctxbuilder::MainContext::seeded(1).rng().next_u64();
+
+    // WHEN drawing random numbers from a layered context
+    let ctx = LayeredContext::new(&base).with_layer(&top);
+
+    // THEN the random number generator of the base context is used
+    assert_eq!(ctx.rng().next_u64(), expected);
+}
@@ -0,0 +1,55 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ctxbuilder::{Builder, Context, LocalContext};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum PetType {
+    Dog,
+    Cat,
+}
+
+impl Builder for PetType {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        *ctx.entry().or_insert(PetType::Dog)
+    }
+}
+
+#[test]
+fn test_local_values() {
+    // GIVEN a local context with non-Send values
+    let log: Rc<RefCell<Vec<&str>>> = Rc::new(RefCell::new(Vec::new()));
+    let ctx = LocalContext::new()
+        .with_local(log.clone())
+        .with_local_named("counter", RefCell::new(0));
+
+    // WHEN using the values
+    ctx.get_local::<Rc<RefCell<Vec<&str>>>>()
+        .unwrap()
+        .borrow_mut()
+        .push("hello");
+    *ctx.get_local_named::<RefCell<i32>>("counter")
+        .unwrap()
+        .borrow_mut() += 1;
+
+    // THEN they are shared with the test
+    assert_eq!(*log.borrow(), vec!["hello"]);
+    assert_eq!(
+        *ctx.get_local_named::<RefCell<i32>>("counter")
+            .unwrap()
+            .borrow(),
+        1
+    );
+}
+
+#[test]
+fn test_local_builders() {
+    // GIVEN a local context with a shared value
+    let mut ctx = LocalContext::new().with(PetType::Cat);
+
+    // WHEN building objects
+    let pet_type: PetType = ctx.build();
+
+    // THEN builders use the shared value, which is also visible as a local value
+    assert_eq!(pet_type, PetType::Cat);
+    assert_eq!(ctx.get_local(), Some(&PetType::Cat));
+}
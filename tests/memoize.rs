@@ -0,0 +1,60 @@
+use ctxbuilder::{Builder, Context, NamedBuilder};
+
+#[derive(Debug, PartialEq, Eq)]
+struct Person {
+    id: u64,
+}
+
+impl Builder for Person {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self { id: ctx.next_seq() }
+    }
+}
+
+impl NamedBuilder for Person {
+    fn build_with_name<C: Context>(ctx: &mut C, name: &'static str) -> Self {
+        Self {
+            id: ctx.next_seq_named(name) + 100,
+        }
+    }
+}
+
+#[test]
+fn test_get_or_build() {
+    // GIVEN a context
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN getting the person multiple times
+    let first = ctx.get_or_build::<Person>().id;
+    let second = ctx.get_or_build::<Person>().id;
+
+    // THEN it is only built once
+    assert_eq!(first, 1);
+    assert_eq!(second, 1);
+    assert_eq!(ctx.build::<Person>().id, 2);
+}
+
+#[test]
+fn test_get_or_build_existing() {
+    // GIVEN a context with an existing person
+    let mut ctx = ctxbuilder::ctx().with(Person { id: 42 });
+
+    // WHEN getting the person
+    // THEN it returns the existing person
+    assert_eq!(ctx.get_or_build::<Person>(), &Person { id: 42 });
+}
+
+#[test]
+fn test_get_or_build_named() {
+    // GIVEN a context
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN getting named persons
+    let alice = ctx.get_or_build_named::<Person>("alice").id;
+    let bob = ctx.get_or_build_named::<Person>("bob").id;
+
+    // THEN each name is built once
+    assert_eq!(alice, 101);
+    assert_eq!(bob, 101);
+    assert_eq!(ctx.get_or_build_named::<Person>("alice").id, 101);
+}
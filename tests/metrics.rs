@@ -0,0 +1,77 @@
+#![cfg(feature = "metrics")]
+
+use ctxbuilder::{Builder, Context, ContextRead, ContextWrite, Stats, TypeStats};
+
+struct Person {
+    id: u64,
+}
+
+impl Builder for Person {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self { id: ctx.next_seq() }
+    }
+}
+
+#[test]
+fn test_stats() {
+    // GIVEN a context where objects are built, reused and inserted
+    let mut ctx = ctxbuilder::ctx();
+    ctx.build_vec::<Person>(2);
+    let first = ctx.get_or_build::<Person>().id;
+    let second = ctx.get_or_build::<Person>().id;
+    ctx.insert_named("retries", 3u32);
+
+    // WHEN getting the stats
+    let stats = ctx.stats();
+
+    // THEN the builds, cache hits and inserts are counted per type
+    assert_eq!(first, second);
+    assert_eq!(
+        stats.get::<Person>(),
+        TypeStats {
+            builds: 3,
+            cache_hits: 1,
+            inserts: 1,
+        }
+    );
+    assert_eq!(stats.get::<u32>().inserts, 1);
+    assert_eq!(stats.get::<bool>(), TypeStats::default());
+    assert_eq!(
+        stats.to_string(),
+        "metrics::Person: 3 builds, 1 cache hits, 1 inserts\nu32: 0 builds, 0 cache hits, 1 inserts\n"
+    );
+}
+
+#[test]
+fn test_stats_subcontext() {
+    // GIVEN a sub-context and a fork of a context
+    let mut ctx = ctxbuilder::ctx();
+    {
+        let mut sub = ctx.sub();
+        sub.build::<Person>();
+        sub.insert(1u32);
+        assert_eq!(sub.stats().get::<Person>().builds, 1);
+    }
+    let mut fork = ctx.fork();
+    fork.build::<Person>();
+
+    // WHEN getting the stats of the context
+    let stats = ctx.stats();
+
+    // THEN they include the sub-context, but not the fork
+    assert_eq!(stats.get::<Person>().builds, 1);
+    assert_eq!(stats.get::<u32>().inserts, 1);
+    assert_eq!(fork.stats().get::<Person>().builds, 1);
+}
+
+#[test]
+fn test_stats_frozen() {
+    // GIVEN a frozen context
+    let mut ctx = ctxbuilder::ctx();
+    ctx.build::<Person>();
+    let frozen = ctx.freeze();
+
+    // WHEN getting its stats
+    // THEN they are empty
+    assert_eq!(frozen.stats(), Stats::default());
+}
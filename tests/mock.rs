@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use ctxbuilder::{Builder, Context};
+
+#[mockall::automock]
+trait UserRepo {
+    fn find(&self, id: u64) -> Option<String>;
+}
+
+struct UserService {
+    repo: Arc<dyn UserRepo + Send + Sync>,
+}
+
+impl Builder for UserService {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            repo: ctx.mock::<MockUserRepo>(),
+        }
+    }
+}
+
+#[test]
+fn test_mock_configure() {
+    // GIVEN a context with a configured mock
+    let mut ctx = ctxbuilder::ctx();
+    ctx.configure_mock::<MockUserRepo>(|mock| {
+        mock.expect_find()
+            .withf(|id| *id == 1)
+            .return_const(Some("alice".to_string()));
+    });
+    ctx.configure_mock::<MockUserRepo>(|mock| {
+        mock.expect_find().return_const(None);
+    });
+
+    // WHEN building a service that uses the mock
+    let service = ctx.build::<UserService>();
+
+    // THEN the service calls the configured mock
+    assert_eq!(service.repo.find(1), Some("alice".to_string()));
+    assert_eq!(service.repo.find(2), None);
+}
+
+#[test]
+fn test_mock_shared() {
+    // GIVEN a context without any mock
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN getting the mock several times
+    let first = ctx.mock::<MockUserRepo>();
+    let second = ctx.mock::<MockUserRepo>();
+
+    // THEN the same mock is returned
+    assert!(Arc::ptr_eq(&first, &second));
+}
+
+#[test]
+#[should_panic(expected = "was already shared")]
+fn test_mock_configure_shared() {
+    // GIVEN a context with a mock that was already shared
+    let mut ctx = ctxbuilder::ctx();
+    let _mock = ctx.mock::<MockUserRepo>();
+
+    // WHEN configuring it
+    // THEN it panics
+    ctx.configure_mock::<MockUserRepo>(|mock| {
+        mock.expect_find().return_const(None);
+    });
+}
+
+#[test]
+fn test_mock_sub() {
+    // GIVEN a context with a configured mock
+    let mut ctx = ctxbuilder::ctx();
+    ctx.configure_mock::<MockUserRepo>(|mock| {
+        mock.expect_find().return_const(Some("alice".to_string()));
+    });
+
+    // WHEN a sub-context configures its own mock
+    let mut sub = ctx.sub();
+    sub.configure_mock::<MockUserRepo>(|mock| {
+        mock.expect_find().return_const(Some("bob".to_string()));
+    });
+    let sub_name = sub.build::<UserService>().repo.find(1);
+    drop(sub);
+
+    // THEN it shadows the one of the parent
+    assert_eq!(sub_name, Some("bob".to_string()));
+    assert_eq!(
+        ctx.build::<UserService>().repo.find(1),
+        Some("alice".to_string())
+    );
+}
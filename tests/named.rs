@@ -0,0 +1,149 @@
+use ctxbuilder::{Context, ContextRead, ContextWrite, Name, NamedBuilder};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct User {
+    name: &'static str,
+    id: u64,
+}
+
+impl NamedBuilder for User {
+    fn build_with_name<C: Context>(ctx: &mut C, name: &'static str) -> Self {
+        let id = ctx.next_seq();
+        ctx.entry_named(name)
+            .or_insert_with(|| User { name, id })
+            .clone()
+    }
+}
+
+#[test]
+fn test_build_all_named() {
+    // GIVEN a context
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN building a roster of named users
+    let users: Vec<User> = ctx.build_all_named(&["alice", "bob"]);
+
+    // THEN each user is built with its name, in order
+    assert_eq!(
+        users,
+        vec![
+            User {
+                name: "alice",
+                id: 1
+            },
+            User { name: "bob", id: 2 }
+        ]
+    );
+}
+
+#[test]
+fn test_build_array_named() {
+    // GIVEN a context with an existing user
+    let mut ctx = ctxbuilder::ctx().with_named(
+        "bob",
+        User {
+            name: "bob",
+            id: 42,
+        },
+    );
+
+    // WHEN building named users into an array
+    let [alice, bob]: [User; 2] = ctx.build_array_named(["alice", "bob"]);
+
+    // THEN existing users are reused
+    assert_eq!(alice.name, "alice");
+    assert_eq!(bob.id, 42);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Role {
+    Admin,
+    Guest,
+}
+
+impl Name for Role {
+    fn as_str(&self) -> Option<&'static str> {
+        Some(match self {
+            Self::Admin => "admin",
+            Self::Guest => "guest",
+        })
+    }
+}
+
+impl NamedBuilder<Role> for User {
+    fn build_with_name<C: Context>(ctx: &mut C, role: Role) -> Self {
+        ctx.build_named(role.as_str().unwrap())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Shard(u32);
+
+impl NamedBuilder<u32> for Shard {
+    fn build_with_name<C: Context>(_ctx: &mut C, index: u32) -> Self {
+        Self(index)
+    }
+}
+
+#[test]
+fn test_build_named_enum() {
+    // GIVEN a context
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN building users by role
+    let admin: User = ctx.build_named(Role::Admin);
+    let users: Vec<User> = ctx.build_all_named(&[Role::Guest, Role::Admin]);
+
+    // THEN roles are used as names
+    assert_eq!(admin.name, "admin");
+    assert_eq!(users[0].name, "guest");
+    assert_eq!(users[1], admin);
+}
+
+#[test]
+fn test_build_named_integer() {
+    // GIVEN a context
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN building objects with integer names
+    let shards: [Shard; 2] = ctx.build_array_named([3, 5]);
+
+    // THEN each object receives its name
+    assert_eq!(shards, [Shard(3), Shard(5)]);
+}
+
+#[test]
+fn test_iter_named() {
+    // GIVEN a context with named users built by fixtures
+    let mut ctx = ctxbuilder::ctx();
+    ctx.build_all_named::<User, _>(&["carol", "alice"]);
+    ctx.insert(User {
+        name: "unnamed",
+        id: 0,
+    });
+
+    // WHEN iterating over the named users
+    let names: Vec<_> = ctx.iter_named::<User>().map(|(name, _)| name).collect();
+
+    // THEN all of them are returned, sorted by name
+    assert_eq!(names, ["alice", "carol"]);
+}
+
+#[test]
+fn test_iter_named_layers() {
+    // GIVEN a sub-context that shadows one named entry of its parent
+    let mut ctx = ctxbuilder::ctx()
+        .with_named("a", 1u32)
+        .with_named("b", 2u32);
+    let mut fork = ctx.fork();
+    fork.retain(|_, name| name != Some("a"));
+    let mut sub = fork.sub();
+    sub.insert_named("b", 3u32);
+    sub.insert_named("c", 4u32);
+
+    // WHEN iterating over the named entries
+    let values: Vec<_> = sub.iter_named::<u32>().collect();
+
+    // THEN only the newest visible entries are returned
+    assert_eq!(values, [("b", &3), ("c", &4)]);
+}
@@ -0,0 +1,92 @@
+use ctxbuilder::{Context, ContextRead, ContextWrite, MainContext};
+
+#[derive(Debug, PartialEq)]
+struct Otp(u32);
+
+#[test]
+fn test_insert_once() {
+    // GIVEN a context with a one-shot value
+    let mut ctx = MainContext::new();
+    ctx.insert_once_named("login", Otp(123456));
+
+    // WHEN reading it once
+    let otp = ctx.get_named::<Otp>("login");
+
+    // THEN it returns the value
+    assert_eq!(otp, Some(&Otp(123456)));
+}
+
+#[test]
+#[should_panic(expected = "one-shot context entry of type `once::Otp` was already read")]
+fn test_insert_once_reuse() {
+    // GIVEN a context with a one-shot value that was read
+    let mut ctx = MainContext::new();
+    ctx.insert_once(Otp(123456));
+    ctx.get::<Otp>();
+
+    // WHEN reading it again
+    // THEN it panics
+    ctx.get::<Otp>();
+}
+
+#[test]
+fn test_insert_once_replace() {
+    // GIVEN a context with a one-shot value that was read
+    let mut ctx = MainContext::new();
+    assert_eq!(ctx.insert_once(Otp(1)), None);
+    ctx.get::<Otp>();
+
+    // WHEN inserting a new one
+    let old = ctx.insert_once(Otp(2));
+
+    // THEN the new value can be read
+    assert_eq!(old, Some(Otp(1)));
+    assert_eq!(ctx.get::<Otp>(), Some(&Otp(2)));
+}
+
+#[test]
+#[should_panic(expected = "already read")]
+fn test_insert_once_entry() {
+    // GIVEN a context with a one-shot value that was read
+    let mut ctx = MainContext::new();
+    ctx.insert_once(Otp(1));
+    ctx.get::<Otp>();
+
+    // WHEN modifying it through its entry
+    // THEN it panics
+    ctx.entry::<Otp>().and_modify(|otp| otp.0 += 1);
+}
+
+#[test]
+fn test_insert_once_get_or_build() {
+    // GIVEN a context with a one-shot value
+    let mut ctx = MainContext::new();
+    ctx.insert_once(uuid::Uuid::from_u128(5));
+
+    // WHEN getting it or building it
+    let uuid = *ctx.get_or_build::<uuid::Uuid>();
+
+    // THEN the value is read once, without building a new one
+    assert_eq!(uuid, uuid::Uuid::from_u128(5));
+}
+
+#[test]
+fn test_insert_once_contains() {
+    // GIVEN a sub-context over a one-shot value
+    let mut ctx = MainContext::new();
+    ctx.insert_once_named("login", Otp(123456));
+    let mut sub = ctx.sub();
+
+    // WHEN checking for it and reserving it
+    let contains = sub.contains_named::<Otp>("login");
+    let reservation = sub.reserve_named::<Otp>("login");
+    let deferred = sub
+        .deferred_entry_named::<Otp>("login")
+        .or_insert_with_ctx(|_| Otp(0))
+        .0;
+
+    // THEN the value is found, and only the last read consumes it
+    assert!(contains);
+    assert!(!reservation.is_vacant());
+    assert_eq!(deferred, 123456);
+}
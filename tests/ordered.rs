@@ -0,0 +1,46 @@
+#![cfg(feature = "ordered")]
+
+use core::any::TypeId;
+
+use ctxbuilder::{ContextRead, ContextWrite, MainContext};
+
+#[test]
+fn test_entry_keys_insertion_order() {
+    // GIVEN a context with entries inserted in a given order
+    let mut ctx = MainContext::new();
+    ctx.insert(1u32);
+    ctx.insert_named("b", "b".to_string());
+    ctx.insert(true);
+    ctx.insert_named("a", "a".to_string());
+
+    // WHEN listing the entries
+    let keys = ctx.entry_keys();
+
+    // THEN they are in insertion order
+    assert_eq!(
+        keys,
+        vec![
+            ("u32", None),
+            ("alloc::string::String", Some("b")),
+            ("bool", None),
+            ("alloc::string::String", Some("a")),
+        ]
+    );
+}
+
+#[test]
+fn test_entry_keys_after_remove() {
+    // GIVEN a context where an entry is removed and another one is replaced
+    let mut ctx = MainContext::new();
+    ctx.insert(1u32);
+    ctx.insert(true);
+    ctx.insert(1u8);
+    ctx.retain(|type_id, _| type_id != TypeId::of::<bool>());
+    ctx.insert(2u32);
+
+    // WHEN listing the entries
+    let keys = ctx.entry_keys();
+
+    // THEN the other entries keep their order
+    assert_eq!(keys, vec![("u32", None), ("u8", None)]);
+}
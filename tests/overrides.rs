@@ -0,0 +1,137 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use ctxbuilder::{Builder, Context, ContextRead, ContextWrite, MainContext, Step};
+
+#[derive(Debug, PartialEq)]
+struct Config {
+    region: &'static str,
+    retries: u32,
+}
+
+impl Builder for Config {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            region: ctx.entry().or_insert("eu-west-1"),
+            retries: *ctx.entry_named("retries").or_insert(1),
+        }
+    }
+}
+
+#[test]
+fn test_with_override() {
+    // GIVEN a context with an entry
+    let mut ctx = ctxbuilder::ctx().with_named("retries", 3u32);
+
+    // WHEN building with an override
+    let config = ctx.with_override_named("retries", 5u32, |ctx| ctx.build::<Config>());
+
+    // THEN the override is used, then the previous value is restored
+    assert_eq!(config.retries, 5);
+    assert_eq!(ctx.get_named("retries"), Some(&3u32));
+}
+
+#[test]
+fn test_with_override_missing() {
+    // GIVEN a context without an entry
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN overriding it
+    let region = ctx.with_override("us-east-1", |ctx| ctx.build::<Config>().region);
+
+    // THEN the entry is removed afterwards
+    assert_eq!(region, "us-east-1");
+    assert_eq!(ctx.get::<&str>(), None);
+}
+
+#[test]
+fn test_with_override_panic() {
+    // GIVEN a context with an entry
+    let mut ctx = ctxbuilder::ctx().with_named("retries", 3u32);
+
+    // WHEN the closure panics
+    let res = catch_unwind(AssertUnwindSafe(|| {
+        ctx.with_override_named("retries", 5u32, |_| panic!("test failure"));
+    }));
+
+    // THEN the previous value is restored
+    assert!(res.is_err());
+    assert_eq!(ctx.get_named("retries"), Some(&3u32));
+}
+
+#[test]
+fn test_override_guard() {
+    // GIVEN a context with an entry
+    let mut ctx = ctxbuilder::ctx().with("eu-west-1");
+
+    // WHEN overriding it with a guard
+    let mut guard = ctx.override_guard("us-east-1");
+    guard.insert_named("retries", 2u32);
+    let config = guard.build::<Config>();
+    drop(guard);
+
+    // THEN the override lasts until the guard is dropped
+    assert_eq!(
+        config,
+        Config {
+            region: "us-east-1",
+            retries: 2
+        }
+    );
+    assert_eq!(ctx.get(), Some(&"eu-west-1"));
+    assert_eq!(ctx.get_named("retries"), Some(&2u32));
+}
+
+#[test]
+fn test_override_guard_fork() {
+    // GIVEN a fork sharing an entry
+    let mut ctx = ctxbuilder::ctx().with("eu-west-1");
+    let mut fork = ctx.fork();
+
+    // WHEN overriding the entry in the fork
+    {
+        let guard = fork.override_guard_named("retries", 5u32);
+        assert_eq!(guard.get_named("retries"), Some(&5u32));
+    }
+    fork.with_override("us-east-1", |fork| {
+        assert_eq!(fork.get(), Some(&"us-east-1"));
+    });
+
+    // THEN the shared entry is visible again afterwards
+    assert_eq!(fork.get(), Some(&"eu-west-1"));
+    assert_eq!(fork.get_named::<u32>("retries"), None);
+    assert_eq!(ctx.get(), Some(&"eu-west-1"));
+}
+
+#[test]
+fn test_with_override_recorded() {
+    // GIVEN a recording context keeping the history of an entry
+    let mut ctx = MainContext::recording(42);
+    ctx.register_display::<u32>();
+    ctx.keep_history::<u32>("retries");
+    ctx.insert_named("retries", 3u32);
+
+    // WHEN overriding the entry, and an entry that doesn't exist
+    let retries = ctx.with_override_named("retries", 5u32, |ctx| {
+        ctx.get_named::<u32>("retries").copied()
+    });
+    ctx.with_override(7u32, |_| ());
+
+    // THEN the override and the restored value are recorded, but not the removal
+    let insert = |name: Option<&str>, value: &str| Step::Insert {
+        type_name: "u32".to_string(),
+        name: name.map(str::to_string),
+        value: Some(value.to_string()),
+    };
+    assert_eq!(
+        ctx.recipe().unwrap().steps(),
+        [
+            insert(Some("retries"), "3"),
+            insert(Some("retries"), "5"),
+            insert(Some("retries"), "3"),
+            insert(None, "7"),
+        ]
+    );
+    assert_eq!(retries, Some(5));
+    assert_eq!(ctx.history::<u32>("retries"), vec![&3, &5]);
+    assert_eq!(ctx.get::<u32>(), None);
+}
@@ -0,0 +1,58 @@
+use ctxbuilder::{Context, ParamBuilder};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Pending,
+    Shipped,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Order {
+    id: u64,
+    status: Status,
+    items: Vec<u64>,
+}
+
+impl ParamBuilder<(Status, usize)> for Order {
+    fn build_with<C: Context>(ctx: &mut C, (status, count): (Status, usize)) -> Self {
+        Self {
+            id: ctx.next_seq_named("orders"),
+            status,
+            items: (0..count).map(|_| ctx.next_seq_named("items")).collect(),
+        }
+    }
+}
+
+impl ParamBuilder<Status> for Order {
+    fn build_with<C: Context>(ctx: &mut C, status: Status) -> Self {
+        ctx.build_with((status, 1))
+    }
+}
+
+#[test]
+fn test_build_with() {
+    // GIVEN a context
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN building objects with parameters
+    let pending = ctx.build_with::<Order, _>((Status::Pending, 2));
+    let shipped: Order = ctx.build_with(Status::Shipped);
+
+    // THEN the parameters are used alongside the context
+    assert_eq!(
+        pending,
+        Order {
+            id: 1,
+            status: Status::Pending,
+            items: vec![1, 2],
+        }
+    );
+    assert_eq!(
+        shipped,
+        Order {
+            id: 2,
+            status: Status::Shipped,
+            items: vec![3],
+        }
+    );
+}
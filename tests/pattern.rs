@@ -0,0 +1,98 @@
+#![cfg(feature = "regex")]
+
+use ctxbuilder::{Context, ContextWrite, MainContext};
+use regex::Regex;
+
+const PATTERNS: &[&str] = &[
+    r"[A-Z]{3}-\d{4}",
+    r"^[A-Z]{2}[0-9]{2} [A-Z]{3}$",
+    r"97[89]-\d-\d{2}-\d{6}-\d",
+    r"(foo|bar)+baz?",
+    r"\w+@\w+\.(com|org)",
+    r"[^a-z]{5}",
+    r".{0,3}",
+    r"(?i)ab[c-e]*",
+];
+
+#[test]
+fn test_string_matching() {
+    // GIVEN a context
+    let ctx = ctxbuilder::ctx();
+
+    for pattern in PATTERNS {
+        let regex = Regex::new(&format!("^(?:{pattern})$")).unwrap();
+        for _ in 0..50 {
+            // WHEN generating a string matching the pattern
+            let value = ctx.string_matching(pattern);
+
+            // THEN it matches
+            assert!(
+                regex.is_match(&value),
+                "`{value}` doesn't match `{pattern}`"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_string_matching_deterministic() {
+    // GIVEN two contexts with the same seed
+    let a = MainContext::seeded(9);
+    let b = MainContext::seeded(9);
+
+    // WHEN generating strings
+    // THEN they are the same
+    for pattern in PATTERNS {
+        assert_eq!(a.string_matching(pattern), b.string_matching(pattern));
+    }
+}
+
+#[test]
+fn test_string_matching_ascii() {
+    // GIVEN a context
+    let ctx = ctxbuilder::ctx();
+
+    // WHEN generating strings from Unicode classes
+    let value = ctx.string_matching(r"\d{20}\w{20}");
+
+    // THEN they only use ASCII characters
+    assert!(value.is_ascii());
+}
+
+#[test]
+fn test_entry_or_insert_matching() {
+    // GIVEN a context with a license plate
+    let mut ctx = ctxbuilder::ctx().with_named("plate", "AB12 CDE".to_string());
+
+    // WHEN inserting strings matching patterns
+    let plate = ctx
+        .entry_named("plate")
+        .or_insert_matching(r"[A-Z]{2}\d{2} [A-Z]{3}")
+        .clone();
+    let sku = ctx
+        .entry_named("sku")
+        .or_insert_matching(r"SKU-\d{6}")
+        .clone();
+
+    // THEN only the missing ones are generated
+    assert_eq!(plate, "AB12 CDE");
+    assert!(Regex::new(r"^SKU-\d{6}$").unwrap().is_match(&sku));
+}
+
+#[test]
+#[should_panic(expected = "invalid pattern `[A-Z`")]
+fn test_string_matching_invalid() {
+    // GIVEN an invalid pattern
+    // WHEN generating a string
+    // THEN it panics
+    ctxbuilder::ctx().string_matching("[A-Z");
+}
+
+#[test]
+#[should_panic(expected = "can't match any string")]
+fn test_string_matching_empty_class() {
+    // GIVEN a pattern that can't match anything
+    // WHEN generating a string
+    // THEN it panics
+    ctxbuilder::ctx().string_matching(r"[^\s\S]");
+}
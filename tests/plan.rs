@@ -0,0 +1,155 @@
+use ctxbuilder::{
+    BuildPlan, Builder, Context, ContextRead, ContextWrite, PlanError, Planned, Requirement,
+};
+
+struct Person {
+    name: String,
+}
+
+impl Builder for Person {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            name: ctx.get_named::<String>("name").unwrap().clone(),
+        }
+    }
+
+    fn requires() -> &'static [Requirement] {
+        const REQUIRES: &[Requirement] = &[Requirement::named::<String>("name")];
+        REQUIRES
+    }
+}
+
+struct Pet {
+    owner: String,
+}
+
+impl Builder for Pet {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            owner: ctx.get::<Person>().unwrap().name.clone(),
+        }
+    }
+
+    fn requires() -> &'static [Requirement] {
+        const REQUIRES: &[Requirement] = &[Requirement::of::<Person>()];
+        REQUIRES
+    }
+}
+
+struct Vet {
+    patients: usize,
+}
+
+impl Builder for Vet {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            patients: ctx.get::<Pet>().into_iter().count(),
+        }
+    }
+
+    fn requires() -> &'static [Requirement] {
+        const REQUIRES: &[Requirement] = &[Requirement::of::<Pet>()];
+        REQUIRES
+    }
+}
+
+struct Chicken;
+
+impl Builder for Chicken {
+    fn build<C: Context>(_ctx: &mut C) -> Self {
+        Self
+    }
+
+    fn requires() -> &'static [Requirement] {
+        const REQUIRES: &[Requirement] = &[Requirement::of::<Egg>()];
+        REQUIRES
+    }
+}
+
+struct Egg;
+
+impl Builder for Egg {
+    fn build<C: Context>(_ctx: &mut C) -> Self {
+        Self
+    }
+
+    fn requires() -> &'static [Requirement] {
+        const REQUIRES: &[Requirement] = &[Requirement::of::<Chicken>()];
+        REQUIRES
+    }
+}
+
+#[test]
+fn test_plan_order() {
+    // GIVEN types requested in the wrong order
+    let steps = [Vet::PLAN, Pet::PLAN, Person::PLAN, Pet::PLAN];
+
+    // WHEN computing the plan
+    let plan = BuildPlan::new(&steps).unwrap();
+
+    // THEN the types are built after their requirements, once each
+    let names: Vec<_> = plan.steps().iter().map(|step| step.type_name()).collect();
+    assert_eq!(names, vec!["plan::Person", "plan::Pet", "plan::Vet"]);
+}
+
+#[test]
+fn test_build_all() {
+    // GIVEN a context with the entries required by the plan
+    let mut ctx = ctxbuilder::ctx();
+    ctx.insert_named("name", "alice".to_string());
+
+    // WHEN building the types of the plan
+    ctx.build_all(&[Vet::PLAN, Pet::PLAN, Person::PLAN])
+        .unwrap();
+
+    // THEN they are built in order and inserted in the context
+    assert_eq!(ctx.get::<Person>().unwrap().name, "alice");
+    assert_eq!(ctx.get::<Pet>().unwrap().owner, "alice");
+    assert_eq!(ctx.get::<Vet>().unwrap().patients, 1);
+}
+
+#[test]
+fn test_build_all_existing() {
+    // GIVEN a context that already holds one of the types
+    let mut ctx = ctxbuilder::ctx().with(Person {
+        name: "bob".to_string(),
+    });
+
+    // WHEN building the types of the plan
+    ctx.build_all(&[Pet::PLAN, Person::PLAN]).unwrap();
+
+    // THEN the existing object is kept and used by the others
+    assert_eq!(ctx.get::<Person>().unwrap().name, "bob");
+    assert_eq!(ctx.get::<Pet>().unwrap().owner, "bob");
+}
+
+#[test]
+fn test_build_all_missing() {
+    // GIVEN a context without the entries required by the plan
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN building the types of the plan
+    let err = ctx.build_all(&[Pet::PLAN, Person::PLAN]).unwrap_err();
+
+    // THEN the missing entries are reported and nothing is built
+    let PlanError::Missing(missing) = err else {
+        panic!("expected missing entries, got {err:?}");
+    };
+    assert_eq!(missing.entries().len(), 1);
+    assert_eq!(missing.entries()[0].name(), Some("name"));
+    assert!(ctx.get::<Pet>().is_none());
+}
+
+#[test]
+fn test_plan_cycle() {
+    // GIVEN types that require each other
+    // WHEN computing the plan
+    let err = BuildPlan::new(&[Person::PLAN, Chicken::PLAN, Egg::PLAN]).unwrap_err();
+
+    // THEN the cycle is reported
+    assert_eq!(err, PlanError::Cycle(vec!["plan::Chicken", "plan::Egg"]));
+    assert_eq!(
+        err.to_string(),
+        "dependency cycle between `plan::Chicken`, `plan::Egg`"
+    );
+}
@@ -0,0 +1,113 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+use ctxbuilder::{Context, ContextPool, ContextRead, ContextWrite, MainContext};
+
+/// Stand-in for an external resource, such as a database
+#[derive(Default)]
+struct Database {
+    rows: Mutex<Vec<u32>>,
+}
+
+#[test]
+fn test_pool_reuse() {
+    // GIVEN a pool built with a counted setup
+    let setups = Arc::new(AtomicUsize::new(0));
+    let pool = {
+        let setups = setups.clone();
+        ContextPool::new(2, move || {
+            setups.fetch_add(1, Ordering::Relaxed);
+            MainContext::seeded(42).with_named("dataset", vec![1u32, 2, 3])
+        })
+    };
+
+    // WHEN using contexts from the pool multiple times
+    for _ in 0..5 {
+        let mut ctx = pool.get();
+        assert!(ctx.get_named::<u32>("retries").is_none());
+        ctx.insert_named("retries", 3u32);
+        assert_eq!(ctx.get_named::<Vec<u32>>("dataset").unwrap().len(), 3);
+    }
+
+    // THEN the setup only ran for the contexts of the pool
+    assert_eq!(setups.load(Ordering::Relaxed), 2);
+    assert_eq!((pool.size(), pool.idle()), (2, 2));
+}
+
+#[test]
+fn test_pool_reset() {
+    // GIVEN a pool that resets the external state of its contexts
+    let pool =
+        ContextPool::new(1, || MainContext::new().with(Database::default())).with_reset(|ctx| {
+            ctx.get::<Database>().unwrap().rows.lock().unwrap().clear();
+        });
+
+    // WHEN a test changes the external state
+    {
+        let ctx = pool.get();
+        ctx.get::<Database>().unwrap().rows.lock().unwrap().push(1);
+        assert_eq!(pool.idle(), 0);
+    }
+
+    // THEN the next test starts from a clean state
+    let ctx = pool.get();
+    assert!(ctx
+        .get::<Database>()
+        .unwrap()
+        .rows
+        .lock()
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_pool_wait() {
+    // GIVEN a pool whose only context is in use
+    let pool = ContextPool::new(1, MainContext::new);
+    let mut ctx = pool.get();
+    ctx.insert(1u32);
+
+    std::thread::scope(|s| {
+        // WHEN another thread takes a context
+        let other = s.spawn(|| pool.get().get::<u32>().copied());
+
+        // THEN it gets it once the first one is given back
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        assert!(!other.is_finished());
+        drop(ctx);
+        assert_eq!(other.join().unwrap(), None);
+    });
+}
+
+#[test]
+fn test_pool_teardown() {
+    // GIVEN a pool whose setup registers a teardown hook
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let pool = {
+        let log = log.clone();
+        ContextPool::new(1, move || {
+            let mut ctx = MainContext::new();
+            let log = log.clone();
+            ctx.on_teardown(move || log.lock().unwrap().push("setup"));
+            ctx
+        })
+    };
+
+    // WHEN a test registers a teardown hook and gives the context back
+    {
+        let mut ctx = pool.get();
+        let log = log.clone();
+        ctx.on_teardown(move || log.lock().unwrap().push("test"));
+    }
+
+    // THEN only the hook of the test ran
+    assert_eq!(*log.lock().unwrap(), ["test"]);
+
+    // WHEN the pool is dropped
+    drop(pool);
+
+    // THEN the hook of the setup ran
+    assert_eq!(*log.lock().unwrap(), ["test", "setup"]);
+}
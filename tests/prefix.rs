@@ -0,0 +1,85 @@
+use ctxbuilder::{Builder, Context, ContextRead, ContextWrite};
+
+#[test]
+fn test_get_prefixed() {
+    // GIVEN a context with dotted names
+    let ctx = ctxbuilder::ctx()
+        .with_named("order.id", 1u64)
+        .with_named("order.customer.id", 2u64)
+        .with_named("order.customer.name", "alice")
+        .with_named("invoice.id", 3u64);
+
+    // WHEN fetching entries under a prefix
+    // THEN only matching entries are returned, sorted by name
+    assert_eq!(
+        ctx.get_prefixed::<u64>("order."),
+        vec![("order.customer.id", &2), ("order.id", &1)]
+    );
+    assert_eq!(
+        ctx.names_with_prefix("order.customer."),
+        vec!["order.customer.id", "order.customer.name"]
+    );
+}
+
+#[test]
+fn test_remove_prefix() {
+    // GIVEN a context with dotted names
+    let mut ctx = ctxbuilder::ctx()
+        .with_named("order.id", 1u64)
+        .with_named("order.customer.name", "alice")
+        .with_named("invoice.id", 3u64);
+
+    // WHEN removing a prefix
+    let removed = ctx.remove_prefix("order.");
+
+    // THEN only entries under the prefix are removed
+    assert_eq!(removed, 2);
+    assert_eq!(ctx.get_named::<u64>("order.id"), None);
+    assert_eq!(ctx.get_named::<&str>("order.customer.name"), None);
+    assert_eq!(ctx.get_named("invoice.id"), Some(&3u64));
+}
+
+#[test]
+fn test_remove_prefix_fork() {
+    // GIVEN a forked context
+    let mut base = ctxbuilder::ctx().with_named("order.id", 1u64);
+    let mut fork = base.fork();
+
+    // WHEN removing a prefix from the fork
+    assert_eq!(fork.remove_prefix("order."), 1);
+
+    // THEN the shared entry is only hidden in the fork
+    assert_eq!(fork.get_named::<u64>("order.id"), None);
+    assert_eq!(base.get_named("order.id"), Some(&1u64));
+
+    // THEN new values can be inserted in the fork
+    assert_eq!(*fork.entry_named("order.id").or_insert(2u64), 2);
+    assert_eq!(fork.get_named("order.id"), Some(&2u64));
+    assert!(fork.unused_entries().is_empty());
+}
+
+struct Order {
+    id: u64,
+}
+
+impl Builder for Order {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            id: *ctx.entry_named("order.id").or_insert(42),
+        }
+    }
+}
+
+#[test]
+fn test_remove_prefix_build() {
+    // GIVEN a frozen base context forked after removing a prefix
+    let mut fork = ctxbuilder::ctx()
+        .with_named("order.id", 1u64)
+        .freeze()
+        .fork();
+    fork.remove_prefix("order.");
+
+    // WHEN building an object
+    // THEN the default value is used
+    assert_eq!(fork.build::<Order>().id, 42);
+}
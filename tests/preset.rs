@@ -0,0 +1,106 @@
+use ctxbuilder::{
+    preset::{self, Insert, InsertNamed, Preset},
+    Context, ContextRead, Email, FullName, Locale, Username,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Role {
+    Admin,
+    Guest,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Region {
+    Eu,
+    Us,
+}
+
+struct AdminUser;
+
+impl Preset for AdminUser {
+    fn apply<C: Context>(self, ctx: &mut C) {
+        ctx.insert(Role::Admin);
+        ctx.insert_named("email", "admin@example.com");
+    }
+}
+
+struct EuRegion;
+
+impl Preset for EuRegion {
+    fn apply<C: Context>(self, ctx: &mut C) {
+        ctx.insert(Region::Eu);
+    }
+}
+
+#[test]
+fn test_preset_compose() {
+    // GIVEN a context with composed presets
+    let ctx = ctxbuilder::ctx().with_preset((AdminUser, EuRegion));
+
+    // THEN all the presets are applied
+    assert_eq!(ctx.get(), Some(&Role::Admin));
+    assert_eq!(ctx.get(), Some(&Region::Eu));
+    assert_eq!(ctx.get_named("email"), Some(&"admin@example.com"));
+}
+
+#[test]
+fn test_preset_order() {
+    // GIVEN a base context
+    let mut ctx = ctxbuilder::ctx().with(Region::Us);
+
+    // WHEN applying presets on top of it
+    ctx.apply((
+        AdminUser,
+        Insert(Role::Guest),
+        InsertNamed("email", "guest@example.com"),
+    ));
+
+    // THEN later presets override earlier values
+    assert_eq!(ctx.get(), Some(&Role::Guest));
+    assert_eq!(ctx.get(), Some(&Region::Us));
+    assert_eq!(ctx.get_named("email"), Some(&"guest@example.com"));
+}
+
+#[test]
+fn test_preset_subcontext() {
+    // GIVEN a main context
+    let ctx = ctxbuilder::ctx().with(Role::Guest);
+
+    // WHEN applying a preset on a subcontext
+    let subctx = ctx.sub().with_preset(AdminUser);
+
+    // THEN only the subcontext is affected
+    assert_eq!(subctx.get(), Some(&Role::Admin));
+    assert_eq!(ctx.get(), Some(&Role::Guest));
+}
+
+#[test]
+fn test_preset_shipped() {
+    // GIVEN a context with the shipped presets
+    let mut ctx = ctxbuilder::ctx().with_preset((preset::AdminUser, preset::EuRegion));
+
+    // WHEN building the user of the context
+    let username = ctx.build::<Username>();
+    let email = ctx.build::<Email>();
+
+    // THEN it is the administrator, in the European Union
+    assert_eq!(username.as_str(), "admin");
+    assert_eq!(email.as_str(), "admin@example.de");
+    assert_eq!(ctx.build::<FullName>().as_str(), "Admin User");
+    assert_eq!(ctx.get(), Some(&Locale::DeDe));
+}
+
+#[cfg(feature = "aws_lambda_events")]
+#[test]
+fn test_preset_eu_region_aws() {
+    use ctxbuilder::aws_lambda_events::Region;
+
+    // GIVEN a context with the EU region preset
+    let mut ctx = ctxbuilder::ctx().with_preset(preset::EuRegion);
+
+    // WHEN building the AWS region
+    let region = ctx.build::<Region>();
+
+    // THEN it is in the European Union
+    assert_eq!(region, Region("eu-central-1".to_string()));
+}
@@ -0,0 +1,51 @@
+#![cfg(feature = "proptest")]
+
+use ctxbuilder::{
+    proptest::{strategy, strategy_with, Arb},
+    Builder, Context, ContextWrite, MainContext,
+};
+use proptest::prelude::*;
+
+#[derive(Clone, Debug)]
+struct Person {
+    age: u32,
+    name: String,
+}
+
+impl Builder for Person {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            age: *ctx.entry_named("age").or_insert_in_range(18..65),
+            name: ctx.format("person-{seq}"),
+        }
+    }
+}
+
+#[test]
+fn test_arb_deterministic() {
+    // GIVEN two contexts with the same seed
+    let mut a = MainContext::seeded(11);
+    let mut b = MainContext::seeded(11);
+
+    // WHEN building arbitrary values
+    let Arb(a): Arb<(u64, String)> = a.build();
+    let Arb(b): Arb<(u64, String)> = b.build();
+
+    // THEN they are the same
+    assert_eq!(a, b);
+}
+
+proptest! {
+    #[test]
+    fn test_builder_strategy(person in strategy::<Person>()) {
+        prop_assert!((18..65).contains(&person.age));
+        prop_assert_eq!(person.name, "person-1");
+    }
+
+    #[test]
+    fn test_builder_strategy_with(
+        person in strategy_with::<Person, _>(|ctx| { ctx.insert_named("age", 30u32); }),
+    ) {
+        prop_assert_eq!(person.age, 30);
+    }
+}
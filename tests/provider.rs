@@ -0,0 +1,116 @@
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+
+use ctxbuilder::{Context, ContextRead};
+
+#[derive(Debug, PartialEq)]
+struct User {
+    id: u64,
+    login: &'static str,
+}
+
+#[derive(Debug, PartialEq)]
+struct Session {
+    user_id: u64,
+}
+
+#[test]
+fn test_provide_named() {
+    // GIVEN a context with providers
+    let calls = Arc::new(AtomicU32::new(0));
+    let mut ctx = ctxbuilder::ctx();
+    let counter = calls.clone();
+    ctx.provide_named("admin", move |ctx| {
+        counter.fetch_add(1, Ordering::Relaxed);
+        User {
+            id: ctx.next_seq(),
+            login: "root",
+        }
+    });
+
+    // WHEN getting the object several times
+    let first = ctx.get_or_provide_named::<User>("admin").id;
+    let second = ctx.get_or_provide_named::<User>("admin").id;
+
+    // THEN it is created once
+    assert_eq!(first, second);
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+    assert_eq!(ctx.get_named::<User>("admin").unwrap().login, "root");
+}
+
+#[test]
+fn test_provide_named_lazy() {
+    // GIVEN a context with providers that depend on each other
+    let mut ctx = ctxbuilder::ctx();
+    ctx.provide_named("admin", |_| User {
+        id: 1,
+        login: "root",
+    });
+    ctx.provide_named("admin", |ctx| Session {
+        user_id: ctx.get_or_provide_named::<User>("admin").id,
+    });
+    ctx.provide_named("guest", |_| User {
+        id: 2,
+        login: "guest",
+    });
+
+    // WHEN getting one of the objects
+    let session = ctx.get_or_provide_named::<Session>("admin");
+
+    // THEN only the objects it needs are created
+    assert_eq!(session, &Session { user_id: 1 });
+    assert!(ctx.get_named::<User>("admin").is_some());
+    assert!(ctx.get_named::<User>("guest").is_none());
+}
+
+#[test]
+fn test_provide_named_sub() {
+    // GIVEN a provider in a parent context
+    let mut ctx = ctxbuilder::ctx();
+    ctx.provide_named("guest", |_| User {
+        id: 2,
+        login: "guest",
+    });
+
+    // WHEN getting the object from a sub-context
+    let mut sub = ctx.sub();
+    let login = sub.get_or_provide_named::<User>("guest").login;
+
+    // THEN it is only stored in the sub-context
+    assert_eq!(login, "guest");
+    drop(sub);
+    assert!(ctx.get_named::<User>("guest").is_none());
+}
+
+#[test]
+fn test_provide_named_existing() {
+    // GIVEN a context with an object and a provider for it
+    let mut ctx = ctxbuilder::ctx().with_named(
+        "admin",
+        User {
+            id: 7,
+            login: "admin",
+        },
+    );
+    ctx.provide_named("admin", |_| User {
+        id: 1,
+        login: "root",
+    });
+
+    // WHEN getting the object
+    // THEN the existing object is returned
+    assert_eq!(ctx.get_or_provide_named::<User>("admin").id, 7);
+}
+
+#[test]
+#[should_panic(expected = "no provider for `provider::User` named `admin`")]
+fn test_provide_named_missing() {
+    // GIVEN a context without provider
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN getting the object
+    // THEN it panics
+    ctx.get_or_provide_named::<User>("admin");
+}
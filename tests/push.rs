@@ -0,0 +1,73 @@
+use ctxbuilder::{Builder, Context, ContextRead};
+
+struct Person {
+    id: u64,
+}
+
+impl Builder for Person {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        let id = ctx.next_seq();
+        ctx.push(id);
+        Self { id }
+    }
+}
+
+#[test]
+fn test_push_get_all() {
+    // GIVEN a builder that records the objects it creates
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN building multiple objects
+    let people = ctx.build_vec::<Person>(3);
+
+    // THEN all of them are recorded, in order
+    let ids: Vec<_> = ctx.get_all::<u64>().iter().map(|id| **id).collect();
+    assert_eq!(ids, people.iter().map(|p| p.id).collect::<Vec<_>>());
+    assert_eq!(ids.len(), 3);
+}
+
+#[test]
+fn test_get_all_empty() {
+    // GIVEN an empty context
+    let ctx = ctxbuilder::ctx();
+
+    // WHEN getting all the values of a type
+    // THEN there are none
+    assert!(ctx.get_all::<u64>().is_empty());
+}
+
+#[test]
+fn test_push_separate_from_entry() {
+    // GIVEN a context with an entry of type `u32`
+    let mut ctx = ctxbuilder::ctx().with(1u32);
+
+    // WHEN pushing values of the same type
+    ctx.push(2u32);
+    ctx.push(3u32);
+
+    // THEN the entry is left untouched
+    assert_eq!(ctx.get::<u32>(), Some(&1));
+    assert_eq!(ctx.get_all::<u32>().len(), 2);
+}
+
+#[test]
+fn test_push_subcontext() {
+    // GIVEN a context with pushed values
+    let mut ctx = ctxbuilder::ctx();
+    ctx.push(1u32);
+
+    {
+        // WHEN pushing values in a sub-context
+        let mut sub = ctx.sub();
+        sub.push(2u32);
+        sub.push(3u32);
+
+        // THEN the sub-context sees the values of its parent and its own
+        let values: Vec<_> = sub.get_all::<u32>().iter().map(|v| **v).collect();
+        assert_eq!(values, [1, 2, 3]);
+    }
+
+    // THEN the parent only has its own values
+    let values: Vec<_> = ctx.get_all::<u32>().iter().map(|v| **v).collect();
+    assert_eq!(values, [1]);
+}
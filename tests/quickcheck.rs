@@ -0,0 +1,32 @@
+#![cfg(feature = "quickcheck")]
+
+use ctxbuilder::{
+    quickcheck::{arbitrary_sized, Arb},
+    Context, MainContext,
+};
+
+#[test]
+fn test_arb_deterministic() {
+    // GIVEN two contexts with the same seed
+    let mut a = MainContext::seeded(11);
+    let mut b = MainContext::seeded(11);
+
+    // WHEN building arbitrary values
+    let Arb(a): Arb<(u64, String)> = a.build();
+    let Arb(b): Arb<(u64, String)> = b.build();
+
+    // THEN they are the same
+    assert_eq!(a, b);
+}
+
+#[test]
+fn test_arbitrary_sized() {
+    // GIVEN a context
+    let mut ctx = MainContext::seeded(11);
+
+    // WHEN generating values with a small size
+    let values: Vec<Vec<u8>> = (0..20).map(|_| arbitrary_sized(&mut ctx, 3)).collect();
+
+    // THEN collections are no larger than the size
+    assert!(values.iter().all(|value| value.len() <= 3));
+}
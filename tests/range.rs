@@ -0,0 +1,55 @@
+use ctxbuilder::{Builder, Context, ContextRead, MainContext};
+
+struct Person {
+    age: u32,
+}
+
+impl Builder for Person {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            age: *ctx.entry_named("age").or_insert_in_range(18..65),
+        }
+    }
+}
+
+#[test]
+fn test_range_entry() {
+    // GIVEN a seeded context
+    let mut ctx = MainContext::seeded(3);
+
+    // WHEN building a person
+    let person: Person = ctx.build();
+
+    // THEN the age is in the range and shared
+    assert!((18..65).contains(&person.age));
+    assert_eq!(ctx.get_named("age"), Some(&person.age));
+}
+
+#[test]
+fn test_range_bounds() {
+    // GIVEN a seeded context
+    let mut ctx = MainContext::seeded(5);
+
+    // WHEN generating many values
+    for _ in 0..1_000 {
+        let i: i8 = ctx.pick_in_range(-3..4);
+        let f: f64 = ctx.pick_in_range(0.5..1.5);
+
+        // THEN they are in the range
+        assert!((-3..4).contains(&i));
+        assert!((0.5..1.5).contains(&f));
+    }
+}
+
+#[test]
+fn test_range_override() {
+    // GIVEN a context overriding the range
+    let mut ctx = MainContext::seeded(5)
+        .with(10u64..11)
+        .with_named("price", 100u64..101);
+
+    // WHEN generating values with default ranges
+    // THEN the ranges from the context are used
+    assert_eq!(ctx.pick_in_range(0u64..5), 10);
+    assert_eq!(ctx.pick_in_range_named("price", 0u64..5), 100);
+}
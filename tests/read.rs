@@ -0,0 +1,62 @@
+use ctxbuilder::{Builder, Context, ContextRead};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Region {
+    Eu,
+}
+
+/// Helper that only inspects the context
+fn region(ctx: &impl ContextRead) -> Option<Region> {
+    ctx.get().copied()
+}
+
+#[test]
+fn test_context_read() {
+    // GIVEN contexts with a region
+    let mut main = ctxbuilder::ctx().with(Region::Eu);
+    let sub = main.sub();
+
+    // WHEN inspecting them through a shared reference
+    // THEN the helper can read the entries
+    assert_eq!(region(&sub), Some(Region::Eu));
+    assert_eq!(region(&main.fork()), Some(Region::Eu));
+    assert_eq!(region(&main.freeze()), Some(Region::Eu));
+}
+
+struct Account {
+    region: Region,
+    owner: String,
+    id: u64,
+}
+
+impl Builder for Account {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        // Owned values don't keep the context borrowed across `next_seq`
+        let region = ctx.get_copied().unwrap();
+        let owner = ctx.get_cloned_named("owner").unwrap();
+        Self {
+            region,
+            owner,
+            id: ctx.next_seq(),
+        }
+    }
+}
+
+#[test]
+fn test_get_copied_cloned() {
+    // GIVEN a context with a region and an owner
+    let mut ctx = ctxbuilder::ctx()
+        .with(Region::Eu)
+        .with_named("owner", "alice".to_string())
+        .with_named("retries", 3u32);
+
+    // WHEN building an object that reads owned values
+    let account = ctx.build::<Account>();
+
+    // THEN the values are copied or cloned from the context
+    assert_eq!(account.region, Region::Eu);
+    assert_eq!(account.owner, "alice");
+    assert_eq!(account.id, 1);
+    assert_eq!(ctx.get_copied_named::<u32>("retries"), Some(3));
+    assert_eq!(ctx.get_cloned::<String>(), None);
+}
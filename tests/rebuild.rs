@@ -0,0 +1,68 @@
+use ctxbuilder::{Builder, Context, ContextWrite, Rebuild};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Now(u64);
+
+#[derive(Debug, PartialEq, Eq)]
+struct Session {
+    id: u64,
+    updated_at: u64,
+}
+
+impl Builder for Session {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            id: ctx.next_seq(),
+            updated_at: ctx.get::<Now>().map(|now| now.0).unwrap_or_default(),
+        }
+    }
+}
+
+impl Rebuild for Session {
+    fn rebuild<C: Context>(&mut self, ctx: &mut C) {
+        self.updated_at = ctx.get::<Now>().map(|now| now.0).unwrap_or_default();
+    }
+}
+
+#[test]
+fn test_rebuild() {
+    // GIVEN an object built with a context
+    let mut ctx = ctxbuilder::ctx().with(Now(10));
+    let mut session = ctx.build::<Session>();
+
+    // WHEN the context changes and the object is rebuilt
+    ctx.insert(Now(20));
+    ctx.rebuild(&mut session);
+
+    // THEN the object is updated in place
+    assert_eq!(
+        session,
+        Session {
+            id: 1,
+            updated_at: 20
+        }
+    );
+}
+
+#[test]
+fn test_rebuild_vec() {
+    // GIVEN multiple objects built with a context
+    let mut ctx = ctxbuilder::ctx().with(Now(10));
+    let mut sessions = vec![Some(ctx.build::<Session>()), None];
+
+    // WHEN the context changes and the objects are rebuilt
+    ctx.insert(Now(20));
+    ctx.rebuild(&mut sessions);
+
+    // THEN every object is updated
+    assert_eq!(
+        sessions,
+        vec![
+            Some(Session {
+                id: 1,
+                updated_at: 20
+            }),
+            None
+        ]
+    );
+}
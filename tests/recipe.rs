@@ -0,0 +1,201 @@
+use ctxbuilder::{
+    Builder, Context, ContextRead, ContextWrite, MainContext, Recipe, Replayer, Step,
+};
+
+#[derive(Debug, PartialEq)]
+struct User {
+    id: u64,
+    name: String,
+}
+
+impl Builder for User {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        let name = ctx
+            .entry_named::<String>("name")
+            .or_insert_with_rng(|rng| format!("user-{}", rng.below(1000)))
+            .clone();
+        Self {
+            id: ctx.next_seq(),
+            name,
+        }
+    }
+}
+
+#[test]
+fn test_recipe_record() {
+    // GIVEN a recording context
+    let mut ctx = MainContext::recording(7);
+    ctx.register_display::<u32>();
+
+    // WHEN building and inserting objects
+    ctx.insert(3u32);
+    ctx.insert(true);
+    ctx.build::<User>();
+    ctx.build_named::<uuid::Uuid, _>("alice");
+
+    // THEN only the outermost builds and the inserts are recorded
+    let recipe = ctx.recipe().unwrap();
+    assert_eq!(recipe.seed(), 7);
+    assert_eq!(
+        recipe.steps(),
+        [
+            Step::Insert {
+                type_name: "u32".to_string(),
+                name: None,
+                value: Some("3".to_string()),
+            },
+            Step::Insert {
+                type_name: "bool".to_string(),
+                name: None,
+                value: None,
+            },
+            Step::Build {
+                type_name: std::any::type_name::<User>().to_string(),
+                name: None,
+            },
+            Step::Build {
+                type_name: "uuid::Uuid".to_string(),
+                name: Some("alice".to_string()),
+            },
+        ]
+    );
+    assert!(MainContext::new().recipe().is_none());
+}
+
+#[test]
+fn test_recipe_replay() {
+    // GIVEN a recipe recorded from a context
+    let mut ctx = MainContext::recording(42);
+    ctx.register_display::<String>();
+    ctx.insert_named("team", "core\tplatform\n".to_string());
+    let first = ctx.build::<User>();
+    let second = ctx.build::<User>();
+    let text = ctx.recipe().unwrap().to_string();
+
+    // WHEN replaying it from its text form
+    let mut replayer = Replayer::new();
+    replayer.register_builder::<User>();
+    replayer.register_value::<String>();
+    let recipe: Recipe = text.parse().unwrap();
+    let mut replayed = replayer.replay(&recipe).unwrap();
+
+    // THEN the replayed context has the same entries and state
+    assert_eq!(recipe, ctx.recipe().unwrap());
+    assert_eq!(
+        replayed.get_named::<String>("team").map(String::as_str),
+        Some("core\tplatform\n")
+    );
+    assert_eq!(replayed.get_named::<String>("name"), Some(&first.name));
+    assert_eq!(second.id, 2);
+    assert_eq!(replayed.build::<User>(), ctx.build::<User>());
+}
+
+#[test]
+fn test_recipe_replay_interned_names() {
+    // GIVEN a recipe with a named entry
+    let mut ctx = MainContext::recording(42);
+    ctx.register_display::<String>();
+    ctx.insert_named("team", "core".to_string());
+    let recipe = ctx.recipe().unwrap();
+    let mut replayer = Replayer::new();
+    replayer.register_value::<String>();
+
+    // WHEN replaying it twice
+    let first = replayer.replay(&recipe).unwrap();
+    let second = replayer.replay(&recipe).unwrap();
+
+    // THEN both contexts share the same name
+    let name = |ctx: &MainContext| ctx.entry_keys()[0].1.unwrap();
+    assert!(core::ptr::eq(name(&first), name(&second)));
+}
+
+#[test]
+fn test_recipe_replay_errors() {
+    // GIVEN a recipe with a value that was not recorded
+    let mut ctx = MainContext::recording(1);
+    ctx.insert(5u8);
+    ctx.build::<User>();
+    let recipe = ctx.recipe().unwrap();
+
+    // WHEN replaying it
+    let mut replayer = Replayer::new();
+    let missing_builder = {
+        replayer.register_value::<u8>();
+        let recipe: Recipe = "seed\t1\nbuild\tUser\t\n".parse().unwrap();
+        replayer.replay(&recipe).err().unwrap()
+    };
+    let missing_value = replayer.replay(&recipe).err().unwrap();
+
+    // THEN it fails on the step that can't be replayed
+    assert_eq!(missing_value.step(), 0);
+    assert_eq!(
+        missing_value.to_string(),
+        "step 0: no value recorded for `u8`"
+    );
+    assert_eq!(missing_builder.type_name(), "User");
+    assert_eq!(
+        missing_builder.to_string(),
+        "step 0: no builder registered for `User`"
+    );
+}
+
+#[test]
+fn test_recipe_parse_error() {
+    // GIVEN invalid recipes
+    // WHEN parsing them
+    let no_seed = "build\tUser\t\n".parse::<Recipe>().unwrap_err();
+    let bad_step = "seed\t1\n\nremove\tUser\t\n".parse::<Recipe>().unwrap_err();
+
+    // THEN the error points at the invalid line
+    assert_eq!(no_seed.line(), 1);
+    assert_eq!(bad_step.line(), 3);
+    assert_eq!(
+        bad_step.to_string(),
+        "invalid recipe at line 3: unknown step"
+    );
+}
+
+#[test]
+fn test_recipe_format_header() {
+    // GIVEN a recorded recipe
+    let mut ctx = MainContext::recording(3);
+    ctx.build::<User>();
+    let recipe = ctx.recipe().unwrap();
+
+    // WHEN writing it as text
+    let text = recipe.to_string();
+
+    // THEN it starts with the format and crate versions
+    let header = format!(
+        "ctxbuilder\t{}\t{}\n",
+        Recipe::FORMAT_VERSION,
+        env!("CARGO_PKG_VERSION")
+    );
+    assert!(text.starts_with(&header));
+    assert_eq!(recipe.crate_version(), Some(env!("CARGO_PKG_VERSION")));
+    assert_eq!(text.parse::<Recipe>().unwrap(), recipe);
+}
+
+#[test]
+fn test_recipe_format_versions() {
+    // GIVEN recipes written with older and newer formats
+    let legacy = "seed\t1\nbuild\tUser\t\n";
+    let older = "ctxbuilder\t1\t0.1.0\nseed\t1\nbuild\tUser\t\n";
+    let newer = "ctxbuilder\t99\t9.0.0\nseed\t1\nbuild\tUser\t\n";
+
+    // WHEN parsing them
+    let legacy = legacy.parse::<Recipe>().unwrap();
+    let older = older.parse::<Recipe>().unwrap();
+    let newer = newer.parse::<Recipe>().unwrap_err();
+
+    // THEN the supported formats are read, and the newer one is rejected
+    assert_eq!(legacy.crate_version(), None);
+    assert_eq!(older.crate_version(), Some("0.1.0"));
+    assert_eq!(legacy.steps(), older.steps());
+    assert_eq!(newer.line(), 1);
+    assert_eq!(newer.unsupported_version(), Some(99));
+    assert_eq!(
+        newer.to_string(),
+        "invalid recipe at line 1: unsupported format version 99, this version of ctxbuilder reads up to version 1"
+    );
+}
@@ -0,0 +1,115 @@
+use ctxbuilder::{
+    BuildProblem, Builder, Context, ContextWrite, MainContext, MissingEntry, Requirement,
+};
+
+#[derive(Debug)]
+struct Address;
+
+impl Builder for Address {
+    fn build<C: Context>(_ctx: &mut C) -> Self {
+        Self
+    }
+
+    fn requires() -> &'static [Requirement] {
+        const REQUIRES: &[Requirement] = &[
+            Requirement::named::<String>("city"),
+            Requirement::of::<u16>(),
+        ];
+        REQUIRES
+    }
+}
+
+#[derive(Debug)]
+struct Customer {
+    _address: Address,
+}
+
+impl Builder for Customer {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            _address: ctx.build(),
+        }
+    }
+
+    fn requires() -> &'static [Requirement] {
+        const REQUIRES: &[Requirement] = &[Requirement::named::<String>("name")];
+        REQUIRES
+    }
+}
+
+#[derive(Debug)]
+struct Invoice;
+
+impl Builder for Invoice {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        ctx.build::<Customer>();
+        let total = ctx.get_named::<u64>("total").expect("missing total");
+        assert!(*total > 0);
+        Self
+    }
+}
+
+#[test]
+fn test_try_build() {
+    // GIVEN a context with all the required entries
+    let mut ctx = MainContext::new()
+        .with_named("name", "Alice".to_string())
+        .with_named("city", "Paris".to_string())
+        .with(75u16);
+
+    // WHEN trying to build an object
+    let result = ctx.try_build::<Customer>();
+
+    // THEN it succeeds
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_try_build_missing() {
+    // GIVEN an empty context
+    let mut ctx = MainContext::new();
+
+    // WHEN trying to build an object with nested requirements
+    let report = ctx.try_build::<Customer>().unwrap_err();
+
+    // THEN all the missing entries are reported
+    assert_eq!(report.type_name(), "report::Customer");
+    assert_eq!(
+        report.missing().cloned().collect::<Vec<_>>(),
+        [
+            MissingEntry::new::<String>(Some("name")),
+            MissingEntry::new::<String>(Some("city")),
+            MissingEntry::new::<u16>(None),
+        ]
+    );
+    assert_eq!(
+        report.to_string(),
+        "3 problems while building `report::Customer`:\n\
+         - `report::Customer` requires `name` of type `alloc::string::String`\n\
+         - `report::Address` requires `city` of type `alloc::string::String`\n\
+         - `report::Address` requires an entry of type `u16`"
+    );
+}
+
+#[test]
+fn test_try_build_panic() {
+    // GIVEN a context missing entries that a builder unwraps
+    let mut ctx = MainContext::new();
+    ctx.insert_named("city", "Paris".to_string());
+
+    // WHEN trying to build the object
+    let report = ctx.try_build::<Invoice>().unwrap_err();
+
+    // THEN the missing entries found before the panic are reported with it
+    assert_eq!(report.missing().count(), 2);
+    assert_eq!(
+        report.problems().last(),
+        Some(&BuildProblem::Panicked {
+            builder: "report::Invoice",
+            message: Some("missing total".to_string()),
+        })
+    );
+
+    // THEN the context can be used normally afterwards
+    ctx.build::<Customer>();
+}
@@ -0,0 +1,102 @@
+use ctxbuilder::{Builder, Context, ContextRead, ContextWrite, Requirement};
+
+struct Order {
+    customer: String,
+    quantity: u32,
+    express: bool,
+}
+
+impl Builder for Order {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            customer: ctx.get::<String>().unwrap().clone(),
+            quantity: *ctx.get_named("quantity").unwrap(),
+            express: ctx.get().copied().unwrap_or_default(),
+        }
+    }
+
+    fn requires() -> &'static [Requirement] {
+        const REQUIRES: &[Requirement] = &[
+            Requirement::of::<String>(),
+            Requirement::named::<u32>("quantity"),
+        ];
+        REQUIRES
+    }
+}
+
+struct Empty;
+
+impl Builder for Empty {
+    fn build<C: Context>(_ctx: &mut C) -> Self {
+        Self
+    }
+}
+
+#[test]
+fn test_validate_for_missing() {
+    // GIVEN an empty context
+    let ctx = ctxbuilder::ctx();
+
+    // WHEN validating it for a builder
+    let err = ctx.validate_for::<Order>().unwrap_err();
+
+    // THEN all the missing entries are reported
+    let entries: Vec<_> = err
+        .entries()
+        .iter()
+        .map(|entry| (entry.type_name(), entry.name()))
+        .collect();
+    assert_eq!(
+        entries,
+        vec![("alloc::string::String", None), ("u32", Some("quantity"))]
+    );
+    assert_eq!(
+        err.to_string(),
+        "2 missing context entries:\n\
+         - entry of type `alloc::string::String`\n\
+         - `quantity` of type `u32`"
+    );
+}
+
+#[test]
+fn test_validate_for_parent() {
+    // GIVEN a sub-context where one entry comes from the parent
+    let ctx = ctxbuilder::ctx().with("alice".to_string());
+    let mut sub = ctx.sub();
+    sub.insert_named("quantity", 3u32);
+
+    // WHEN validating it for a builder
+    let res = sub.validate_for::<Order>();
+
+    // THEN no entry is missing
+    assert_eq!(res, Ok(()));
+    let order = sub.build::<Order>();
+    assert_eq!(
+        (order.customer.as_str(), order.quantity, order.express),
+        ("alice", 3, false)
+    );
+}
+
+#[test]
+fn test_validate_for_removed() {
+    // GIVEN a fork where a shared entry was removed
+    let mut ctx = ctxbuilder::ctx().with("alice".to_string());
+    ctx.insert_named("quantity", 3u32);
+    let mut fork = ctx.fork();
+    fork.retain(|_, name| name != Some("quantity"));
+
+    // WHEN validating it for a builder
+    let err = fork.validate_for::<Order>().unwrap_err();
+
+    // THEN the removed entry is reported
+    assert_eq!(err.entries().len(), 1);
+    assert_eq!(err.entries()[0].name(), Some("quantity"));
+}
+
+#[test]
+fn test_validate_for_no_requirements() {
+    // GIVEN a builder without requirements
+    // WHEN validating an empty context for it
+    // THEN no entry is missing
+    assert_eq!(ctxbuilder::ctx().validate_for::<Empty>(), Ok(()));
+}
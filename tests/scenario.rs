@@ -0,0 +1,75 @@
+use ctxbuilder::{scenario::Scenario, Builder, Context, ContextRead};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Customer {
+    id: u64,
+}
+
+impl Builder for Customer {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            id: ctx.next_seq_named("customers"),
+        }
+    }
+}
+
+struct Item {
+    customer: u64,
+}
+
+struct OrderWithItems(usize);
+
+impl Scenario for OrderWithItems {
+    fn apply<C: Context>(&self, ctx: &mut C) {
+        let customer = ctx.get_or_build::<Customer>().id;
+        for name in ["item-1", "item-2", "item-3"].into_iter().take(self.0) {
+            ctx.insert_named(name, Item { customer });
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "order with items"
+    }
+}
+
+#[test]
+fn test_run_scenario() {
+    // GIVEN a context
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN running a scenario
+    let record = ctx.run_scenario(OrderWithItems(3));
+
+    // THEN the context is populated, and the record lists the created entries
+    assert_eq!(ctx.get_named::<Item>("item-2").unwrap().customer, 1);
+    assert_eq!(record.name(), "order with items");
+    assert_eq!(
+        record.created_named::<Item>(),
+        vec!["item-1", "item-2", "item-3"]
+    );
+    assert!(record
+        .created()
+        .contains(&(std::any::type_name::<Customer>(), None)));
+}
+
+#[test]
+fn test_run_scenario_existing() {
+    // GIVEN a context where a scenario already ran
+    let mut ctx = ctxbuilder::ctx();
+    ctx.run_scenario(OrderWithItems(1));
+
+    // WHEN running a bigger scenario in a sub-context
+    let mut sub = ctx.sub();
+    let record = sub.run_scenario(OrderWithItems(2));
+
+    // THEN only the new entries are recorded
+    assert_eq!(record.created_named::<Item>(), vec!["item-2"]);
+    assert_eq!(record.created().len(), 1);
+    assert_eq!(
+        record.to_string(),
+        format!(
+            "scenario `order with items`\n+ `item-2`: {}",
+            std::any::type_name::<Item>()
+        )
+    );
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "semver")]
+
+use ctxbuilder::{Context, ContextRead, VersionBump, VersionStrategy};
+use semver::Version;
+
+fn v(version: &str) -> Version {
+    version.parse().unwrap()
+}
+
+#[test]
+fn test_semver_default() {
+    // GIVEN a context without version strategy
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN building versions
+    // THEN they default to 1.0.0
+    assert_eq!(ctx.build::<Version>(), v("1.0.0"));
+    assert_eq!(ctx.build_named::<Version, _>("next"), v("1.0.0"));
+}
+
+#[test]
+fn test_semver_strategy() {
+    // GIVEN contexts with version strategies
+    let mut major = ctxbuilder::ctx().with(VersionStrategy::new(
+        v("2.3.4-beta.1+build.5"),
+        VersionBump::Major,
+    ));
+    let mut patch = ctxbuilder::ctx().with(VersionStrategy::default());
+
+    // WHEN building named versions
+    let majors: Vec<Version> = ["a", "b", "c"]
+        .into_iter()
+        .map(|name| major.build_named(name))
+        .collect();
+    let patches: Vec<Version> = ["a", "b"]
+        .into_iter()
+        .map(|name| patch.build_named(name))
+        .collect();
+
+    // THEN each version bumps the base once more
+    assert_eq!(majors, [v("2.3.4-beta.1+build.5"), v("3.0.0"), v("4.0.0")]);
+    assert_eq!(patches, [v("1.0.0"), v("1.0.1")]);
+}
+
+#[test]
+fn test_semver_stored() {
+    // GIVEN a context with a version strategy
+    let mut ctx = ctxbuilder::ctx().with(VersionStrategy::new(v("0.9.0"), VersionBump::Minor));
+
+    // WHEN building the same versions again
+    let current = ctx.build_named::<Version, _>("current");
+    let next = ctx.build_named::<Version, _>("next");
+    let again = ctx.build_named::<Version, _>("current");
+
+    // THEN they are stored in the context
+    assert_eq!((current.clone(), next), (v("0.9.0"), v("0.10.0")));
+    assert_eq!(again, current);
+    assert_eq!(ctx.get_named("current"), Some(&current));
+    assert_eq!(ctx.build::<Version>(), v("0.11.0"));
+    assert_eq!(ctx.build::<Version>(), v("0.11.0"));
+}
@@ -0,0 +1,124 @@
+use ctxbuilder::{Builder, Context, ContextRead, ContextWrite, MainContext, Shrink, ShrunkEntry};
+
+#[derive(Debug)]
+struct Order {
+    customer: String,
+    quantity: u32,
+    discount: Option<u8>,
+}
+
+impl Builder for Order {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            customer: ctx.get_cloned_named("customer").unwrap(),
+            quantity: *ctx.get_named("quantity").unwrap(),
+            discount: ctx.get_copied().unwrap(),
+        }
+    }
+}
+
+fn order_ctx() -> MainContext {
+    let mut ctx = MainContext::seeded(1);
+    ctx.register_shrink::<String>();
+    ctx.register_shrink::<u32>();
+    ctx.register_shrink::<Option<u8>>();
+    ctx.insert_named("customer", "Alice Smith".to_string());
+    ctx.insert_named("quantity", 120u32);
+    ctx.insert(Some(15u8));
+    ctx
+}
+
+#[test]
+fn test_shrink_passing() {
+    // GIVEN a context
+    let mut ctx = order_ctx();
+
+    // WHEN running a passing test
+    let result = ctx.shrink(|ctx| {
+        let order = ctx.build::<Order>();
+        assert!(order.quantity > 0);
+    });
+
+    // THEN nothing is shrunk
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_shrink_minimal() {
+    // GIVEN a context
+    let mut ctx = order_ctx();
+
+    // WHEN running a test failing for long names with a quantity
+    let report = ctx
+        .shrink(|ctx| {
+            let order = ctx.build::<Order>();
+            assert!(
+                order.customer.len() < 3 || order.quantity == 0,
+                "invalid order"
+            );
+        })
+        .unwrap_err();
+
+    // THEN the entries are shrunk to the simplest failing values
+    assert_eq!(report.message(), Some("invalid order"));
+    let order = report.into_context().build::<Order>();
+    assert_eq!(order.customer, "Ali");
+    assert_eq!(order.quantity, 1);
+    assert_eq!(order.discount, None);
+}
+
+#[test]
+fn test_shrink_report() {
+    // GIVEN a context with an entry that is not registered
+    let mut ctx = order_ctx();
+    ctx.insert_named("note", "fragile".to_string());
+    ctx.insert(7u64);
+
+    // WHEN running a failing test
+    let report = ctx
+        .shrink(|ctx| {
+            let quantity = *ctx.get_named::<u32>("quantity").unwrap();
+            assert!(quantity < 100);
+        })
+        .unwrap_err();
+
+    // THEN the shrunk entries are reported, leaving the others untouched
+    assert_eq!(
+        report.shrunk(),
+        [
+            ShrunkEntry {
+                type_name: "alloc::string::String",
+                name: Some("customer"),
+                value: "\"\"".to_string(),
+            },
+            ShrunkEntry {
+                type_name: "alloc::string::String",
+                name: Some("note"),
+                value: "\"\"".to_string(),
+            },
+            ShrunkEntry {
+                type_name: "core::option::Option<u8>",
+                name: None,
+                value: "None".to_string(),
+            },
+            ShrunkEntry {
+                type_name: "u32",
+                name: Some("quantity"),
+                value: "100".to_string(),
+            },
+        ]
+    );
+    assert_eq!(report.context().get::<u64>(), Some(&7));
+    assert_eq!(ctx.get_named::<u32>("quantity"), Some(&120));
+}
+
+#[test]
+fn test_shrink_values() {
+    assert_eq!(10u32.shrink(), [0, 5, 9]);
+    assert_eq!((-3i32).shrink(), [0, -1, -2]);
+    assert!(0u8.shrink().is_empty());
+    assert_eq!(true.shrink(), [false]);
+    assert_eq!("abcd".to_string().shrink(), ["", "ab", "abc"]);
+    assert_eq!(vec![1, 2].shrink(), [vec![], vec![1]]);
+    assert_eq!(Some(2u8).shrink(), [None, Some(0), Some(1)]);
+}
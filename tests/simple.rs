@@ -10,7 +10,7 @@ struct Person {
 impl Builder for Person {
     fn build<C: Context>(ctx: &mut C) -> Self {
         // Create a new ID for `Person`
-        let id = *ctx.entry_named("person").or_insert_with(|| Uuid::new_v4());
+        let id = *ctx.entry_named("person").or_insert_with(Uuid::new_v4);
 
         Self { id }
     }
@@ -24,7 +24,7 @@ struct Pet {
 
 impl Builder for Pet {
     fn build<C: Context>(ctx: &mut C) -> Self {
-        let owner = *ctx.entry_named("person").or_insert_with(|| Uuid::new_v4());
+        let owner = *ctx.entry_named("person").or_insert_with(Uuid::new_v4);
         Self {
             owner,
             pet_type: PetType::build(ctx),
@@ -69,3 +69,18 @@ fn test_builder_pet_type() {
     // THEN it uses the specified pet type
     assert_eq!(pet.pet_type, PetType::Cat);
 }
+
+#[test]
+fn test_builder_uuid_seeded() {
+    // GIVEN two contexts with the same seed
+    let mut a = ctxbuilder::MainContext::seeded(42);
+    let mut b = ctxbuilder::MainContext::seeded(42);
+
+    // WHEN building UUIDs
+    let a: Uuid = a.build();
+    let b: Uuid = b.build();
+
+    // THEN they are the same random UUIDs
+    assert_eq!(a, b);
+    assert_eq!(a.get_version(), Some(uuid::Version::Random));
+}
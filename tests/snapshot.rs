@@ -0,0 +1,62 @@
+#![cfg(feature = "insta")]
+
+use ctxbuilder::{Context, ContextWrite, MainContext};
+
+fn setup() -> MainContext {
+    let mut ctx = MainContext::seeded(42);
+    ctx.register_debug::<u32>();
+    ctx.register_debug::<String>();
+    ctx.insert_named("retries", 3u32);
+    ctx.insert_named("user", "alice".to_string());
+    ctx.insert_named("admin", "bob".to_string());
+    ctx.insert(10u32);
+    ctx.insert(true);
+    ctx
+}
+
+#[test]
+fn test_snapshot_text() {
+    // GIVEN a context with registered and unregistered entries
+    let ctx = setup();
+
+    // WHEN taking a snapshot
+    let snapshot = ctx.snapshot().to_string();
+
+    // THEN only the registered entries are included, in a stable order
+    assert_eq!(
+        snapshot,
+        "`admin`: alloc::string::String = \"bob\"\n\
+         `user`: alloc::string::String = \"alice\"\n\
+         u32 = 10\n\
+         `retries`: u32 = 3\n"
+    );
+}
+
+#[test]
+fn test_snapshot_fork() {
+    // GIVEN a fork that shadows and removes shared entries
+    let mut ctx = setup();
+    let mut fork = ctx.fork();
+    fork.insert(11u32);
+    fork.retain(|_, name| name != Some("user"));
+
+    // WHEN taking a snapshot
+    let snapshot = fork.snapshot().to_string();
+
+    // THEN only the visible entries are included
+    assert_eq!(
+        snapshot,
+        "`admin`: alloc::string::String = \"bob\"\nu32 = 11\n`retries`: u32 = 3\n"
+    );
+}
+
+#[test]
+fn test_snapshot_insta() {
+    // GIVEN a context after the setup of a test
+    let mut ctx = setup();
+    ctx.build::<uuid::Uuid>();
+
+    // WHEN comparing it with a snapshot
+    // THEN it matches the stored snapshot
+    ctxbuilder::assert_snapshot!(ctx, "after_setup");
+}
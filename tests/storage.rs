@@ -0,0 +1,309 @@
+use ctxbuilder::{Builder, Context, ContextRead, ContextWrite, EntryState, MainContext};
+
+#[derive(Debug, PartialEq, Eq)]
+struct Large([u64; 16]);
+
+#[test]
+fn test_storage_replace() {
+    // GIVEN a context with small, zero-sized and large values
+    let mut ctx = ctxbuilder::ctx()
+        .with(1u8)
+        .with(())
+        .with(Large([1; 16]))
+        .with_named("name", "alice".to_string());
+
+    // WHEN replacing the values
+    // THEN the previous values are returned
+    assert_eq!(ctx.insert(2u8), Some(1));
+    assert_eq!(ctx.insert(()), Some(()));
+    assert_eq!(ctx.insert(Large([2; 16])), Some(Large([1; 16])));
+    assert_eq!(
+        ctx.insert_named("name", "bob".to_string()),
+        Some("alice".to_string())
+    );
+
+    // THEN the new values are stored
+    assert_eq!(ctx.get(), Some(&2u8));
+    assert_eq!(ctx.get(), Some(&Large([2; 16])));
+    assert_eq!(ctx.get_named("name"), Some(&"bob".to_string()));
+}
+
+#[test]
+fn test_storage_and_modify() {
+    // GIVEN a context with a value
+    let mut ctx = ctxbuilder::ctx().with(41u32);
+
+    // WHEN modifying the value in place
+    let value = ctx.entry::<u32>().and_modify(|v| *v += 1).or_insert(0);
+
+    // THEN the modified value is returned
+    assert_eq!(*value, 42);
+}
+
+#[test]
+fn test_storage_or_insert_entry() {
+    // GIVEN a context with a shared value in a parent
+    let ctx = ctxbuilder::ctx().with(7u32);
+    let mut sub = ctx.sub();
+
+    // WHEN inserting values through entries
+    let (inherited, inherited_state) = sub.entry::<u32>().or_insert_entry(0);
+    let inherited = *inherited;
+    let (created, created_state) = sub.entry::<u64>().or_insert_with_entry(|| 3);
+    let created = *created;
+    let (_, existing_state) = sub.entry::<u64>().or_insert_entry(4);
+
+    // THEN the state tells whether the value was inserted
+    assert_eq!((inherited, inherited_state), (7, EntryState::Existing));
+    assert_eq!((created, created_state), (3, EntryState::Inserted));
+    assert!(created_state.is_inserted());
+    assert!(!existing_state.is_inserted());
+}
+
+#[test]
+fn test_storage_deferred_entry() {
+    // GIVEN a context with a name
+    let mut ctx = ctxbuilder::ctx().with_named("name", "alice".to_string());
+
+    // WHEN deriving defaults from other entries
+    let email = ctx
+        .deferred_entry_named::<String>("email")
+        .or_insert_with_ctx(|ctx| {
+            format!("{}@example.com", ctx.get_named::<String>("name").unwrap())
+        })
+        .clone();
+    let seq = *ctx
+        .deferred_entry::<u64>()
+        .or_insert_with_ctx(|ctx| ctx.next_seq() + 100);
+    let again = *ctx
+        .deferred_entry::<u64>()
+        .or_insert_with_ctx(|_| panic!("the entry exists"));
+
+    // THEN the defaults are inserted once
+    assert_eq!(email, "alice@example.com");
+    assert_eq!(ctx.get_named::<String>("email"), Some(&email));
+    assert_eq!((seq, again), (101, 101));
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Account {
+    id: u64,
+}
+
+impl Builder for Account {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            id: ctx.next_seq() + 100,
+        }
+    }
+}
+
+#[test]
+fn test_storage_deferred_entry_or_build() {
+    // GIVEN a sub-context with an inherited named entry
+    let ctx = ctxbuilder::ctx().with_named("billing", Account { id: 7 });
+    let mut sub = ctx.sub();
+
+    // WHEN building missing entries with their builder
+    let billing = sub.deferred_entry_named::<Account>("billing").or_build().id;
+    let shipping = sub
+        .deferred_entry_named::<Account>("shipping")
+        .or_build()
+        .id;
+    let again = sub
+        .deferred_entry_named::<Account>("shipping")
+        .or_build()
+        .id;
+    let default = sub.deferred_entry::<Account>().or_build().id;
+
+    // THEN existing entries are kept, and missing ones are built once
+    assert_eq!(billing, 7);
+    assert_eq!((shipping, again), (101, 101));
+    assert_eq!(default, 102);
+    drop(sub);
+    assert!(ctx.get_named::<Account>("shipping").is_none());
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Invoice {
+    account_id: u64,
+    reference: String,
+}
+
+impl Builder for Invoice {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        let reference = ctx.reserve_named::<String>("reference");
+        let account = ctx.build::<Account>();
+        let reference = reference
+            .or_insert_with(ctx, |ctx| format!("INV-{}-{}", account.id, ctx.next_seq()))
+            .clone();
+        Self {
+            account_id: account.id,
+            reference,
+        }
+    }
+}
+
+#[test]
+fn test_storage_reserve() {
+    // GIVEN a context
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN building an object that reserves an entry before nested builds
+    let invoice = ctx.build::<Invoice>();
+    let again = ctx.build::<Invoice>();
+
+    // THEN the entry is filled after the nested builds, and then reused
+    assert_eq!(invoice.account_id, 101);
+    assert_eq!(invoice.reference, "INV-101-2");
+    assert_eq!(again.reference, "INV-101-2");
+    assert!(!ctx.reserve_named::<String>("reference").is_vacant());
+}
+
+#[test]
+fn test_storage_reserve_filled() {
+    // GIVEN a reservation for a vacant entry
+    let mut ctx = ctxbuilder::ctx();
+    let reservation = ctx.reserve::<u32>();
+    assert!(reservation.is_vacant());
+    assert_eq!(reservation.get(&ctx), None);
+
+    // WHEN the entry is filled before the reservation
+    ctx.insert(3u32);
+
+    // THEN the existing value is kept
+    assert_eq!(reservation.or_insert(&mut ctx, 5), &3);
+}
+
+#[test]
+fn test_get_or_default() {
+    // GIVEN a context with a named value
+    let mut ctx = ctxbuilder::ctx().with_named("retries", 3u32);
+
+    // WHEN getting values with defaults
+    let retries = ctx.get_or_default_named::<u32>("retries");
+    let timeout = ctx.get_or_default_named::<u32>("timeout");
+    let tags = ctx.get_or_default::<Vec<String>>();
+
+    // THEN existing values are returned, and missing ones are inserted
+    assert_eq!((retries, timeout), (3, 0));
+    assert!(tags.is_empty());
+    assert_eq!(ctx.get_named::<u32>("timeout"), Some(&0));
+    assert!(ctx.get::<Vec<String>>().is_some());
+}
+
+#[test]
+fn test_insert_with() {
+    // GIVEN a context with a named value
+    let mut ctx = ctxbuilder::ctx().with_named("retries", 3u32);
+    let mut calls = 0;
+
+    // WHEN lazily inserting values
+    let existing = ctx.insert_named_with("retries", || {
+        calls += 1;
+        5u32
+    });
+    let missing = ctx.insert_named_with("timeout", || {
+        calls += 1;
+        30u32
+    });
+
+    // THEN only the missing value is computed and inserted
+    assert_eq!((existing, missing, calls), (false, true, 1));
+    assert_eq!(ctx.get_named::<u32>("retries"), Some(&3));
+    assert_eq!(ctx.get_named::<u32>("timeout"), Some(&30));
+}
+
+#[test]
+fn test_insert_if_absent() {
+    // GIVEN a context with a named value
+    let mut ctx = ctxbuilder::ctx().with_named("retries", 3u32);
+
+    // WHEN inserting values only if absent
+    let existing = ctx.insert_named_if_absent("retries", 5u32);
+    let missing = ctx.insert_if_absent(30u32);
+
+    // THEN only the missing value is inserted
+    assert_eq!((existing, missing), (false, true));
+    assert_eq!(ctx.get_named::<u32>("retries"), Some(&3));
+    assert_eq!(ctx.get::<u32>(), Some(&30));
+}
+
+#[test]
+fn test_insert_or_panic_absent() {
+    // GIVEN a context with a named value
+    let mut ctx = ctxbuilder::ctx().with_named("retries", 3u32);
+
+    // WHEN inserting values that don't exist yet
+    ctx.insert_or_panic(30u32);
+    ctx.insert_named_or_panic("timeout", 10u32);
+
+    // THEN they are inserted
+    assert_eq!(ctx.get::<u32>(), Some(&30));
+    assert_eq!(ctx.get_named::<u32>("timeout"), Some(&10));
+}
+
+#[test]
+#[should_panic(expected = "context already holds an entry `retries` of type `u32`")]
+fn test_insert_or_panic_inherited() {
+    // GIVEN a sub-context inheriting a named value
+    let ctx = ctxbuilder::ctx().with_named("retries", 3u32);
+    let mut sub = ctx.sub();
+
+    // WHEN inserting the value again
+    // THEN it panics
+    sub.insert_named_or_panic("retries", 5u32);
+}
+
+#[test]
+fn test_replace() {
+    // GIVEN a sub-context inheriting a named value, with its own value
+    let ctx = ctxbuilder::ctx().with_named("retries", 3u32);
+    let mut sub = ctx.sub().with(1u64);
+
+    // WHEN replacing the values
+    let inherited = sub.replace_named("retries", 5u32);
+    let local = sub.replace(2u64);
+
+    // THEN only the local value is returned, and both are replaced
+    assert_eq!((inherited, local), (None, Some(1)));
+    assert_eq!(sub.get_named::<u32>("retries"), Some(&5));
+    assert_eq!(sub.get::<u64>(), Some(&2));
+}
+
+#[test]
+#[should_panic(expected = "context has no entry of type `u32` to replace")]
+fn test_replace_missing() {
+    // GIVEN an empty context
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN replacing a value
+    // THEN it panics
+    ctx.replace(1u32);
+}
+
+#[test]
+fn test_replace_unread() {
+    // GIVEN a context with an unread value, and a one-shot value
+    let mut ctx = MainContext::new();
+    ctx.insert_named("retries", 3u32);
+    ctx.insert_once(1u64);
+
+    // WHEN replacing the values and inserting a new one
+    ctx.replace_named("retries", 5u32);
+    ctx.sub().replace(2u64);
+    ctx.insert_or_panic(true);
+    ctx.insert_named_or_panic("timeout", 10u32);
+
+    // THEN the replaced values are still unread, and the one-shot value can be read
+    assert_eq!(
+        ctx.unused_entries(),
+        [
+            ("bool", None),
+            ("u32", Some("retries")),
+            ("u32", Some("timeout")),
+            ("u64", None),
+        ]
+    );
+    assert_eq!(ctx.get::<u64>(), Some(&1));
+}
@@ -26,3 +26,66 @@ fn test_subcontext() {
     assert_eq!(subctx.get(), Some(&PetType::Dog));
     assert_eq!(ctx.get(), Some(&PetType::Cat));
 }
+
+#[test]
+fn test_subcontext_write_through() {
+    // GIVEN a write-through subcontext with a local override
+    let mut ctx = ctxbuilder::ctx().with(PetType::Cat);
+    let mut subctx = ctx.sub_mut().with(PetType::Dog);
+    subctx.set_write_through(true);
+
+    // WHEN inserting values in the subcontext
+    subctx.insert_named("owner", "alice");
+    assert_eq!(*subctx.entry_named("age").or_insert(3u32), 3);
+
+    // THEN the local override stays local
+    assert_eq!(subctx.get(), Some(&PetType::Dog));
+    assert_eq!(subctx.get_named("owner"), Some(&"alice"));
+
+    // THEN the new values are inserted in the parent context
+    assert_eq!(ctx.get(), Some(&PetType::Cat));
+    assert_eq!(ctx.get_named("owner"), Some(&"alice"));
+    assert_eq!(ctx.get_named("age"), Some(&3u32));
+}
+
+#[test]
+#[should_panic(expected = "write-through requires")]
+fn test_subcontext_write_through_shared() {
+    // GIVEN a subcontext holding its parent immutably
+    let ctx = ctxbuilder::ctx();
+    let mut subctx = ctx.sub();
+
+    // WHEN enabling write-through
+    // THEN it panics
+    subctx.set_write_through(true);
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Handle(u32);
+
+#[test]
+fn test_subcontext_detach() {
+    // GIVEN a forked context with cloneable and non-cloneable values
+    let mut base = ctxbuilder::ctx().with(Handle(1));
+    let mut ctx = base.fork().with(PetType::Cat).with(2u32);
+    ctx.register_clone::<PetType>();
+
+    // WHEN detaching a subcontext
+    let detached = std::thread::scope(|s| {
+        let mut subctx = ctx.sub();
+        subctx.insert_named("owner", "alice");
+        let detached = subctx.detach();
+
+        // THEN the detached context can be sent to another thread
+        s.spawn(move || detached).join().unwrap()
+    });
+
+    // THEN local, shared and cloneable values are available
+    assert_eq!(detached.get_named("owner"), Some(&"alice"));
+    assert_eq!(detached.get(), Some(&Handle(1)));
+    assert_eq!(detached.get(), Some(&PetType::Cat));
+
+    // THEN other inherited values are left out
+    assert_eq!(detached.get::<u32>(), None);
+    assert_eq!(ctx.get(), Some(&2u32));
+}
@@ -0,0 +1,75 @@
+use ctxbuilder::{Context, ContextRead, ContextWrite, MainContext};
+
+#[derive(Debug, PartialEq)]
+struct Currency(&'static str);
+
+fn setup() -> MainContext {
+    let mut ctx = MainContext::new();
+    ctx.insert_tagged(Currency("EUR"), &["billing"]);
+    ctx.insert_named_tagged("net_days", 30u32, &["billing", "invoices"]);
+    ctx.insert_named_tagged("region", "eu-west-1", &["infra"]);
+    ctx.insert_named("retries", 3u32);
+    ctx
+}
+
+#[test]
+fn test_iter_tagged() {
+    // GIVEN a context with tagged entries
+    let ctx = setup();
+
+    // WHEN iterating over the entries of a tag
+    let keys: Vec<_> = ctx.iter_tagged("billing").collect();
+
+    // THEN only the entries with that tag are returned, sorted
+    assert_eq!(
+        keys,
+        vec![("tags::Currency", None), ("u32", Some("net_days"))]
+    );
+    assert_eq!(ctx.iter_tagged("invoices").count(), 1);
+    assert_eq!(ctx.iter_tagged("unknown").count(), 0);
+}
+
+#[test]
+fn test_remove_tagged() {
+    // GIVEN a context with tagged entries
+    let mut ctx = setup();
+
+    // WHEN removing the entries of a tag
+    let count = ctx.remove_tagged("billing");
+
+    // THEN only those entries are removed, including from their other tags
+    assert_eq!(count, 2);
+    assert!(ctx.get::<Currency>().is_none());
+    assert!(ctx.get_named::<u32>("net_days").is_none());
+    assert_eq!(ctx.get_named::<u32>("retries"), Some(&3));
+    assert_eq!(ctx.iter_tagged("invoices").count(), 0);
+    assert_eq!(ctx.iter_tagged("infra").count(), 1);
+}
+
+#[test]
+fn test_remove_tagged_fork() {
+    // GIVEN a fork of a context with tagged entries
+    let mut ctx = setup();
+    let mut fork = ctx.fork();
+
+    // WHEN removing the entries of a tag from the fork
+    fork.remove_tagged("billing");
+
+    // THEN they are hidden from the fork, but stay in the original context
+    assert!(fork.get::<Currency>().is_none());
+    assert_eq!(fork.iter_tagged("billing").count(), 0);
+    assert_eq!(ctx.get(), Some(&Currency("EUR")));
+    assert_eq!(ctx.iter_tagged("billing").count(), 2);
+}
+
+#[test]
+fn test_tags_removed_entries() {
+    // GIVEN a context where a tagged entry was removed without its tag
+    let mut ctx = setup().with(true);
+    ctx.retain(|_, name| name != Some("region"));
+
+    // WHEN iterating over the entries of its tag
+    // THEN the removed entry is skipped
+    assert_eq!(ctx.iter_tagged("infra").count(), 0);
+    assert_eq!(ctx.remove_tagged("infra"), 0);
+}
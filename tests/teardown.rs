@@ -0,0 +1,133 @@
+use std::sync::{Arc, Mutex};
+
+use ctxbuilder::{Context, ContextRead, Teardown};
+
+type Log = Arc<Mutex<Vec<&'static str>>>;
+
+fn hook(log: &Log, step: &'static str) -> impl FnOnce() + Send + 'static {
+    let log = log.clone();
+    move || log.lock().unwrap().push(step)
+}
+
+struct Guard(&'static str, Log);
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        self.1.lock().unwrap().push(self.0);
+    }
+}
+
+#[test]
+fn test_teardown_order() {
+    // GIVEN a context with hooks and guards
+    let log = Log::default();
+    let mut ctx = ctxbuilder::ctx();
+    ctx.on_teardown(hook(&log, "drop schema"));
+    ctx.add_guard(Guard("stop server", log.clone()));
+    ctx.on_teardown(hook(&log, "flush logs"));
+
+    // WHEN dropping the context
+    assert!(log.lock().unwrap().is_empty());
+    drop(ctx);
+
+    // THEN they run in reverse registration order
+    assert_eq!(
+        *log.lock().unwrap(),
+        ["flush logs", "stop server", "drop schema"]
+    );
+}
+
+#[test]
+fn test_teardown_sub() {
+    // GIVEN a context with a hook, and a sub-context adding another one
+    let log = Log::default();
+    let mut ctx = ctxbuilder::ctx();
+    ctx.on_teardown(hook(&log, "parent"));
+    let mut sub = ctx.sub();
+    sub.on_teardown(hook(&log, "sub"));
+
+    // WHEN dropping the sub-context
+    drop(sub);
+
+    // THEN its hooks run with the ones of the parent
+    assert!(log.lock().unwrap().is_empty());
+    assert_eq!(ctx.get::<Teardown>().unwrap().len(), 2);
+    drop(ctx);
+    assert_eq!(*log.lock().unwrap(), ["sub", "parent"]);
+}
+
+#[test]
+fn test_teardown_run() {
+    // GIVEN a context with a hook
+    let log = Log::default();
+    let mut ctx = ctxbuilder::ctx();
+    ctx.on_teardown(hook(&log, "cleanup"));
+
+    // WHEN running the hooks before dropping the context
+    ctx.get::<Teardown>().unwrap().run();
+    drop(ctx);
+
+    // THEN the hook only runs once
+    assert_eq!(*log.lock().unwrap(), ["cleanup"]);
+}
+
+#[test]
+fn test_teardown_panic() {
+    // GIVEN a context with a hook
+    let log = Log::default();
+    let mut ctx = ctxbuilder::ctx();
+    ctx.on_teardown(hook(&log, "cleanup"));
+
+    // WHEN the test panics
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(move || {
+        let _ctx = ctx;
+        panic!("test failure");
+    }));
+
+    // THEN the hook still runs
+    assert!(result.is_err());
+    assert_eq!(*log.lock().unwrap(), ["cleanup"]);
+}
+
+#[tokio::test]
+async fn test_teardown_shutdown() {
+    // GIVEN a context with sync and async hooks
+    let log = Log::default();
+    let mut ctx = ctxbuilder::ctx();
+    ctx.on_teardown(hook(&log, "drop schema"));
+    let async_log = log.clone();
+    ctx.on_async_teardown(async move {
+        tokio::task::yield_now().await;
+        async_log.lock().unwrap().push("close pool");
+    });
+    ctx.on_teardown(hook(&log, "flush logs"));
+
+    // WHEN shutting down the context
+    ctx.shutdown().await;
+
+    // THEN all hooks run in reverse registration order, and only once
+    assert_eq!(
+        *log.lock().unwrap(),
+        ["flush logs", "close pool", "drop schema"]
+    );
+    drop(ctx);
+    assert_eq!(log.lock().unwrap().len(), 3);
+}
+
+#[test]
+fn test_teardown_async_dropped() {
+    // GIVEN a context with sync and async hooks
+    let log = Log::default();
+    let mut ctx = ctxbuilder::ctx();
+    ctx.on_teardown(hook(&log, "drop schema"));
+    let async_log = log.clone();
+    ctx.on_async_teardown(async move {
+        async_log.lock().unwrap().push("close pool");
+    });
+
+    // WHEN dropping the context without shutting it down
+    drop(ctx);
+
+    // THEN only the sync hooks run
+    assert_eq!(*log.lock().unwrap(), ["drop schema"]);
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "tempfile")]
+
+use std::sync::{Arc, Mutex};
+
+use ctxbuilder::Context;
+
+#[test]
+fn test_temp_dir() {
+    // GIVEN a context with a temporary directory
+    let mut ctx = ctxbuilder::ctx();
+    let uploads = ctx.temp_dir("uploads").to_path_buf();
+    std::fs::write(uploads.join("avatar.png"), b"png").unwrap();
+
+    // WHEN getting it again, or getting another one
+    let again = ctx.temp_dir("uploads").to_path_buf();
+    let cache = ctx.temp_dir("cache").to_path_buf();
+
+    // THEN the same directory is returned for the same name
+    assert_eq!(again, uploads);
+    assert_ne!(cache, uploads);
+    assert!(cache.is_dir());
+
+    // AND the directories are removed when the context is dropped
+    drop(ctx);
+    assert!(!uploads.exists());
+    assert!(!cache.exists());
+}
+
+#[test]
+fn test_temp_file() {
+    // GIVEN a context with a temporary file
+    let mut ctx = ctxbuilder::ctx();
+    let config = ctx.temp_file("config").to_path_buf();
+
+    // WHEN writing to it
+    std::fs::write(&config, "debug = true").unwrap();
+
+    // THEN it is available until the context is dropped
+    assert_eq!(
+        std::fs::read_to_string(ctx.temp_file("config")).unwrap(),
+        "debug = true"
+    );
+    drop(ctx);
+    assert!(!config.exists());
+}
+
+#[test]
+fn test_temp_dir_teardown_order() {
+    // GIVEN a hook registered after creating a temporary directory
+    let seen = Arc::new(Mutex::new(None));
+    let mut ctx = ctxbuilder::ctx();
+    let path = ctx.temp_dir("data").to_path_buf();
+    let hook_seen = seen.clone();
+    ctx.on_teardown(move || *hook_seen.lock().unwrap() = Some(path.exists()));
+
+    // WHEN dropping the context
+    drop(ctx);
+
+    // THEN the hook runs before the directory is removed
+    assert_eq!(*seen.lock().unwrap(), Some(true));
+}
@@ -0,0 +1,114 @@
+use ctxbuilder::{Builder, Context, ContextRead, ContextWrite, LayerKind};
+
+#[derive(Debug, PartialEq)]
+struct Account {
+    tenant: &'static str,
+    plan: &'static str,
+}
+
+impl Builder for Account {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            tenant: ctx.entry_named("tenant").or_insert("default"),
+            plan: ctx.entry_named("plan").or_insert("free"),
+        }
+    }
+}
+
+#[test]
+fn test_tenant_isolation() {
+    // GIVEN a context with two tenants
+    let mut ctx = ctxbuilder::ctx().with_named("plan", "team");
+    ctx.tenant("acme").insert_named("tenant", "acme");
+    ctx.tenant("globex").insert_named("tenant", "globex");
+
+    // WHEN building objects in each tenant
+    let acme = ctx.tenant("acme").build::<Account>();
+    let globex = ctx.tenant("globex").build::<Account>();
+
+    // THEN each tenant only sees its own entries and the global ones
+    assert_eq!(
+        acme,
+        Account {
+            tenant: "acme",
+            plan: "team"
+        }
+    );
+    assert_eq!(
+        globex,
+        Account {
+            tenant: "globex",
+            plan: "team"
+        }
+    );
+    assert_eq!(ctx.get_named::<&str>("tenant"), None);
+}
+
+#[test]
+fn test_tenant_shadows_global() {
+    // GIVEN a context with a global entry
+    let mut ctx = ctxbuilder::ctx().with_named("plan", "team");
+
+    // WHEN overriding it in a tenant
+    let mut acme = ctx.tenant("acme");
+    let old = acme.insert_named("plan", "enterprise");
+
+    // THEN the override is resolved from the tenant
+    assert_eq!(old, None);
+    assert_eq!(acme.get_named("plan"), Some(&"enterprise"));
+    let (_, layer) = acme.ancestors().resolve_named::<&str>("plan").unwrap();
+    assert_eq!(layer.kind(), LayerKind::Local);
+    drop(acme);
+
+    // THEN the global entry and the other tenants are untouched
+    assert_eq!(ctx.get_named("plan"), Some(&"team"));
+    assert_eq!(ctx.tenant("globex").get_named("plan"), Some(&"team"));
+}
+
+#[test]
+fn test_tenant_entry_keys() {
+    // GIVEN a tenant overriding a global entry
+    let mut ctx = ctxbuilder::ctx().with_named("plan", "team").with(true);
+    let mut acme = ctx.tenant("acme");
+    acme.insert_named("plan", "enterprise");
+
+    // WHEN listing its entries
+    let mut keys = acme.entry_keys();
+    keys.sort_unstable();
+
+    // THEN each entry is listed once
+    assert_eq!(keys, vec![("&str", Some("plan")), ("bool", None)]);
+}
+
+#[test]
+fn test_remove_tenant() {
+    // GIVEN a context with two tenants
+    let mut ctx = ctxbuilder::ctx();
+    ctx.tenant("acme").insert_named("tenant", "acme");
+    ctx.tenant("globex").insert_named("tenant", "globex");
+    ctx.tenant("initech");
+    assert_eq!(ctx.tenants(), vec!["acme", "globex"]);
+
+    // WHEN removing one of them
+    let count = ctx.remove_tenant("acme");
+
+    // THEN its entries are gone
+    assert_eq!(count, 1);
+    assert_eq!(ctx.tenants(), vec!["globex"]);
+    assert_eq!(ctx.tenant("acme").get_named::<&str>("tenant"), None);
+}
+
+#[test]
+fn test_tenant_fork() {
+    // GIVEN a context with a tenant
+    let mut ctx = ctxbuilder::ctx();
+    ctx.tenant("acme").insert_named("tenant", "acme");
+
+    // WHEN forking it
+    let mut fork = ctx.fork();
+
+    // THEN the fork starts without tenants
+    assert!(fork.tenants().is_empty());
+    assert_eq!(fork.tenant("acme").get_named::<&str>("tenant"), None);
+    assert_eq!(ctx.tenants(), vec!["acme"]);
+}
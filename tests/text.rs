@@ -0,0 +1,117 @@
+use std::collections::HashSet;
+
+use ctxbuilder::{
+    Context, ContextRead, Email, FullName, Locale, MainContext, PhoneNumber, Slug, Username,
+};
+
+#[test]
+fn test_text_unique() {
+    // GIVEN a context with a sequence
+    let mut ctx = ctxbuilder::ctx();
+    ctx.next_seq();
+
+    // WHEN building usernames in separate sub-contexts of the same parent
+    let usernames: HashSet<_> = (0..50).map(|_| ctx.sub_mut().build::<Username>()).collect();
+
+    // THEN they are unique
+    assert_eq!(usernames.len(), 50);
+    assert!(usernames.iter().all(|username| username
+        .as_str()
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')));
+}
+
+#[test]
+fn test_text_deterministic() {
+    // GIVEN two contexts with the same seed
+    let mut a = MainContext::seeded(3);
+    let mut b = MainContext::seeded(3);
+
+    // WHEN building strings
+    let a = (a.build::<Username>(), a.build::<Slug>());
+    let b = (b.build::<Username>(), b.build::<Slug>());
+
+    // THEN they are the same
+    assert_eq!(a, b);
+}
+
+#[test]
+fn test_text_named() {
+    // GIVEN a context
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN building named strings
+    let email = ctx.build_named::<Email, _>("Site Admin");
+    let slug = ctx.build_named::<Slug, _>("release notes");
+
+    // THEN they are derived from the name, and the email from the username
+    let username = ctx.get_named::<Username>("Site Admin").unwrap();
+    assert_eq!(username.as_str(), "site_admin_1");
+    assert_eq!(email.as_str(), "site_admin_1@example.com");
+    assert_eq!(slug.to_string(), "release-notes-2");
+}
+
+#[test]
+fn test_text_stored() {
+    // GIVEN a context with a username
+    let mut ctx = ctxbuilder::ctx().with(Username("alice".to_string()));
+
+    // WHEN building values several times
+    let email = ctx.build::<Email>();
+    let again = ctx.build::<Email>();
+    let slug = ctx.build::<Slug>();
+
+    // THEN the stored values are reused
+    assert_eq!(email, Email("alice@example.com".to_string()));
+    assert_eq!(again, email);
+    assert_eq!(ctx.build::<Slug>(), slug);
+    assert_eq!(String::from(slug.clone()), slug.as_str());
+}
+
+#[test]
+fn test_text_locale() {
+    // GIVEN contexts with different locales
+    let mut us = ctxbuilder::ctx();
+    let mut gb = ctxbuilder::ctx().with(Locale::EnGb);
+    let mut de = ctxbuilder::ctx().with(Locale::DeDe);
+
+    // WHEN building the same fixtures
+    // THEN they follow the locale
+    assert!(us
+        .build::<PhoneNumber>()
+        .as_str()
+        .starts_with("+1 202-555-01"));
+    assert!(us.build::<Email>().as_str().ends_with("@example.com"));
+    assert!(gb
+        .build::<PhoneNumber>()
+        .as_str()
+        .starts_with("+44 7700 900"));
+    assert!(gb
+        .build_named::<Email, _>("alice")
+        .as_str()
+        .ends_with("@example.co.uk"));
+    assert_eq!(
+        de.build::<PhoneNumber>().as_str().len(),
+        "+49 30 12345678".len()
+    );
+}
+
+#[test]
+fn test_text_full_name() {
+    // GIVEN a context with a locale
+    let mut ctx = MainContext::seeded(5).with(Locale::FrFr);
+
+    // WHEN building names
+    let alice = ctx.build_named::<FullName, _>("alice");
+    let again = ctx.build_named::<FullName, _>("alice");
+
+    // THEN they are stored, and have a first and last name
+    assert_eq!(alice, again);
+    assert_eq!(alice.as_str().split(' ').count(), 2);
+    assert_eq!(
+        MainContext::seeded(5)
+            .with(Locale::FrFr)
+            .build_named::<FullName, _>("bob"),
+        alice
+    );
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "tracing")]
+
+use std::sync::{Arc, Mutex};
+
+use ctxbuilder::{Builder, Context};
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Metadata, Subscriber,
+};
+
+/// Subscriber recording span names and event messages
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<String>>>);
+
+struct Message(Option<String>);
+
+impl Visit for Message {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.0 = Some(format!("{value:?}"));
+        }
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("span:{}", span.metadata().name()));
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut message = Message(None);
+        event.record(&mut message);
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("event:{}", message.0.unwrap_or_default()));
+    }
+
+    fn enter(&self, _: &span::Id) {}
+
+    fn exit(&self, _: &span::Id) {}
+}
+
+struct Person {
+    id: u64,
+}
+
+impl Builder for Person {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            id: *ctx.entry().or_insert(42u64),
+        }
+    }
+}
+
+#[test]
+fn test_tracing() {
+    // GIVEN a subscriber recording spans and events
+    let recorder = Recorder::default();
+
+    // WHEN building an object
+    let person =
+        tracing::subscriber::with_default(recorder.clone(), || ctxbuilder::ctx().build::<Person>());
+
+    // THEN the build and the entry lookup are recorded
+    assert_eq!(person.id, 42);
+    assert_eq!(
+        *recorder.0.lock().unwrap(),
+        vec!["span:build".to_string(), "event:entry".to_string()]
+    );
+}
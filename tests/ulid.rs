@@ -0,0 +1,40 @@
+#![cfg(feature = "ulid")]
+
+use std::time::Duration;
+
+use ctxbuilder::{Clock, Context, ContextRead, MainContext};
+use ulid::Ulid;
+
+#[test]
+fn test_builder_ulid_clock() {
+    // GIVEN seeded contexts with a clock
+    let mut a = MainContext::seeded(7).with(Clock::from_unix_millis(1_700_000_000_000));
+    let mut b = MainContext::seeded(7).with(Clock::from_unix_millis(1_700_000_000_000));
+
+    // WHEN building ULIDs
+    let ulid = a.build::<Ulid>();
+
+    // THEN they use the clock and are deterministic
+    assert_eq!(ulid.timestamp_ms(), 1_700_000_000_000);
+    assert_eq!(ulid, b.build::<Ulid>());
+    assert_eq!(ulid, a.build::<Ulid>());
+}
+
+#[test]
+fn test_named_builder_ulid() {
+    // GIVEN a context with a clock
+    let mut ctx = ctxbuilder::ctx().with(Clock::from_unix_millis(1_000));
+
+    // WHEN building named ULIDs after advancing the clock
+    let alice: Ulid = ctx.build_named("alice");
+    ctx.get::<Clock>()
+        .unwrap()
+        .advance(Duration::from_millis(5));
+    let bob: Ulid = ctx.build_named("bob");
+
+    // THEN they are sorted by time
+    assert_eq!(alice.timestamp_ms(), 1_000);
+    assert_eq!(bob.timestamp_ms(), 1_005);
+    assert!(alice < bob);
+    assert_eq!(ctx.build_named::<Ulid, _>("alice"), alice);
+}
@@ -0,0 +1,65 @@
+use ctxbuilder::{Builder, Context, ContextRead};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PetType {
+    Dog,
+}
+
+struct Pet {
+    pet_type: PetType,
+}
+
+impl Builder for Pet {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        Self {
+            pet_type: *ctx.entry().or_insert(PetType::Dog),
+        }
+    }
+}
+
+#[test]
+fn test_unused_entries() {
+    // GIVEN a context with entries that are not used by the builder
+    let mut ctx = ctxbuilder::ctx()
+        .with(PetType::Dog)
+        .with(42u32)
+        .with_named("owner", "alice");
+
+    // WHEN building an object
+    let pet = ctx.build::<Pet>();
+
+    // THEN only the unused entries are reported
+    assert_eq!(pet.pet_type, PetType::Dog);
+    assert_eq!(
+        ctx.unused_entries(),
+        vec![("&str", Some("owner")), ("u32", None)]
+    );
+}
+
+#[test]
+fn test_unused_entries_get() {
+    // GIVEN a forked context
+    let mut base = ctxbuilder::ctx().with(42u32).with(1u64);
+    let mut fork = base.fork().with(2u64);
+
+    // WHEN reading entries
+    assert_eq!(fork.get::<u32>(), Some(&42));
+    fork.get_or_build::<Pet>();
+
+    // THEN shadowed and read entries are not reported
+    assert_eq!(fork.unused_entries(), vec![("u64", None)]);
+
+    // THEN reads through the fork are shared with the base context
+    assert_eq!(base.unused_entries(), vec![("u64", None)]);
+}
+
+#[test]
+fn test_warn_unused() {
+    // GIVEN a context warning about unused entries
+    let ctx = ctxbuilder::ctx().warn_unused().with(42u32);
+
+    // WHEN dropping it
+    // THEN it doesn't panic
+    assert_eq!(ctx.unused_entries(), vec![("u32", None)]);
+    drop(ctx);
+}
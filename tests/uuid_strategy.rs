@@ -0,0 +1,56 @@
+#![cfg(feature = "uuid")]
+
+use ctxbuilder::{Clock, Context, MainContext, UuidStrategy};
+use uuid::{Uuid, Version};
+
+#[test]
+fn test_uuid_strategy_sequential() {
+    // GIVEN a context with a sequential strategy
+    let mut ctx = ctxbuilder::ctx().with(UuidStrategy::sequential(10));
+
+    // WHEN building named UUIDs
+    let ids: Vec<Uuid> = ctx.build_all_named(&["alice", "bob", "alice"]);
+
+    // THEN they count up, and existing UUIDs are reused
+    assert_eq!(ids[0], Uuid::from_u128(10));
+    assert_eq!(ids[1], Uuid::from_u128(11));
+    assert_eq!(ids[2], ids[0]);
+}
+
+#[test]
+fn test_uuid_strategy_prefixed() {
+    // GIVEN a context with a prefixed strategy
+    let mut ctx = ctxbuilder::ctx().with(UuidStrategy::prefixed(0x0bad_cafe));
+
+    // WHEN building UUIDs and expanding templates
+    let id = ctx.build::<Uuid>();
+    let text = ctx.format("user-{uuid}");
+
+    // THEN they share the prefix
+    assert_eq!(id.to_string(), "00000000-0bad-cafe-0000-000000000001");
+    assert_eq!(text, "user-00000000-0bad-cafe-0000-000000000002");
+}
+
+#[test]
+fn test_uuid_strategy_time_ordered() {
+    // GIVEN seeded contexts with a time-ordered strategy and a clock
+    let ctx = || {
+        MainContext::seeded(3)
+            .with(UuidStrategy::time_ordered())
+            .with(Clock::from_unix_millis(1_700_000_000_000))
+    };
+    let (mut a, mut b) = (ctx(), ctx());
+
+    // WHEN building UUIDs
+    let first: Uuid = a.build_named("first");
+    let second: Uuid = a.build_named("second");
+
+    // THEN they are deterministic version 7 UUIDs with the clock's timestamp
+    assert_eq!(first.get_version(), Some(Version::SortRand));
+    assert_eq!(
+        first.get_timestamp().map(|ts| ts.to_unix()),
+        Some((1_700_000_000, 0))
+    );
+    assert_ne!(first, second);
+    assert_eq!(b.build_named::<Uuid, _>("first"), first);
+}
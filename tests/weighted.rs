@@ -0,0 +1,71 @@
+use ctxbuilder::{Builder, Context, MainContext, Weighted};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum PetType {
+    Dog,
+    Cat,
+}
+
+impl Builder for PetType {
+    fn build<C: Context>(ctx: &mut C) -> Self {
+        *ctx.entry()
+            .or_insert_weighted(&[(PetType::Dog, 9), (PetType::Cat, 1)])
+    }
+}
+
+#[test]
+fn test_weighted_shared() {
+    // GIVEN a seeded context
+    let mut ctx = MainContext::seeded(42);
+
+    // WHEN building multiple pet types
+    let first: PetType = ctx.build();
+    let second: PetType = ctx.build();
+
+    // THEN the first value is shared
+    assert_eq!(first, second);
+}
+
+#[test]
+fn test_weighted_deterministic() {
+    // GIVEN two contexts with the same seed
+    let mut a = MainContext::seeded(7);
+    let mut b = MainContext::seeded(7);
+
+    // WHEN picking values from both
+    let a: Vec<PetType> = (0..32)
+        .map(|_| a.pick_weighted(&[(PetType::Dog, 1), (PetType::Cat, 1)]))
+        .collect();
+    let b: Vec<PetType> = (0..32)
+        .map(|_| b.pick_weighted(&[(PetType::Dog, 1), (PetType::Cat, 1)]))
+        .collect();
+
+    // THEN they generate the same values
+    assert_eq!(a, b);
+}
+
+#[test]
+fn test_weighted_distribution() {
+    // GIVEN a seeded context
+    let mut ctx = MainContext::seeded(1);
+
+    // WHEN picking many values
+    let dogs = (0..10_000)
+        .filter(|_| ctx.pick_weighted(&[(PetType::Dog, 9), (PetType::Cat, 1)]) == PetType::Dog)
+        .count();
+
+    // THEN they follow the weights
+    assert!((8_500..9_500).contains(&dogs), "{dogs} dogs");
+}
+
+#[test]
+fn test_weighted_override() {
+    // GIVEN a context overriding the weights
+    let mut ctx = MainContext::seeded(1).with(Weighted::new([(PetType::Cat, 1)]));
+
+    // WHEN picking values with default weights
+    let pet_type = ctx.pick_weighted(&[(PetType::Dog, 1)]);
+
+    // THEN it uses the weights from the context
+    assert_eq!(pet_type, PetType::Cat);
+}
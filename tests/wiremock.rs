@@ -0,0 +1,106 @@
+#![cfg(feature = "wiremock")]
+
+use std::io::{Read, Write};
+
+use ctxbuilder::{
+    wiremock::{start_mock_server, start_mock_server_named},
+    Context, ContextRead,
+};
+use url::Url;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+/// Send a GET request with the standard library, to avoid depending on an HTTP client
+fn get(url: &Url) -> String {
+    let addr = (url.host_str().unwrap(), url.port().unwrap());
+    let mut stream = std::net::TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        url.path()
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[tokio::test]
+async fn test_wiremock_url() {
+    // GIVEN a context with a mock server
+    let mut ctx = ctxbuilder::ctx();
+    let server = start_mock_server(&mut ctx).await;
+    Mock::given(method("GET"))
+        .and(path("/users/1"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("alice"))
+        .expect(1)
+        .mount(server)
+        .await;
+
+    // WHEN building a URL and calling it
+    let url = ctx.build::<Url>().join("users/1").unwrap();
+    let response = get(&url);
+
+    // THEN the request reaches the mock server
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+    assert!(response.ends_with("alice"), "{response}");
+}
+
+#[tokio::test]
+async fn test_wiremock_named() {
+    // GIVEN a context with two named mock servers
+    let mut ctx = ctxbuilder::ctx();
+    let users = start_mock_server_named(&mut ctx, "users").await.uri();
+    let orders = start_mock_server_named(&mut ctx, "orders").await.uri();
+
+    // WHEN building the named URLs
+    let users_url = ctx.build_named::<Url, _>("users");
+    let orders_url = ctx.build_named::<Url, _>("orders");
+
+    // THEN they point at their own server
+    assert_ne!(users, orders);
+    assert_eq!(users_url, Url::parse(&users).unwrap());
+    assert_eq!(orders_url, Url::parse(&orders).unwrap());
+    assert!(ctx.get::<MockServer>().is_none());
+}
+
+#[tokio::test]
+async fn test_wiremock_reuse() {
+    // GIVEN a context with a mock server
+    let mut ctx = ctxbuilder::ctx();
+    let first = *start_mock_server(&mut ctx).await.address();
+
+    // WHEN starting a mock server again
+    let second = *start_mock_server(&mut ctx).await.address();
+
+    // THEN the existing server is returned
+    assert_eq!(first, second);
+}
+
+#[test]
+fn test_wiremock_default_url() {
+    // GIVEN a context without a mock server
+    let mut ctx = ctxbuilder::ctx();
+
+    // WHEN building a URL
+    let url = ctx.build::<Url>();
+
+    // THEN it defaults to localhost
+    assert_eq!(url.as_str(), "http://localhost/");
+}
+
+#[cfg(feature = "axum")]
+#[tokio::test]
+async fn test_wiremock_axum_request() {
+    // GIVEN a context with a mock server
+    let mut ctx = ctxbuilder::ctx();
+    let uri = start_mock_server(&mut ctx).await.uri();
+
+    // WHEN building a request
+    let req = ctx.build::<axum::http::Request<axum::body::Body>>();
+
+    // THEN it points at the mock server
+    assert_eq!(req.uri().to_string(), format!("{uri}/"));
+}